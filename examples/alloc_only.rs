@@ -0,0 +1,24 @@
+//! Runs the parts of the crate that only need `alloc`: parsing, lowering, validation, layout and emitting.
+//!
+//! This is the build check for embedded targets, run it with
+//! `cargo run --no-default-features --example alloc_only`.
+
+use glyph::layout::Rect;
+
+fn main() -> Result<(), glyph::GlyphError> {
+    let src = "@language ratatui\n@Form main {\n    @Label hint { text = env\"GLYPH_HINT\" }\n    @Button ok { text = \"OK\" width = 6 }\n}\n";
+    let document = glyph::parser::parse(src)?;
+    let options = glyph::lower::LowerOptions { env_default: Some("Ready".to_string()), ..Default::default() };
+    let ui = glyph::lower::lower_with_options(&document, &options)?;
+    glyph::validate::ensure_valid(&ui)?;
+    let rects = glyph::layout::arrange(&ui, Rect::new(0, 0, 40, 10));
+    println!("ok is {} cells wide", rects["ok"].width);
+    print!("{}", glyph::to_str(&ui));
+
+    let include = glyph::parser::parse("@language ratatui\n@Form main { @include \"buttons.gl\" }")?;
+    // Without `std` there are no files to include, so this fails with a message saying so.
+    if let Err(error) = glyph::lower::lower(&include) {
+        println!("{error}");
+    }
+    Ok(())
+}
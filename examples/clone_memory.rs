@@ -0,0 +1,50 @@
+//! Measures how many bytes cloning a large tree allocates.
+//!
+//! Run with `cargo run --release --example clone_memory`, and again with
+//! `--features shared-names` to compare with shared names.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const PANELS: usize = 1_000;
+const BUTTONS: usize = 10;
+
+fn main() -> Result<(), glyph::GlyphError> {
+    let mut src = String::from("@language ratatui\n@Form main_form {\n");
+    for panel in 0..PANELS {
+        src.push_str(&format!("    @Panel settings_panel_{panel} {{\n"));
+        for button in 0..BUTTONS {
+            src.push_str(&format!("        @Button action_button_{panel}_{button} {{ text = \"Go\" }}\n"));
+        }
+        src.push_str("    }\n");
+    }
+    src.push_str("}\n");
+    let ui = glyph::from_str(&src)?;
+
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let copy = std::hint::black_box(ui.clone());
+    let allocated = ALLOCATED.load(Ordering::Relaxed) - before;
+
+    let elements = 1 + PANELS * (BUTTONS + 1);
+    println!("cloning {elements} elements allocated {allocated} bytes");
+    drop(copy);
+    Ok(())
+}
@@ -0,0 +1,31 @@
+//! Compares re-laying out an unchanged tree every frame with using a `LayoutCache`.
+//!
+//! Run with `cargo run --release --example layout_cache`.
+
+use std::time::Instant;
+
+use glyph::layout::{LayoutCache, Rect, arrange, layout_cached};
+
+const FRAMES: u32 = 10_000;
+
+fn main() -> Result<(), glyph::GlyphError> {
+    let ui = glyph::from_file("examples/form.gl")?;
+    let area = Rect::new(0, 0, 120, 40);
+
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        std::hint::black_box(arrange(&ui, area));
+    }
+    let uncached = start.elapsed();
+
+    let mut cache = LayoutCache::new();
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        std::hint::black_box(layout_cached(&ui, area, &mut cache));
+    }
+    let cached = start.elapsed();
+
+    println!("arrange:       {:?} per frame", uncached / FRAMES);
+    println!("layout_cached: {:?} per frame ({} solve)", cached / FRAMES, cache.solves());
+    Ok(())
+}
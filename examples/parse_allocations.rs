@@ -0,0 +1,63 @@
+//! Counts the allocations parsing a source with many large strings makes.
+//!
+//! Run with `cargo run --release --example parse_allocations`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use glyph::parser::{Engine, ParseOptions, parse_with_options};
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const LABELS: usize = 200;
+const LINES: usize = 40;
+
+fn main() -> Result<(), glyph::GlyphError> {
+    let line = "Lorem ipsum dolor sit amet, consectetur adipiscing elit.";
+    let text = vec![line; LINES].join(" ");
+    let mut src = String::from("@language ratatui\n@Form main {\n");
+    for label in 0..LABELS {
+        src.push_str(&format!("    @Label text_{label} {{ text = \"{text}\" }}\n"));
+        src.push_str(&format!("    @Label raw_{label} {{ text = d!\"{}\" }}\n", vec![line; LINES].join("\n")));
+    }
+    src.push_str("}\n");
+
+    for engine in [Engine::Chumsky, Engine::Handwritten] {
+        let options = ParseOptions { engine, ..ParseOptions::default() };
+        let (allocations, allocated) = (ALLOCATIONS.load(Ordering::Relaxed), ALLOCATED.load(Ordering::Relaxed));
+        let document = std::hint::black_box(parse_with_options(&src, &options)?);
+        println!(
+            "{engine:?}: parsing {} bytes with {} strings made {} allocations of {} bytes",
+            src.len(),
+            LABELS * 2,
+            ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+            ALLOCATED.load(Ordering::Relaxed) - allocated,
+        );
+        drop(document);
+    }
+    Ok(())
+}
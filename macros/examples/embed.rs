@@ -0,0 +1,11 @@
+use glyph::layout::Rect;
+use glyph::render::{RecordingBackend, render};
+
+fn main() {
+    let ui = glyph_macros::include_layout!("../examples/form.gl");
+    let mut backend = RecordingBackend::default();
+    render(ui, Rect::new(0, 0, 80, 24), &mut backend);
+    for call in &backend.calls {
+        println!("{call:?}");
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{LitStr, parse_macro_input};
+
+/// Embeds a `.gl` layout file and evaluates to a `&'static glyph::UI`.
+///
+/// The path is relative to the directory of the calling crate's `Cargo.toml`.
+/// The layout is parsed and lowered while compiling, so a broken layout is a
+/// compile error pointing at the macro call. The source is embedded with
+/// `include_str!` and turned into the `UI` once, on first use.
+///
+/// ```
+/// let ui = glyph_macros::include_layout!("../examples/form.gl");
+/// assert_eq!(ui.root.as_form().unwrap().name, "main_form");
+/// ```
+///
+/// ```compile_fail
+/// let ui = glyph_macros::include_layout!("examples/broken.gl");
+/// ```
+#[proc_macro]
+pub fn include_layout(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let full_path = Path::new(&manifest_dir).join(path.value());
+    let source = match std::fs::read_to_string(&full_path) {
+        Ok(source) => source,
+        Err(error) => return compile_error(&path, format!("can not read `{}`: {error}", full_path.display())),
+    };
+    if let Err(error) = glyph::from_str(&source) {
+        return compile_error(&path, format!("invalid layout `{}`: {error}", path.value()));
+    }
+    let full_path = full_path.to_string_lossy().into_owned();
+    quote! {{
+        static UI: ::std::sync::LazyLock<::glyph::UI> = ::std::sync::LazyLock::new(|| {
+            ::glyph::from_str(include_str!(#full_path)).expect("layout was checked at compile time")
+        });
+        &*UI
+    }}
+    .into()
+}
+
+fn compile_error(path: &LitStr, message: String) -> TokenStream {
+    syn::Error::new(path.span(), message).to_compile_error().into()
+}
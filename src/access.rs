@@ -0,0 +1,128 @@
+use crate::compat::*;
+use crate::types::*;
+use crate::validate::{Node, visit_at};
+
+/// Represents an element as accessibility tooling sees it, see [`accessibility_tree`].
+///
+/// # Fields
+///
+/// - `name` (`String`) - Name of the element.
+/// - `kind` (`&'static str`) - Kind of the element, as written after `@` in the source.
+/// - `role` (`Option<String>`) - Role given by the `role` property, `None` when it has none.
+/// - `text` (`String`) - Text the element shows: the title of containers, tabs and columns, the text of
+///   labels, buttons and menu entries, the label of checkboxes, radios and options, and the text
+///   of text inputs or their placeholder while they are empty. Empty for elements without text.
+/// - `depth` (`usize`) - How far below the root the element is, the root is at depth 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessNode {
+    pub name: String,
+    pub kind: &'static str,
+    pub role: Option<String>,
+    pub text: String,
+    pub depth: usize,
+}
+
+/// Collects the name, role and text of every element of the UI, parents before their children.
+///
+/// Radios, dropdown options, grid columns, tabs and menu entries are included. Dropdown options
+/// take no `role`, so theirs is always `None`.
+///
+/// ```
+/// use glyph::access::accessibility_tree;
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form login {
+///         title = "Sign in"
+///         @Panel links {
+///             role = navigation
+///             @Button home { text = "Home" role = "link" }
+///         }
+///         @TextInput user { placeholder = "User name" }
+///         @Label hint { text = "Press *Enter*" markup }
+///     }
+/// "#).unwrap();
+///
+/// let nodes: Vec<_> = accessibility_tree(&ui)
+///     .into_iter()
+///     .map(|node| (node.depth, node.kind, node.name, node.role, node.text))
+///     .collect();
+/// assert_eq!(nodes, [
+///     (0, "Form", "login".to_string(), None, "Sign in".to_string()),
+///     (1, "Panel", "links".to_string(), Some("navigation".to_string()), String::new()),
+///     (2, "Button", "home".to_string(), Some("link".to_string()), "Home".to_string()),
+///     (1, "TextInput", "user".to_string(), None, "User name".to_string()),
+///     (1, "Label", "hint".to_string(), None, "Press Enter".to_string()),
+/// ]);
+/// ```
+pub fn accessibility_tree(ui: &UI) -> Vec<AccessNode> {
+    let mut nodes = Vec::new();
+    visit_at(&ui.root, 0, &mut |node, depth| {
+        nodes.push(AccessNode {
+            name: node.name().to_string(),
+            kind: node.kind(),
+            role: role(node).map(str::to_string),
+            text: text(node),
+            depth,
+        });
+    });
+    nodes
+}
+
+fn role(node: Node<'_>) -> Option<&str> {
+    let role = match node {
+        Node::Element(element) => match element {
+            Element::Form(form) => &form.role,
+            Element::Panel(panel) => &panel.role,
+            Element::Label(label) => &label.role,
+            Element::TextInput(input) => &input.role,
+            Element::Button(button) => &button.role,
+            Element::Checkbox(checkbox) => &checkbox.role,
+            Element::CheckboxGroup(group) => &group.role,
+            Element::RadioGroup(group) => &group.role,
+            Element::Radio(radio) => &radio.role,
+            Element::Dropdown(dropdown) => &dropdown.role,
+            Element::Grid(grid) => &grid.role,
+            Element::Column(column) => &column.role,
+            Element::Modal(modal) => &modal.role,
+            Element::Tabs(tabs) => &tabs.role,
+            Element::Tab(tab) => &tab.role,
+            Element::MenuBar(menu_bar) => &menu_bar.role,
+            Element::MenuItem(item) => &item.role,
+            Element::StatusBar(status_bar) => &status_bar.role,
+            Element::Custom(custom) => &custom.role,
+        },
+        Node::Radio(radio) => &radio.role,
+        Node::Option(_) => return None,
+        Node::Column(column) => &column.role,
+        Node::Tab(tab) => &tab.role,
+        Node::MenuItem(item) => &item.role,
+    };
+    role.as_deref()
+}
+
+fn text(node: Node<'_>) -> String {
+    let text = match node {
+        Node::Element(element) => match element {
+            Element::Form(form) => &form.title,
+            Element::Panel(panel) => &panel.title,
+            Element::Label(label) => return label.plain_text(),
+            Element::TextInput(input) if input.default_text.is_empty() => &input.placeholder,
+            Element::TextInput(input) => &input.default_text,
+            Element::Button(button) => &button.text,
+            Element::Checkbox(checkbox) => &checkbox.label,
+            Element::Radio(radio) => &radio.label,
+            Element::Column(column) => &column.title,
+            Element::Modal(modal) => &modal.title,
+            Element::Tab(tab) => &tab.title,
+            Element::MenuItem(item) => &item.text,
+            _ => "",
+        },
+        Node::Radio(radio) => &radio.label,
+        Node::Option(option) => &option.label,
+        Node::Column(column) => &column.title,
+        Node::Tab(tab) => &tab.title,
+        Node::MenuItem(item) => &item.text,
+    };
+    text.to_string()
+}
@@ -0,0 +1,133 @@
+use crate::parser::Property;
+use crate::types::*;
+
+/// Rewrites the UI into a canonical form that does not depend on source order.
+///
+/// Children, radios, options, columns, tabs and menu items are sorted by name and `extra`
+/// properties by property name. Sorting is stable, so elements or properties
+/// sharing a name keep their relative order.
+///
+/// This changes the visual order of the UI and is meant for comparing trees,
+/// e.g. in snapshot tests. [`crate::emit::to_source`] keeps the source order.
+///
+/// ```
+/// use glyph::canonical::canonicalize;
+///
+/// let mut a = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Button ok { text = "OK" tooltip = "Save" shortcut = "s" }
+///         @Label hint { text = "Ready" }
+///     }
+/// "#).unwrap();
+/// let mut b = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Label hint { text = "Ready" }
+///         @Button ok { shortcut = "s" tooltip = "Save" text = "OK" }
+///     }
+/// "#).unwrap();
+/// assert_ne!(glyph::to_str(&a), glyph::to_str(&b));
+///
+/// canonicalize(&mut a);
+/// canonicalize(&mut b);
+/// assert_eq!(glyph::to_str(&a), glyph::to_str(&b));
+/// ```
+pub fn canonicalize(ui: &mut UI) {
+    canonicalize_element(&mut ui.root);
+}
+
+fn canonicalize_element(element: &mut Element) {
+    match element {
+        Element::Form(form) => {
+            canonicalize_children(&mut form.children);
+            sort_properties(&mut form.extra);
+        }
+        Element::Panel(panel) => {
+            canonicalize_children(&mut panel.children);
+            sort_properties(&mut panel.extra);
+        }
+        Element::Label(label) => sort_properties(&mut label.extra),
+        Element::TextInput(input) => sort_properties(&mut input.extra),
+        Element::Button(button) => sort_properties(&mut button.extra),
+        Element::Checkbox(checkbox) => sort_properties(&mut checkbox.extra),
+        Element::CheckboxGroup(group) => {
+            canonicalize_children(&mut group.children);
+            sort_properties(&mut group.extra);
+        }
+        Element::RadioGroup(group) => {
+            group.children.sort_by(|a, b| a.name.cmp(&b.name));
+            for radio in &mut group.children {
+                sort_properties(&mut radio.extra);
+            }
+            sort_properties(&mut group.extra);
+        }
+        Element::Radio(radio) => sort_properties(&mut radio.extra),
+        Element::Dropdown(dropdown) => {
+            // Sorting by group first keeps the options of a group together.
+            dropdown.options.sort_by(|a, b| (&a.group, &a.name).cmp(&(&b.group, &b.name)));
+            for option in &mut dropdown.options {
+                sort_properties(&mut option.extra);
+            }
+            sort_properties(&mut dropdown.extra);
+        }
+        Element::Grid(grid) => {
+            grid.columns.sort_by(|a, b| a.name.cmp(&b.name));
+            for column in &mut grid.columns {
+                sort_properties(&mut column.extra);
+            }
+            sort_properties(&mut grid.extra);
+        }
+        Element::Column(column) => sort_properties(&mut column.extra),
+        Element::Modal(modal) => {
+            canonicalize_children(&mut modal.children);
+            sort_properties(&mut modal.extra);
+        }
+        Element::Tabs(tabs) => {
+            tabs.children.sort_by(|a, b| a.name.cmp(&b.name));
+            for tab in &mut tabs.children {
+                canonicalize_tab(tab);
+            }
+            sort_properties(&mut tabs.extra);
+        }
+        Element::Tab(tab) => canonicalize_tab(tab),
+        Element::MenuBar(menu_bar) => {
+            canonicalize_menu_items(&mut menu_bar.items);
+            sort_properties(&mut menu_bar.extra);
+        }
+        Element::MenuItem(item) => canonicalize_menu_item(item),
+        Element::StatusBar(status_bar) => {
+            canonicalize_children(&mut status_bar.children);
+            sort_properties(&mut status_bar.extra);
+        }
+        Element::Custom(_) => {}
+    }
+}
+
+fn canonicalize_menu_items(items: &mut [MenuItem]) {
+    items.sort_by(|a, b| a.name.cmp(&b.name));
+    for item in items {
+        canonicalize_menu_item(item);
+    }
+}
+
+fn canonicalize_menu_item(item: &mut MenuItem) {
+    canonicalize_menu_items(&mut item.items);
+    sort_properties(&mut item.extra);
+}
+
+fn canonicalize_tab(tab: &mut Tab) {
+    canonicalize_children(&mut tab.children);
+    sort_properties(&mut tab.extra);
+}
+
+fn canonicalize_children(children: &mut [Element]) {
+    children.sort_by(|a, b| a.name().cmp(b.name()));
+    for child in children {
+        canonicalize_element(child);
+    }
+}
+
+fn sort_properties(properties: &mut [Property]) {
+    properties.sort_by(|a, b| a.name.cmp(&b.name));
+}
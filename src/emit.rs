@@ -0,0 +1,977 @@
+use crate::compat::*;
+use crate::{GlyphError, Result, lower};
+use crate::parser::{self, Property, Value};
+use crate::types::*;
+
+/// Converts the UI back into `.gl` source.
+///
+/// Properties are only written when they differ from their default value.
+/// Doc comments and `extra` properties captured while lowering are written back,
+/// so parsing the output yields the same tree again.
+///
+/// Generated trees check this: each seed builds a tree from the typed structs,
+/// whose source must parse and lower back into the same tree.
+///
+/// ```
+/// use glyph::prelude::*;
+/// use glyph::emit::to_source;
+///
+/// /// Small xorshift generator, so every seed builds the same tree on every run.
+/// struct Rng(u64);
+///
+/// impl Rng {
+///     fn next(&mut self, bound: u64) -> u64 {
+///         self.0 ^= self.0 << 13;
+///         self.0 ^= self.0 >> 7;
+///         self.0 ^= self.0 << 17;
+///         self.0 % bound
+///     }
+///
+///     fn pick<T: Clone>(&mut self, items: &[T]) -> T {
+///         items[self.next(items.len() as u64) as usize].clone()
+///     }
+/// }
+///
+/// const TEXTS: &[&str] = &["", "OK", "Save changes", "20", "line one\nline two", "first\n    indented\n    twice"];
+///
+/// /// Builds a tree of at most `budget` elements, nested no deeper than `depth`.
+/// fn element(rng: &mut Rng, budget: &mut u32, depth: u32, names: &mut u32) -> Element {
+///     *budget = budget.saturating_sub(1);
+///     *names += 1;
+///     let name: Name = format!("e{names}").as_str().into();
+///     let size = |rng: &mut Rng| match rng.next(4) {
+///         0 => SizeConstraint::Auto,
+///         1 => SizeConstraint::Fixed(rng.next(40) as u32),
+///         2 => SizeConstraint::Fraction(rng.next(4) as u32),
+///         _ => SizeConstraint::Percentage(rng.next(101) as u32),
+///     };
+///     let size_constraints = SizeConstraints { width: size(rng), height: size(rng), ..Default::default() };
+///     let margins = Margins { left: rng.next(3) as u32, top: rng.next(3) as u32, ..Default::default() };
+///     match rng.next(if depth == 0 { 4 } else { 5 }) {
+///         0 => Element::Label(Label {
+///             name,
+///             text: rng.pick(TEXTS).to_string(),
+///             word_wrap: rng.next(2) == 1,
+///             align: rng.pick(&[Alignment::Left, Alignment::Center, Alignment::Right]),
+///             size_constraints,
+///             margins,
+///             ..Default::default()
+///         }),
+///         1 => Element::Button(Button {
+///             name,
+///             text: rng.pick(TEXTS).to_string(),
+///             disabled: rng.next(2) == 1,
+///             variant: rng.pick(&[ButtonVariant::Default, ButtonVariant::Primary, ButtonVariant::Danger]),
+///             size_constraints,
+///             margins,
+///             ..Default::default()
+///         }),
+///         2 => Element::Checkbox(Checkbox {
+///             name,
+///             label: rng.pick(TEXTS).to_string(),
+///             checked: rng.next(2) == 1,
+///             size_constraints,
+///             ..Default::default()
+///         }),
+///         3 => Element::TextInput(TextInput {
+///             name,
+///             placeholder: rng.pick(TEXTS).to_string(),
+///             read_only: rng.next(2) == 1,
+///             size_constraints,
+///             ..Default::default()
+///         }),
+///         _ => {
+///             let count = rng.next(4);
+///             let mut children = Vec::new();
+///             for _ in 0..count {
+///                 if *budget == 0 {
+///                     break;
+///                 }
+///                 children.push(element(rng, budget, depth - 1, names));
+///             }
+///             Element::Panel(Panel {
+///                 name,
+///                 title: rng.pick(TEXTS).to_string(),
+///                 layout: rng.pick(&[Layout::LeftToRight, Layout::TopToBottom, Layout::FreeForm]),
+///                 border: rng.pick(&[BorderStyle::Plain, BorderStyle::Rounded, BorderStyle::None]),
+///                 collapsible: rng.next(2) == 1,
+///                 children,
+///                 size_constraints,
+///                 margins,
+///                 ..Default::default()
+///             })
+///         }
+///     }
+/// }
+///
+/// fn ui(seed: u64, budget: u32) -> UI {
+///     let mut rng = Rng(seed * 2 + 1);
+///     let (mut budget, mut names) = (budget, 0);
+///     let mut children = Vec::new();
+///     while budget > 0 {
+///         children.push(element(&mut rng, &mut budget, 3, &mut names));
+///     }
+///     let form = Form { name: "main".into(), title: rng.pick(TEXTS).to_string(), children, ..Default::default() };
+///     UI { language: Language::Ratatui, root: Element::Form(form), assertions: Vec::new() }
+/// }
+///
+/// /// Reading back the source gives the same tree.
+/// fn round_trips(ui: &UI) -> bool {
+///     let again = lower(&parse(&to_source(ui)).unwrap()).unwrap();
+///     again == *ui
+/// }
+///
+/// for seed in 0..200 {
+///     if !round_trips(&ui(seed, 12)) {
+///         // Report the smallest tree of this seed that fails, which is the easiest to debug.
+///         let smallest = (1..=12).map(|budget| ui(seed, budget)).find(|ui| !round_trips(ui)).unwrap();
+///         panic!("seed {seed} does not round-trip:\n{}", to_source(&smallest));
+///     }
+/// }
+/// ```
+pub fn to_source(ui: &UI) -> String {
+    to_source_with_options(ui, &EmitOptions::default())
+}
+
+/// Represents settings for [`to_source_with_options`].
+///
+/// # Fields
+///
+/// - `sort_children` (`bool`) - Writes the children of forms, panels, modals, tabs and checkbox groups sorted
+///   by name instead of in tree order, false by default. Radios, dropdown options, grid columns,
+///   tabs and menu items keep their order, which is the order they are shown in.
+/// - `sort_properties` (`bool`) - Writes the properties of each element sorted by name
+///   instead of in the order [`to_source`] uses, false by default.
+///
+/// Sorting gives the same output for trees that only differ in order,
+/// which keeps diffs small when the order of the source does not matter.
+#[derive(Debug, Clone, Default)]
+pub struct EmitOptions {
+    pub sort_children: bool,
+    pub sort_properties: bool,
+}
+
+/// Converts a UI back to `.gl` source like [`to_source`], with the given settings.
+///
+/// ```
+/// use glyph::emit::{EmitOptions, to_source_with_options};
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Label title { width = 10 text = "Hi" }
+///         @Button cancel { text = "Cancel" }
+///         @Button accept { text = "OK" }
+///     }
+/// "#).unwrap();
+/// let options = EmitOptions { sort_children: true, sort_properties: true };
+/// let sorted = to_source_with_options(&ui, &options);
+/// assert_eq!(sorted, "@language ratatui
+/// @Form main {
+///     @Button accept {
+///         text = \"OK\"
+///     }
+///     @Button cancel {
+///         text = \"Cancel\"
+///     }
+///     @Label title {
+///         text = \"Hi\"
+///         width = 10
+///     }
+/// }
+/// ");
+///
+/// // The sorted source reads back, and sorting it again changes nothing.
+/// let again = glyph::from_str(&sorted).unwrap();
+/// assert_eq!(to_source_with_options(&again, &options), sorted);
+///
+/// // Without options the tree order is kept.
+/// let unsorted = glyph::emit::to_source(&ui);
+/// assert!(unsorted.find("@Label title").unwrap() < unsorted.find("@Button accept").unwrap());
+/// ```
+pub fn to_source_with_options(ui: &UI, options: &EmitOptions) -> String {
+    let mut writer = Writer {
+        options: options.clone(),
+        ..Writer::default()
+    };
+    match &ui.language {
+        Language::Ratatui => writer.line("@language ratatui"),
+        Language::AnyOther { name, url } if url.is_empty() => writer.line(&format!("@language {name}")),
+        Language::AnyOther { name, url } => writer.line(&format!("@language {name}(\"{url}\")")),
+    }
+    writer.element(&ui.root);
+    for assertion in &ui.assertions {
+        writer.line(&assertion_line(assertion));
+    }
+    writer.out
+}
+
+fn assertion_line(assertion: &Assertion) -> String {
+    let mut line = format!("@assert element={}", string(&assertion.element));
+    let bounds = [
+        ("min-width", assertion.min_width),
+        ("max-width", assertion.max_width),
+        ("min-height", assertion.min_height),
+        ("max-height", assertion.max_height),
+    ];
+    for (name, bound) in bounds {
+        if let Some(bound) = bound {
+            line.push_str(&format!(" {name}={bound}"));
+        }
+    }
+    line
+}
+
+/// Reformats `.gl` source the way [`to_source`] writes it.
+///
+/// Doc comments and unknown properties are kept. Plain `//` comments are not part
+/// of the document and `@if` blocks, `@include` lines, `@alias` lines and `env"NAME"` values are resolved
+/// while lowering, so sources using any of them are rejected instead of losing them.
+///
+/// ```
+/// let messy = "@language ratatui
+/// @Form main {   title=\"Login\"
+///   /// Submits the form.
+///       @Button ok ( text = \"OK\"  colour = red )
+/// }";
+/// let formatted = glyph::emit::format_source(messy).unwrap();
+/// assert_eq!(formatted, "@language ratatui
+/// @Form main {
+///     title = \"Login\"
+///     /// Submits the form.
+///     @Button ok {
+///         text = \"OK\"
+///         colour = red
+///     }
+/// }
+/// ");
+/// assert_eq!(glyph::emit::format_source(&formatted).unwrap(), formatted);
+/// assert!(glyph::emit::format_source("@language ratatui\n// note\n@Form main { }").is_err());
+/// ```
+pub fn format_source(src: &str) -> Result<String> {
+    if has_line_comment(src) {
+        return refuse("`//` comments, which formatting would drop; only `///` doc comments are kept");
+    }
+    let document = parser::parse(src)?;
+    if has_conditional(&document.root) {
+        return refuse("`@if` blocks, which formatting would resolve");
+    }
+    if lower::has_include(&document.root) {
+        return refuse("`@include` lines, which formatting would replace by the included files");
+    }
+    if !document.aliases.is_empty() {
+        return refuse("`@alias` lines, which formatting would replace by the kinds they stand for");
+    }
+    if lower::has_env(&document.root) {
+        return refuse("`env\"...\"` values, which formatting would replace by their values");
+    }
+    Ok(to_source(&lower::lower(&document)?))
+}
+
+/// Fails formatting because the source has something formatting would lose, as `reason` says.
+fn refuse(reason: &str) -> Result<String> {
+    Err(GlyphError::Format(format!("the source has {reason}")))
+}
+
+/// Returns whether the source has a `//` comment that is not a `///` doc comment.
+fn has_line_comment(src: &str) -> bool {
+    let mut in_string = false;
+    let mut chars = src.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => in_string = !in_string,
+            '/' if !in_string && chars.peek() == Some(&'/') => {
+                chars.next();
+                if chars.peek() != Some(&'/') {
+                    return true;
+                }
+                // Skip the rest of the doc comment, it may contain quotes.
+                chars.by_ref().take_while(|c| *c != '\n').for_each(drop);
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+fn has_conditional(element: &parser::Element) -> bool {
+    element.kind == "if" || element.all_children().any(has_conditional)
+}
+
+#[derive(Default)]
+struct Writer {
+    out: String,
+    indent: usize,
+    options: EmitOptions,
+    /// Property names and lines of the open element, held back to be sorted by name.
+    pending: Vec<(String, String)>,
+}
+
+impl Writer {
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.indent {
+            self.out.push_str("    ");
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn open(&mut self, kind: &str, name: &str, doc: &Option<String>) {
+        self.flush_properties();
+        if let Some(doc) = doc {
+            for line in doc.lines() {
+                if line.is_empty() {
+                    self.line("///");
+                } else {
+                    self.line(&format!("/// {line}"));
+                }
+            }
+        }
+        match name {
+            "" => self.line(&format!("@{kind} {{")),
+            name => self.line(&format!("@{kind} {name} {{")),
+        }
+        self.indent += 1;
+    }
+
+    /// Writes event bindings sorted by event name, keeping the output stable.
+    fn role(&mut self, role: &Option<String>) {
+        if let Some(role) = role {
+            self.property("role", &string(role));
+        }
+    }
+
+    fn events(&mut self, events: &HashMap<String, String>) {
+        let mut events: Vec<_> = events.iter().collect();
+        events.sort();
+        for (event, handler) in events {
+            self.property(&format!("on-{event}"), &string(handler));
+        }
+    }
+
+    fn extra(&mut self, extra: &[Property]) {
+        for property in extra {
+            self.property(&property.name, &value(&property.value));
+        }
+    }
+
+    fn close(&mut self) {
+        self.flush_properties();
+        self.indent -= 1;
+        self.line("}");
+    }
+
+    fn property(&mut self, name: &str, value: &str) {
+        let line = format!("{name} = {value}");
+        if self.options.sort_properties {
+            self.pending.push((name.to_string(), line));
+        } else {
+            self.line(&line);
+        }
+    }
+
+    /// Writes the held back properties of the open element, sorted by name.
+    fn flush_properties(&mut self) {
+        let mut pending = core::mem::take(&mut self.pending);
+        pending.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (_, line) in pending {
+            self.line(&line);
+        }
+    }
+
+    fn string(&mut self, name: &str, text: &str) {
+        if !text.is_empty() {
+            self.property(name, &string(text));
+        }
+    }
+
+    fn bool(&mut self, name: &str, flag: bool) {
+        if flag {
+            self.property(name, "true");
+        }
+    }
+
+    fn size(&mut self, name: &str, size: &SizeConstraint) {
+        if *size != SizeConstraint::Auto {
+            self.property(name, &self::size(size));
+        }
+    }
+
+    fn size_constraints(&mut self, size: &SizeConstraints) {
+        self.size("width", &size.width);
+        self.size("height", &size.height);
+        self.size("left", &size.left);
+        self.size("top", &size.top);
+    }
+
+    fn margins(&mut self, margins: &Margins) {
+        for (name, cells) in [
+            ("margins-left", margins.left),
+            ("margins-right", margins.right),
+            ("margins-top", margins.top),
+            ("margins-bottom", margins.bottom),
+        ] {
+            if cells != 0 {
+                self.property(name, &cells.to_string());
+            }
+        }
+    }
+
+    fn layout(&mut self, layout: &Layout) {
+        let name = match layout {
+            Layout::LeftToRight => "left-to-right",
+            Layout::RightToLeft => "right-to-left",
+            Layout::TopToBottom => return,
+            Layout::BottomToTop => "bottom-to-top",
+            Layout::FreeForm => "free-form",
+        };
+        self.property("layout", name);
+    }
+
+    fn children(&mut self, children: &[Element]) {
+        let mut children: Vec<&Element> = children.iter().collect();
+        if self.options.sort_children {
+            children.sort_by_key(|child| child.name());
+        }
+        for child in children {
+            self.element(child);
+        }
+    }
+
+    fn element(&mut self, element: &Element) {
+        match element {
+            Element::Form(form) => {
+                self.open("Form", &form.name, &form.doc);
+                self.string("title", &form.title);
+                self.layout(&form.layout);
+                if !form.focus_order.is_empty() {
+                    self.property("focus-order", &format!("[{}]", form.focus_order.join(", ")));
+                }
+                self.role(&form.role);
+                self.events(&form.events);
+                self.extra(&form.extra);
+                self.children(&form.children);
+                self.close();
+            }
+            Element::Panel(panel) => {
+                self.open("Panel", &panel.name, &panel.doc);
+                self.string("title", &panel.title);
+                match panel.title_align {
+                    Alignment::Left => {}
+                    Alignment::Center => self.property("title-align", "center"),
+                    Alignment::Right => self.property("title-align", "right"),
+                }
+                self.bool("title-brackets", panel.title_brackets);
+                self.layout(&panel.layout);
+                self.size_constraints(&panel.size_constraints);
+                self.margins(&panel.margins);
+                self.bool("collapsible", panel.collapsible);
+                self.bool("collapsed", panel.collapsed);
+                match panel.border {
+                    BorderStyle::Plain => {}
+                    BorderStyle::Rounded => self.property("border", "rounded"),
+                    BorderStyle::Double => self.property("border", "double"),
+                    BorderStyle::Thick => self.property("border", "thick"),
+                    BorderStyle::None => self.property("border", "none"),
+                }
+                match panel.scroll {
+                    ScrollMode::None => {}
+                    ScrollMode::Vertical => self.property("scroll", "vertical"),
+                    ScrollMode::Horizontal => self.property("scroll", "horizontal"),
+                    ScrollMode::Both => self.property("scroll", "both"),
+                }
+                self.role(&panel.role);
+                self.events(&panel.events);
+                self.extra(&panel.extra);
+                self.children(&panel.children);
+                self.close();
+            }
+            Element::Label(label) => {
+                self.open("Label", &label.name, &label.doc);
+                self.string("text", &label.text);
+                self.bool("word-wrap", label.word_wrap);
+                self.bool("markup", label.markup);
+                match label.align {
+                    Alignment::Left => {}
+                    Alignment::Center => self.property("align", "center"),
+                    Alignment::Right => self.property("align", "right"),
+                }
+                self.size_constraints(&label.size_constraints);
+                self.margins(&label.margins);
+                self.role(&label.role);
+                self.events(&label.events);
+                self.extra(&label.extra);
+                self.close();
+            }
+            Element::TextInput(input) => {
+                self.open("TextInput", &input.name, &input.doc);
+                self.string("placeholder", &input.placeholder);
+                self.string("text", &input.default_text);
+                self.bool("read-only", input.read_only);
+                self.bool("disabled", input.disabled);
+                self.size_constraints(&input.size_constraints);
+                self.margins(&input.margins);
+                self.role(&input.role);
+                self.events(&input.events);
+                self.extra(&input.extra);
+                self.close();
+            }
+            Element::Button(button) => {
+                self.open("Button", &button.name, &button.doc);
+                self.string("text", &button.text);
+                self.size_constraints(&button.size_constraints);
+                self.margins(&button.margins);
+                self.bool("disabled", button.disabled);
+                match button.variant {
+                    ButtonVariant::Default => {}
+                    ButtonVariant::Primary => self.property("variant", "primary"),
+                    ButtonVariant::Secondary => self.property("variant", "secondary"),
+                    ButtonVariant::Danger => self.property("variant", "danger"),
+                }
+                self.role(&button.role);
+                self.events(&button.events);
+                self.extra(&button.extra);
+                self.close();
+            }
+            Element::Checkbox(checkbox) => {
+                self.open("Checkbox", &checkbox.name, &checkbox.doc);
+                self.string("label", &checkbox.label);
+                self.bool("checked", checkbox.checked);
+                self.size_constraints(&checkbox.size_constraints);
+                self.margins(&checkbox.margins);
+                self.bool("disabled", checkbox.disabled);
+                self.role(&checkbox.role);
+                self.events(&checkbox.events);
+                self.extra(&checkbox.extra);
+                self.close();
+            }
+            Element::CheckboxGroup(group) => {
+                self.open("CheckboxGroup", &group.name, &group.doc);
+                if let Some(min) = group.min_checked {
+                    self.property("min-checked", &min.to_string());
+                }
+                if let Some(max) = group.max_checked {
+                    self.property("max-checked", &max.to_string());
+                }
+                self.layout(&group.layout);
+                self.size_constraints(&group.size_constraints);
+                self.margins(&group.margins);
+                self.bool("disabled", group.disabled);
+                self.role(&group.role);
+                self.events(&group.events);
+                self.extra(&group.extra);
+                self.children(&group.children);
+                self.close();
+            }
+            Element::RadioGroup(group) => {
+                self.open("RadioGroup", &group.name, &group.doc);
+                self.string("selected", &group.selected_radio);
+                self.layout(&group.layout);
+                self.size_constraints(&group.size_constraints);
+                self.margins(&group.margins);
+                self.bool("disabled", group.disabled);
+                self.role(&group.role);
+                self.events(&group.events);
+                self.extra(&group.extra);
+                for radio in &group.children {
+                    self.radio(radio);
+                }
+                self.close();
+            }
+            Element::Radio(radio) => self.radio(radio),
+            Element::Dropdown(dropdown) => {
+                self.open("Dropdown", &dropdown.name, &dropdown.doc);
+                self.bool("multi", dropdown.multi);
+                self.string("selected", &dropdown.selected_option);
+                if !dropdown.selected_options.is_empty() {
+                    let labels: Vec<String> = dropdown.selected_options.iter().map(|label| string(label)).collect();
+                    self.property("selected", &format!("[{}]", labels.join(", ")));
+                }
+                self.size_constraints(&dropdown.size_constraints);
+                self.margins(&dropdown.margins);
+                self.bool("disabled", dropdown.disabled);
+                self.role(&dropdown.role);
+                self.events(&dropdown.events);
+                self.extra(&dropdown.extra);
+                let mut in_group = false;
+                for row in dropdown.rows() {
+                    match row {
+                        DropdownRow::Group(label) => {
+                            if in_group {
+                                self.close();
+                            }
+                            self.open("OptGroup", "", &None);
+                            self.string("label", label);
+                            in_group = true;
+                        }
+                        DropdownRow::Option(option) => {
+                            if in_group && option.group.is_none() {
+                                self.close();
+                                in_group = false;
+                            }
+                            self.option(option);
+                        }
+                    }
+                }
+                if in_group {
+                    self.close();
+                }
+                self.close();
+            }
+            Element::Grid(grid) => {
+                self.open("Grid", &grid.name, &grid.doc);
+                self.size_constraints(&grid.size_constraints);
+                self.margins(&grid.margins);
+                if !grid.show_header {
+                    self.property("show-header", "false");
+                }
+                if grid.frozen > 0 {
+                    self.property("frozen", &grid.frozen.to_string());
+                }
+                self.role(&grid.role);
+                self.events(&grid.events);
+                self.extra(&grid.extra);
+                for column in &grid.columns {
+                    self.column(column);
+                }
+                self.close();
+            }
+            Element::Column(column) => self.column(column),
+            Element::Modal(modal) => {
+                self.open("Modal", &modal.name, &modal.doc);
+                self.string("title", &modal.title);
+                self.size_constraints(&modal.size_constraints);
+                if !modal.center {
+                    self.property("center", "false");
+                }
+                self.bool("backdrop", modal.backdrop);
+                self.role(&modal.role);
+                self.events(&modal.events);
+                self.extra(&modal.extra);
+                self.children(&modal.children);
+                self.close();
+            }
+            Element::Tabs(tabs) => {
+                self.open("Tabs", &tabs.name, &tabs.doc);
+                if !tabs.order.is_empty() {
+                    let titles: Vec<String> = tabs.order.iter().map(|title| string(title)).collect();
+                    self.property("order", &format!("[{}]", titles.join(", ")));
+                }
+                self.string("selected", &tabs.selected_tab);
+                match tabs.tab_position {
+                    TabPosition::Top => {}
+                    TabPosition::Bottom => self.property("tab-position", "bottom"),
+                    TabPosition::Left => self.property("tab-position", "left"),
+                    TabPosition::Right => self.property("tab-position", "right"),
+                }
+                self.size_constraints(&tabs.size_constraints);
+                self.margins(&tabs.margins);
+                self.role(&tabs.role);
+                self.events(&tabs.events);
+                self.extra(&tabs.extra);
+                for tab in &tabs.children {
+                    self.tab(tab);
+                }
+                self.close();
+            }
+            Element::Tab(tab) => self.tab(tab),
+            Element::MenuBar(menu_bar) => {
+                self.open("MenuBar", &menu_bar.name, &menu_bar.doc);
+                self.role(&menu_bar.role);
+                self.events(&menu_bar.events);
+                self.extra(&menu_bar.extra);
+                for item in &menu_bar.items {
+                    self.menu_item(item);
+                }
+                self.close();
+            }
+            Element::MenuItem(item) => self.menu_item(item),
+            Element::StatusBar(status_bar) => {
+                self.open("StatusBar", &status_bar.name, &status_bar.doc);
+                self.role(&status_bar.role);
+                self.events(&status_bar.events);
+                self.extra(&status_bar.extra);
+                self.children(&status_bar.children);
+                self.close();
+            }
+            // Custom elements have no source representation.
+            Element::Custom(custom) => self.line(&format!("// custom element `{}`", custom.name)),
+        }
+    }
+
+    fn radio(&mut self, radio: &Radio) {
+        self.open("Radio", &radio.name, &radio.doc);
+        self.string("label", &radio.label);
+        if *radio.value != *radio.name {
+            self.property("value", &string(&radio.value));
+        }
+        self.margins(&radio.margins);
+        self.role(&radio.role);
+        self.events(&radio.events);
+        self.extra(&radio.extra);
+        self.close();
+    }
+
+    fn option(&mut self, option: &DropdownOption) {
+        self.open("Option", &option.name, &option.doc);
+        self.string("label", &option.label);
+        match &option.value {
+            DropdownOptionValue::StringValue(s) if *s == option.label => {}
+            DropdownOptionValue::StringValue(s) => self.property("value", &string(s)),
+            DropdownOptionValue::NumberValue(n) => self.property("value", &n.to_string()),
+            DropdownOptionValue::FloatValue(f) => self.property("value", &format!("{f:?}")),
+            DropdownOptionValue::BoolValue(b) => self.property("value", &b.to_string()),
+        }
+        self.extra(&option.extra);
+        self.close();
+    }
+
+    fn column(&mut self, column: &Column) {
+        self.open("Column", &column.name, &column.doc);
+        self.string("title", &column.title);
+        self.size("width", &column.width);
+        self.role(&column.role);
+        self.events(&column.events);
+        self.extra(&column.extra);
+        self.close();
+    }
+
+    fn tab(&mut self, tab: &Tab) {
+        self.open("Tab", &tab.name, &tab.doc);
+        self.string("title", &tab.title);
+        self.bool("closable", tab.closable);
+        if let Some(order) = tab.order {
+            self.property("order", &order.to_string());
+        }
+        self.role(&tab.role);
+        self.events(&tab.events);
+        self.extra(&tab.extra);
+        self.children(&tab.children);
+        self.close();
+    }
+
+    fn menu_item(&mut self, item: &MenuItem) {
+        self.open("MenuItem", &item.name, &item.doc);
+        self.string("text", &item.text);
+        self.bool("disabled", item.disabled);
+        self.role(&item.role);
+        self.events(&item.events);
+        self.extra(&item.extra);
+        for child in &item.items {
+            self.menu_item(child);
+        }
+        self.close();
+    }
+}
+
+/// Writes text as a string literal, using a d-string when it spans several lines
+/// and reading it back would not strip indentation from them.
+fn string(text: &str) -> String {
+    if text.contains('\n') && crate::parser::dedent(text, 4) == text {
+        format!("d\"{text}\"")
+    } else {
+        format!("\"{text}\"")
+    }
+}
+
+/// Writes a size the way it appears in the source, `auto` for [`SizeConstraint::Auto`].
+fn size(size: &SizeConstraint) -> String {
+    match size {
+        SizeConstraint::Auto => "auto".to_string(),
+        SizeConstraint::Fixed(cells) => cells.to_string(),
+        SizeConstraint::Percentage(percent) => format!("{percent}%"),
+        SizeConstraint::RelativeTo { name, percent } => format!("{percent}% of {name}"),
+        SizeConstraint::Fraction(weight) => format!("{weight}fr"),
+        SizeConstraint::MinContent => "min-content".to_string(),
+        SizeConstraint::MaxContent => "max-content".to_string(),
+        SizeConstraint::Responsive { cases, otherwise } => {
+            let cases: String = cases
+                .iter()
+                .map(|(breakpoint, case)| format!("{} {breakpoint} else ", self::size(case)))
+                .collect();
+            format!("{cases}{}", self::size(otherwise))
+        }
+    }
+}
+
+fn value(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("\"{s}\""),
+        Value::DString(s) => format!("d\"{s}\""),
+        Value::Env(name) => format!("env\"{name}\""),
+        Value::Number(n) => n.to_string(),
+        Value::Percentage(p) => format!("{p}%"),
+        Value::Fraction(weight) => format!("{weight}fr"),
+        Value::Relative(p, name) => format!("{p}% of {name}"),
+        Value::Identifier(id) => id.clone(),
+        Value::List(items) => {
+            let items: Vec<String> = items.iter().map(self::value).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Element(element) => element_value(element),
+        Value::Responsive(cases, otherwise) => {
+            let cases: String = cases
+                .iter()
+                .map(|(breakpoint, case)| format!("{} {breakpoint} else ", self::value(case)))
+                .collect();
+            format!("{cases}{}", self::value(otherwise))
+        }
+        Value::Concat(parts) => {
+            let parts: Vec<String> = parts.iter().map(self::value).collect();
+            parts.join(" + ")
+        }
+    }
+}
+
+/// Writes a parsed element on a single line, as used for list items.
+fn element_value(element: &parser::Element) -> String {
+    let properties: String = element
+        .properties
+        .iter()
+        .map(|property| format!(" {} = {}", property.name, value(&property.value)))
+        .collect();
+    if let ("include", Some(path)) = (element.kind.as_str(), element.get("path")) {
+        return format!("@include {}", value(path));
+    }
+    let mut out = if element.kind == "if" {
+        // The conditions of an `@if` block come before its braces.
+        format!("@if{properties} {{")
+    } else {
+        format!("@{} {} {{{properties}", element.kind, element.name)
+    };
+    for child in &element.children {
+        out.push(' ');
+        out.push_str(&element_value(child));
+    }
+    out.push_str(" }");
+    out
+}
+
+/// Writes the UI as an indented outline, one line per element.
+///
+/// Every line shows the kind, the name and the element's most telling text,
+/// which makes it easier to read than the derived `Debug` output.
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         title = "Login"
+///         @Panel left {
+///             title = "Account"
+///             @Button ok { text = "OK" }
+///         }
+///         @Checkbox remember { label = "Remember me" checked = true }
+///     }
+/// "#).unwrap();
+/// let expected = "\
+/// Form main \"Login\"
+///   Panel left \"Account\"
+///     Button ok \"OK\"
+///   Checkbox remember \"Remember me\" checked
+/// ";
+/// assert_eq!(glyph::emit::tree_string(&ui), expected);
+/// ```
+pub fn tree_string(ui: &UI) -> String {
+    let mut out = String::new();
+    tree_element(&ui.root, 0, &mut out);
+    out
+}
+
+fn tree_element(element: &Element, depth: usize, out: &mut String) {
+    let summary = match element {
+        Element::Form(form) => quoted(&form.title),
+        Element::Panel(panel) if panel.collapsed => format!("{} collapsed", quoted(&panel.title)),
+        Element::Panel(panel) => quoted(&panel.title),
+        Element::Label(label) => quoted(label.text.trim().lines().next().unwrap_or_default()),
+        Element::TextInput(input) if input.default_text.is_empty() => quoted(&input.placeholder),
+        Element::TextInput(input) => quoted(&input.default_text),
+        Element::Button(button) => quoted(&button.text),
+        Element::Checkbox(checkbox) if checkbox.checked => format!("{} checked", quoted(&checkbox.label)),
+        Element::Checkbox(checkbox) => quoted(&checkbox.label),
+        Element::CheckboxGroup(group) => {
+            let checked = group.checkboxes().filter(|checkbox| checkbox.checked).count();
+            format!("{checked} of {} checked", group.children.len())
+        }
+        Element::RadioGroup(group) => selected(&group.selected_radio),
+        Element::Radio(radio) => quoted(&radio.label),
+        Element::Dropdown(dropdown) => selected(&dropdown.selection().join(", ")),
+        Element::Grid(grid) => format!("{} columns", grid.columns.len()),
+        Element::Column(column) => quoted(&column.title),
+        Element::Modal(modal) => quoted(&modal.title),
+        Element::Tabs(tabs) => selected(&tabs.selected_tab),
+        Element::Tab(tab) => quoted(&tab.title),
+        Element::MenuBar(menu_bar) => format!("{} items", menu_bar.items.len()),
+        Element::MenuItem(item) => quoted(&item.text),
+        Element::StatusBar(_) | Element::Custom(_) => String::new(),
+    };
+    tree_line(depth, element.kind_name(), element.name(), &summary, out);
+    match element {
+        Element::Form(form) => tree_children(&form.children, depth + 1, out),
+        Element::Panel(panel) => tree_children(&panel.children, depth + 1, out),
+        Element::Modal(modal) => tree_children(&modal.children, depth + 1, out),
+        Element::Tab(tab) => tree_children(&tab.children, depth + 1, out),
+        Element::CheckboxGroup(group) => tree_children(&group.children, depth + 1, out),
+        Element::StatusBar(status_bar) => tree_children(&status_bar.children, depth + 1, out),
+        Element::RadioGroup(group) => {
+            for radio in &group.children {
+                tree_line(depth + 1, "Radio", &radio.name, &quoted(&radio.label), out);
+            }
+        }
+        Element::Dropdown(dropdown) => {
+            for row in dropdown.rows() {
+                match row {
+                    DropdownRow::Group(label) => tree_line(depth + 1, "OptGroup", "", &quoted(label), out),
+                    DropdownRow::Option(option) => {
+                        let depth = if option.group.is_some() { depth + 2 } else { depth + 1 };
+                        tree_line(depth, "Option", &option.name, &quoted(&option.label), out);
+                    }
+                }
+            }
+        }
+        Element::Grid(grid) => {
+            for column in &grid.columns {
+                tree_line(depth + 1, "Column", &column.name, &quoted(&column.title), out);
+            }
+        }
+        Element::Tabs(tabs) => {
+            for tab in &tabs.children {
+                tree_line(depth + 1, "Tab", &tab.name, &quoted(&tab.title), out);
+                tree_children(&tab.children, depth + 2, out);
+            }
+        }
+        Element::MenuBar(menu_bar) => tree_menu_items(&menu_bar.items, depth + 1, out),
+        Element::MenuItem(item) => tree_menu_items(&item.items, depth + 1, out),
+        _ => {}
+    }
+}
+
+fn tree_menu_items(items: &[MenuItem], depth: usize, out: &mut String) {
+    for item in items {
+        tree_line(depth, "MenuItem", &item.name, &quoted(&item.text), out);
+        tree_menu_items(&item.items, depth + 1, out);
+    }
+}
+
+fn tree_line(depth: usize, kind: &str, name: &str, summary: &str, out: &mut String) {
+    let line = match name {
+        "" => format!("{}{kind} {summary}", "  ".repeat(depth)),
+        name => format!("{}{kind} {name} {summary}", "  ".repeat(depth)),
+    };
+    out.push_str(line.trim_end());
+    out.push('\n');
+}
+
+fn tree_children(children: &[Element], depth: usize, out: &mut String) {
+    for child in children {
+        tree_element(child, depth, out);
+    }
+}
+
+fn quoted(text: &str) -> String {
+    if text.is_empty() { String::new() } else { format!("{text:?}") }
+}
+
+fn selected(value: &str) -> String {
+    if value.is_empty() { String::new() } else { format!("selected {value:?}") }
+}
@@ -0,0 +1,121 @@
+use core::fmt;
+use core::ops::Range;
+
+use crate::compat::*;
+use crate::parser::{self, ParseError};
+use crate::validate::Diagnostic;
+
+/// Represents a failure of a function of the library, such as [`crate::from_str`] or [`crate::lower::lower`].
+///
+/// Callers can match on the kind of failure.
+///
+/// # Variants
+///
+/// - `Parse(Vec<ParseError>)` - The source is not valid syntax, with every error found.
+/// - `Lower { message, span }` - The syntax is valid but does not describe a UI.
+///   `span` is the byte range of the element the error was found on, if it was found on one.
+/// - `Validate(Vec<Diagnostic>)` - The UI has error diagnostics, see [`crate::validate::ensure_valid`].
+/// - `Io(std::io::Error)` - A file could not be read or written, only with the `std` feature.
+/// - `Layout(String)` - Sizes can not be solved, such as relative sizes depending on each other.
+/// - `Element(String)` - An element can not do what was asked of it, such as selecting an option
+///   it does not have.
+/// - `Format(String)` - [`crate::emit::format_source`] would lose part of the source.
+///
+/// ```
+/// use glyph::GlyphError;
+///
+/// let error = glyph::from_str("@language ratatui\n@Form f { title = }").unwrap_err();
+/// assert!(matches!(&error, GlyphError::Parse(errors) if errors.len() == 1));
+///
+/// let src = "@language ratatui\n@Form main { @Button ok { text = 1 } }";
+/// let Err(GlyphError::Lower { message, span: Some(span) }) = glyph::from_str(src) else { panic!() };
+/// assert_eq!(&src[span], "@Button ok { text = 1 }");
+/// assert_eq!(message, "property `text` of element `ok` expects a string, found number 1");
+///
+/// let cycle = "@language ratatui\n@Form main { @Panel a { width = 50% of b } @Panel b { width = 50% of a } }";
+/// assert!(matches!(glyph::from_str(cycle), Err(GlyphError::Layout(_))));
+///
+/// #[cfg(feature = "std")]
+/// {
+///     let missing = glyph::from_file("/nonexistent/layout.gl").unwrap_err();
+///     assert!(matches!(missing, GlyphError::Io(ref error) if error.kind() == std::io::ErrorKind::NotFound));
+/// }
+///
+/// let ui = glyph::from_str("@language ratatui\n@Form main { @Label a { } @Label a { } }").unwrap();
+/// let Err(GlyphError::Validate(diagnostics)) = glyph::validate::ensure_valid(&ui) else { panic!() };
+/// assert_eq!(diagnostics[0].code, "GL002");
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum GlyphError {
+    Parse(Vec<ParseError>),
+    Lower { message: String, span: Option<Range<usize>> },
+    Validate(Vec<Diagnostic>),
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    Layout(String),
+    Element(String),
+    Format(String),
+}
+
+impl GlyphError {
+    /// Renders the error for display, pointing at the source line of every located error.
+    /// Other errors are rendered with their message only.
+    ///
+    /// ```
+    /// let src = "@language ratatui\n@Form main {\n    @Button ok { text = 1 }\n}";
+    /// let error = glyph::lower::lower(&glyph::parser::parse(src).unwrap()).unwrap_err();
+    /// assert_eq!(error.report(src), "\
+    /// error: property `text` of element `ok` expects a string, found number 1
+    ///  --> 3:5
+    ///   |
+    /// 3 |     @Button ok { text = 1 }
+    ///   |     ^^^^^^^^^^^^^^^^^^^^^^^
+    /// ");
+    /// ```
+    pub fn report(&self, src: &str) -> String {
+        match self {
+            GlyphError::Parse(errors) => errors.iter().map(|error| error.report(src)).collect(),
+            GlyphError::Lower { message, span: Some(span) } => parser::render_span(src, span, message),
+            other => format!("error: {other}\n"),
+        }
+    }
+}
+
+impl fmt::Display for GlyphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GlyphError::Parse(errors) => {
+                let messages: Vec<String> = errors.iter().map(ParseError::to_string).collect();
+                write!(f, "failed to parse layout:\n{}", messages.join("\n"))
+            }
+            GlyphError::Lower { message, .. }
+            | GlyphError::Layout(message)
+            | GlyphError::Element(message)
+            | GlyphError::Format(message) => write!(f, "{message}"),
+            GlyphError::Validate(diagnostics) => {
+                let messages: Vec<String> = diagnostics.iter().map(Diagnostic::to_string).collect();
+                write!(f, "layout is invalid:\n{}", messages.join("\n"))
+            }
+            #[cfg(feature = "std")]
+            GlyphError::Io(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl core::error::Error for GlyphError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            GlyphError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for GlyphError {
+    fn from(error: std::io::Error) -> Self {
+        GlyphError::Io(error)
+    }
+}
@@ -0,0 +1,74 @@
+use crate::compat::*;
+use crate::types::*;
+
+/// Returns whether the element can take the keyboard focus.
+///
+/// Text inputs, buttons, checkboxes, radio groups, dropdowns and tab controls are interactive.
+/// A radio group takes the focus as a whole, its radios are chosen within it.
+pub fn is_interactive(element: &Element) -> bool {
+    matches!(
+        element,
+        Element::TextInput(_)
+            | Element::Button(_)
+            | Element::Checkbox(_)
+            | Element::RadioGroup(_)
+            | Element::Dropdown(_)
+            | Element::Tabs(_)
+    )
+}
+
+/// Returns the names of the elements in the order the keyboard focus moves through them.
+///
+/// This is the `focus-order` of the root form when it has one, lowering has checked that it
+/// only names interactive elements of the form. Otherwise it is every interactive element,
+/// see [`is_interactive`], in the order they are written.
+///
+/// ```
+/// use glyph::focus::focus_order;
+///
+/// let explicit = glyph::from_str(r#"
+///     @language ratatui
+///     @Form signup {
+///         focus-order = [email_input, name_input, submit]
+///         @Label hint { text = "Sign up" }
+///         @Panel fields {
+///             @TextInput name_input { }
+///             @TextInput email_input { }
+///         }
+///         @Button submit { text = "Go" }
+///         @Button cancel { text = "Back" }
+///     }
+/// "#).unwrap();
+/// assert_eq!(focus_order(&explicit), ["email_input", "name_input", "submit"]);
+///
+/// let written = glyph::from_str(r#"
+///     @language ratatui
+///     @Form signup {
+///         @Label hint { text = "Sign up" }
+///         @Panel fields {
+///             @TextInput name_input { }
+///             @Checkbox terms { label = "I agree" }
+///         }
+///         @Button submit { text = "Go" }
+///     }
+/// "#).unwrap();
+/// assert_eq!(focus_order(&written), ["name_input", "terms", "submit"]);
+///
+/// let label = glyph::from_str("@language ratatui\n@Form f { focus-order = [hint] @Label hint { } }").unwrap_err();
+/// assert_eq!(label.to_string(), "`focus-order` of Form `f` lists `hint`, which is a Label and can not take the focus");
+/// ```
+pub fn focus_order(ui: &UI) -> Vec<String> {
+    if let Element::Form(form) = &ui.root
+        && !form.focus_order.is_empty()
+    {
+        return form.focus_order.clone();
+    }
+    let mut names = Vec::new();
+    let _ = ui.try_walk(|element| {
+        if is_interactive(element) {
+            names.push(element.name().to_string());
+        }
+        core::ops::ControlFlow::<()>::Continue(())
+    });
+    names
+}
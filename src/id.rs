@@ -0,0 +1,180 @@
+use crate::compat::*;
+use crate::types::*;
+
+/// Gives every element of the UI an `id` made of its path from the root, such as `form/panel[0]/button[1]`.
+///
+/// The root is named after its kind, every other element after its kind and its position among
+/// the siblings of that kind, counted from zero. Ids only depend on the shape of the tree, so the same
+/// tree gets the same ids on every run, renaming an element keeps them, and adding an element only moves
+/// the ids of its siblings of the same kind that come after it. Radios, dropdown options, grid columns,
+/// tabs and menu entries get ids as well. Kinds are written in snake case, `TextInput` becomes `text_input`.
+///
+/// ```
+/// use glyph::id::assign_ids;
+///
+/// let src = r#"
+///     @language ratatui
+///     @Form main {
+///         @MenuBar { @MenuItem file { text = "File" } }
+///         @Panel left {
+///             @Button ok { text = "OK" }
+///             @Label note { text = "Sure?" }
+///             @Button cancel { text = "Cancel" }
+///         }
+///         @Panel right { @Dropdown size { @Option s { label = "S" } @Option m { label = "M" } } }
+///     }
+/// "#;
+/// let mut ui = glyph::from_str(src).unwrap();
+/// assign_ids(&mut ui);
+///
+/// let form = ui.root.as_form().unwrap();
+/// assert_eq!(form.id, "form");
+/// let Some(glyph::Element::MenuBar(menu_bar)) = form.children.first() else { panic!() };
+/// assert_eq!(menu_bar.id, "form/menu_bar[0]");
+/// assert_eq!(menu_bar.items[0].id, "form/menu_bar[0]/menu_item[0]");
+/// let left = form.children[1].as_panel().unwrap();
+/// assert_eq!(left.children[2].as_button().unwrap().id, "form/panel[0]/button[1]");
+/// assert_eq!(left.children[1].as_label().unwrap().id, "form/panel[0]/label[0]");
+/// let size = form.children[2].as_panel().unwrap().children[0].as_dropdown().unwrap();
+/// assert_eq!(size.options[1].id, "form/panel[1]/dropdown[0]/option[1]");
+///
+/// // Lowering the same source again gives the same ids.
+/// let mut again = glyph::from_str(src).unwrap();
+/// assign_ids(&mut again);
+/// assert_eq!(again, ui);
+/// ```
+pub fn assign_ids(ui: &mut UI) {
+    let id = segment(ui.root.kind_name());
+    assign_element(&mut ui.root, id);
+}
+
+fn assign_element(element: &mut Element, id: String) {
+    let children: &mut [Element] = match element {
+        Element::Form(form) => {
+            form.id.clone_from(&id);
+            &mut form.children
+        }
+        Element::Panel(panel) => {
+            panel.id.clone_from(&id);
+            &mut panel.children
+        }
+        Element::Label(label) => {
+            label.id = id;
+            return;
+        }
+        Element::TextInput(input) => {
+            input.id = id;
+            return;
+        }
+        Element::Button(button) => {
+            button.id = id;
+            return;
+        }
+        Element::Checkbox(checkbox) => {
+            checkbox.id = id;
+            return;
+        }
+        Element::CheckboxGroup(group) => {
+            group.id.clone_from(&id);
+            &mut group.children
+        }
+        Element::RadioGroup(group) => {
+            for (index, radio) in group.children.iter_mut().enumerate() {
+                radio.id = format!("{id}/radio[{index}]");
+            }
+            group.id = id;
+            return;
+        }
+        Element::Radio(radio) => {
+            radio.id = id;
+            return;
+        }
+        Element::Dropdown(dropdown) => {
+            for (index, option) in dropdown.options.iter_mut().enumerate() {
+                option.id = format!("{id}/option[{index}]");
+            }
+            dropdown.id = id;
+            return;
+        }
+        Element::Grid(grid) => {
+            for (index, column) in grid.columns.iter_mut().enumerate() {
+                column.id = format!("{id}/column[{index}]");
+            }
+            grid.id = id;
+            return;
+        }
+        Element::Column(column) => {
+            column.id = id;
+            return;
+        }
+        Element::Modal(modal) => {
+            modal.id.clone_from(&id);
+            &mut modal.children
+        }
+        Element::Tabs(tabs) => {
+            for (index, tab) in tabs.children.iter_mut().enumerate() {
+                assign_tab(tab, format!("{id}/tab[{index}]"));
+            }
+            tabs.id = id;
+            return;
+        }
+        Element::Tab(tab) => {
+            assign_tab(tab, id);
+            return;
+        }
+        Element::MenuBar(menu_bar) => {
+            assign_menu_items(&mut menu_bar.items, &id);
+            menu_bar.id = id;
+            return;
+        }
+        Element::MenuItem(item) => {
+            assign_menu_items(&mut item.items, &id);
+            item.id = id;
+            return;
+        }
+        Element::StatusBar(status_bar) => {
+            status_bar.id.clone_from(&id);
+            &mut status_bar.children
+        }
+        Element::Custom(custom) => {
+            custom.id = id;
+            return;
+        }
+    };
+    assign_children(children, &id);
+}
+
+fn assign_children(children: &mut [Element], parent: &str) {
+    let mut counts: HashMap<&'static str, usize> = HashMap::new();
+    for child in children {
+        let count = counts.entry(child.kind_name()).or_default();
+        let id = format!("{parent}/{}[{count}]", segment(child.kind_name()));
+        *count += 1;
+        assign_element(child, id);
+    }
+}
+
+fn assign_tab(tab: &mut Tab, id: String) {
+    assign_children(&mut tab.children, &id);
+    tab.id = id;
+}
+
+fn assign_menu_items(items: &mut [MenuItem], parent: &str) {
+    for (index, item) in items.iter_mut().enumerate() {
+        let id = format!("{parent}/menu_item[{index}]");
+        assign_menu_items(&mut item.items, &id);
+        item.id = id;
+    }
+}
+
+/// Returns the kind as written in ids, in snake case.
+fn segment(kind: &str) -> String {
+    let mut segment = String::with_capacity(kind.len() + 2);
+    for (index, c) in kind.char_indices() {
+        if c.is_ascii_uppercase() && index > 0 {
+            segment.push('_');
+        }
+        segment.push(c.to_ascii_lowercase());
+    }
+    segment
+}
@@ -0,0 +1,160 @@
+use crate::compat::*;
+use crate::text;
+use crate::types::*;
+
+/// Replaces the `${name}` placeholders in the texts of a lowered UI with values chosen at runtime.
+///
+/// Titles of forms, panels, modals, grid columns and tabs are resolved, as are the texts of labels,
+/// buttons and menu items and the labels of checkboxes, radios and dropdown options. The titles
+/// listed in the `order` of a tab control are resolved too, so they keep naming their tabs.
+/// A placeholder whose name is not in `vars` is kept as written, so the UI can be resolved again
+/// with more values; the texts of raw d-strings are no exception.
+///
+/// ```
+/// use glyph::interpolate::resolve_interpolations;
+///
+/// let mut ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Panel items {
+///             title = "Items (${count})"
+///             @Label owner { text = "Owner: ${user}, since ${since}" }
+///         }
+///     }
+/// "#).unwrap();
+///
+/// let vars = [("count", "3"), ("user", "ada")].map(|(name, value)| (name.to_string(), value.to_string()));
+/// resolve_interpolations(&mut ui, &vars.into_iter().collect());
+///
+/// let panel = ui.root.as_form().unwrap().children[0].as_panel().unwrap();
+/// assert_eq!(panel.title, "Items (3)");
+/// assert_eq!(panel.children[0].as_label().unwrap().text, "Owner: ada, since ${since}");
+/// ```
+pub fn resolve_interpolations(ui: &mut UI, vars: &HashMap<String, String>) {
+    resolve_element(&mut ui.root, vars);
+}
+
+fn resolve_element(element: &mut Element, vars: &HashMap<String, String>) {
+    let children: &mut [Element] = match element {
+        Element::Form(form) => {
+            resolve(&mut form.title, vars);
+            &mut form.children
+        }
+        Element::Panel(panel) => {
+            resolve(&mut panel.title, vars);
+            &mut panel.children
+        }
+        Element::Label(label) => {
+            resolve(&mut label.text, vars);
+            if label.markup {
+                label.spans = text::parse_markup(&label.text);
+            }
+            &mut []
+        }
+        Element::Button(button) => {
+            resolve(&mut button.text, vars);
+            &mut []
+        }
+        Element::Checkbox(checkbox) => {
+            resolve(&mut checkbox.label, vars);
+            &mut []
+        }
+        Element::CheckboxGroup(group) => &mut group.children,
+        Element::RadioGroup(group) => {
+            for radio in &mut group.children {
+                resolve(&mut radio.label, vars);
+            }
+            &mut []
+        }
+        Element::Radio(radio) => {
+            resolve(&mut radio.label, vars);
+            &mut []
+        }
+        Element::Dropdown(dropdown) => {
+            for option in &mut dropdown.options {
+                resolve(&mut option.label, vars);
+                if let Some(group) = &mut option.group {
+                    resolve(group, vars);
+                }
+            }
+            &mut []
+        }
+        Element::Grid(grid) => {
+            for column in &mut grid.columns {
+                resolve(&mut column.title, vars);
+            }
+            &mut []
+        }
+        Element::Column(column) => {
+            resolve(&mut column.title, vars);
+            &mut []
+        }
+        Element::Modal(modal) => {
+            resolve(&mut modal.title, vars);
+            &mut modal.children
+        }
+        Element::Tabs(tabs) => {
+            for title in &mut tabs.order {
+                resolve(title, vars);
+            }
+            for tab in &mut tabs.children {
+                resolve_tab(tab, vars);
+            }
+            &mut []
+        }
+        Element::Tab(tab) => {
+            resolve_tab(tab, vars);
+            &mut []
+        }
+        Element::MenuBar(menu_bar) => {
+            resolve_menu_items(&mut menu_bar.items, vars);
+            &mut []
+        }
+        Element::MenuItem(item) => {
+            resolve_menu_items(core::slice::from_mut(item), vars);
+            &mut []
+        }
+        Element::StatusBar(status_bar) => &mut status_bar.children,
+        Element::TextInput(_) | Element::Custom(_) => &mut [],
+    };
+    for child in children {
+        resolve_element(child, vars);
+    }
+}
+
+fn resolve_tab(tab: &mut Tab, vars: &HashMap<String, String>) {
+    resolve(&mut tab.title, vars);
+    for child in &mut tab.children {
+        resolve_element(child, vars);
+    }
+}
+
+fn resolve_menu_items(items: &mut [MenuItem], vars: &HashMap<String, String>) {
+    for item in items {
+        resolve(&mut item.text, vars);
+        resolve_menu_items(&mut item.items, vars);
+    }
+}
+
+/// Replaces the known placeholders of `text`, leaving it untouched when it has none.
+fn resolve(text: &mut String, vars: &HashMap<String, String>) {
+    if !text.contains("${") {
+        return;
+    }
+    let mut resolved = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + 2 + end];
+        resolved.push_str(&rest[..start]);
+        match vars.get(name) {
+            Some(value) => resolved.push_str(value),
+            None => resolved.push_str(&rest[start..start + 3 + end]),
+        }
+        rest = &rest[start + 3 + end..];
+    }
+    resolved.push_str(rest);
+    *text = resolved;
+}
@@ -0,0 +1,1422 @@
+use core::hash::{Hash, Hasher};
+
+use crate::compat::*;
+
+use crate::text;
+use crate::types::*;
+use crate::validate;
+
+/// Represents a rectangular area measured in terminal cells.
+///
+/// # Fields
+///
+/// - `x` (`u32`) - Column of the left edge.
+/// - `y` (`u32`) - Row of the top edge.
+/// - `width` (`u32`) - Width of the area.
+/// - `height` (`u32`) - Height of the area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Rect { x, y, width, height }
+    }
+
+    /// Returns the area left after removing the given margins.
+    /// Margins larger than the area shrink it to zero.
+    pub fn inset(&self, margins: &Margins) -> Rect {
+        let x = self.x.saturating_add(margins.left.min(self.width));
+        let y = self.y.saturating_add(margins.top.min(self.height));
+        Rect {
+            x,
+            y,
+            width: self.width.saturating_sub(margins.left.saturating_add(margins.right)),
+            height: self.height.saturating_sub(margins.top.saturating_add(margins.bottom)),
+        }
+    }
+
+    /// Returns whether `other` lies completely inside this rect.
+    pub fn contains(&self, other: &Rect) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.x + other.width <= self.x + self.width
+            && other.y + other.height <= self.y + self.height
+    }
+
+    /// Returns the area inside a one cell wide border.
+    pub fn inner(&self) -> Rect {
+        self.inset(&Margins {
+            left: 1,
+            right: 1,
+            top: 1,
+            bottom: 1,
+        })
+    }
+}
+
+/// Computes the area occupied by every element of the UI, keyed by element name.
+///
+/// The root element fills `area`. Containers split their inner area between
+/// their children according to their `Layout` and the children's size constraints:
+/// fixed sizes are taken first, percentages are relative to the container,
+/// and `Auto` children share whatever space remains.
+///
+/// Grid columns are the exception: an `Auto` column is as wide as its widest cell.
+/// Their areas hold the cells, below the header row of titles unless `show_header` is off.
+///
+/// ```
+/// use glyph::layout::{Rect, arrange};
+///
+/// let grid = |show_header: bool| {
+///     glyph::from_str(&format!(r#"
+///         @language ratatui
+///         @Form main {{
+///             @Grid table {{
+///                 show-header = {show_header}
+///                 @Column id {{ title = "Id" width = 6 }}
+///                 @Column name {{ title = "Full name" }}
+///             }}
+///         }}
+///     "#)).unwrap()
+/// };
+/// let rects = arrange(&grid(true), Rect::new(0, 0, 40, 10));
+/// assert_eq!(rects["name"].width, "Full name".len() as u32);
+/// assert_eq!(rects["table"], Rect::new(1, 1, 38, 8));
+/// assert_eq!(rects["id"], Rect::new(2, 3, 6, 5));
+///
+/// let rects = arrange(&grid(false), Rect::new(0, 0, 40, 10));
+/// assert_eq!(rects["id"], Rect::new(2, 2, 6, 6));
+/// ```
+///
+/// Radio groups stack their radios by default. Side by side, each radio is as wide
+/// as its mark and label:
+///
+/// ```
+/// use glyph::layout::{Rect, arrange};
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @RadioGroup size {
+///             layout = left-to-right
+///             @Radio small { label = "Small" }
+///             @Radio large { label = "Extra large" }
+///         }
+///     }
+/// "#).unwrap();
+/// let rects = arrange(&ui, Rect::new(0, 0, 40, 10));
+/// assert_eq!(rects["small"], Rect::new(1, 1, 9, 1));
+/// assert_eq!(rects["large"], Rect::new(10, 1, 15, 1));
+/// ```
+///
+/// A collapsed panel keeps only its title row, its children get no area:
+///
+/// ```
+/// use glyph::layout::{Rect, arrange};
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Panel details {
+///             title = "Details"
+///             collapsible collapsed
+///             @Label hint { text = "Hidden" }
+///         }
+///     }
+/// "#).unwrap();
+/// let rects = arrange(&ui, Rect::new(0, 0, 40, 10));
+/// assert_eq!(rects["details"].height, 1);
+/// assert!(!rects.contains_key("hint"));
+/// ```
+///
+/// Margins are added to fixed sizes without overflowing, however large both are:
+///
+/// ```
+/// use glyph::layout::{Rect, arrange};
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         layout = left-to-right
+///         @Button wide { width = 4294967295 margins-left = 1 margins-right = 4294967295 }
+///         @Button tall { height = 4294967295 margins-top = 4294967295 margins-bottom = 1 }
+///     }
+/// "#).unwrap();
+/// let rects = arrange(&ui, Rect::new(0, 0, 40, 10));
+/// assert_eq!(rects["wide"], Rect::new(2, 1, 0, 8));
+/// assert_eq!(rects["tall"].width, 0);
+/// ```
+///
+/// A size can be a share of another element's size, such as `width = 50% of sidebar`.
+/// Such sizes are resolved after laying out the tree once, then the tree is laid out again.
+/// Lowering rejects references that go around in a cycle:
+///
+/// ```
+/// use glyph::layout::{Rect, arrange};
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         layout = left-to-right
+///         @Panel sidebar { width = 20 }
+///         @Panel content { width = 50% of sidebar }
+///     }
+/// "#).unwrap();
+/// let rects = arrange(&ui, Rect::new(0, 0, 60, 10));
+/// assert_eq!(rects["content"].width, 10);
+///
+/// let cycle = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Panel a { width = 50% of b }
+///         @Panel b { width = 50% of a }
+///     }
+/// "#);
+/// assert!(cycle.unwrap_err().to_string().contains("cycle"));
+/// ```
+pub fn arrange(ui: &UI, area: Rect) -> HashMap<String, Rect> {
+    // Responsive sizes stand for the size chosen for the area from the start.
+    let mut responsive = false;
+    validate::visit(&ui.root, &mut |node| {
+        responsive |= node.sizes().iter().any(|(_, size)| matches!(size, SizeConstraint::Responsive { .. }));
+    });
+    let chosen;
+    let root = if responsive {
+        let mut root = ui.root.clone();
+        choose_sizes(&mut root, area.width, area.height);
+        chosen = root;
+        &chosen
+    } else {
+        &ui.root
+    };
+    let mut rects = HashMap::new();
+    arrange_element(root, area, &mut rects);
+    // Sizes relative to another element are `Auto` in the first pass. They are then
+    // fixed to a share of what the referenced element got and the tree is laid out again.
+    let references = relative_references(root);
+    if !references.is_empty() {
+        let mut resolved = HashMap::new();
+        for key in references.keys() {
+            resolve_reference(key, &references, &rects, &mut resolved, &mut Vec::new());
+        }
+        let mut root = root.clone();
+        fix_sizes(&mut root, &resolved);
+        rects.clear();
+        arrange_element(&root, area, &mut rects);
+    }
+    rects
+}
+
+/// Returns the area the children of every scrolling panel are laid out in, by panel name.
+///
+/// The area starts at the top left of the panel's content and grows past the panel
+/// along the scrolled axes when the children need more room. A renderer clips the children
+/// to the panel and offsets them by its scroll position, which can range up to the
+/// difference between this area and the panel's own content area.
+///
+/// ```
+/// use glyph::layout::{Rect, arrange, scroll_contents};
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Panel log {
+///             scroll = vertical
+///             height = 5
+///             @Label a { text = "1" }
+///             @Label b { text = "2" }
+///             @Label c { text = "3" }
+///             @Label d { text = "4" }
+///             @Label e { text = "5" }
+///             @Label f { text = "6" }
+///         }
+///     }
+/// "#).unwrap();
+/// let area = Rect::new(0, 0, 20, 10);
+/// let rects = arrange(&ui, area);
+/// let content = scroll_contents(&ui, area)["log"];
+/// assert_eq!(rects["log"].inner().height, 3);
+/// assert_eq!(content.height, 6);
+/// assert_eq!(rects["f"].y, content.y + 5);
+/// ```
+pub fn scroll_contents(ui: &UI, area: Rect) -> HashMap<String, Rect> {
+    let rects = arrange(ui, area);
+    let mut contents = HashMap::new();
+    validate::visit(&ui.root, &mut |node| {
+        if let validate::Node::Element(Element::Panel(panel)) = node
+            && panel.scroll != ScrollMode::None
+            && !panel.collapsed
+            && let Some(panel_area) = rects.get(&*panel.name)
+        {
+            contents.insert(panel.name.to_string(), scroll_content(panel, *panel_area));
+        }
+    });
+    contents
+}
+
+/// Maps an element name and size property to a reference to another element.
+pub(crate) type References = HashMap<(String, &'static str), (String, u32)>;
+
+/// Collects every size given relative to another element, keyed by the element
+/// and property carrying it, with the referenced element and the percentage.
+pub(crate) fn relative_references(root: &Element) -> References {
+    let mut references = HashMap::new();
+    validate::visit(root, &mut |node| {
+        for (property, size) in node.sizes() {
+            if let SizeConstraint::RelativeTo { name, percent } = size {
+                references.insert((node.name().to_string(), property), (name, percent));
+            }
+        }
+    });
+    references
+}
+
+/// Returns the property of the referenced element a relative size is a share of:
+/// `width` for widths and left positions, `height` for heights and top positions.
+pub(crate) fn reference_axis(property: &str) -> &'static str {
+    match property {
+        "width" | "left" => "width",
+        _ => "height",
+    }
+}
+
+/// Resolves a relative size to cells, resolving any size it depends on first.
+/// Sizes that are not relative are taken from the first pass.
+fn resolve_reference(
+    key: &(String, &'static str),
+    references: &References,
+    rects: &HashMap<String, Rect>,
+    resolved: &mut HashMap<(String, &'static str), u32>,
+    visiting: &mut Vec<(String, &'static str)>,
+) -> Option<u32> {
+    if let Some(cells) = resolved.get(key) {
+        return Some(*cells);
+    }
+    let Some((target, percent)) = references.get(key) else {
+        let rect = rects.get(&key.0)?;
+        return Some(if key.1 == "width" { rect.width } else { rect.height });
+    };
+    // Lowering rejects cycles, a tree built by hand just keeps them `Auto`.
+    if visiting.contains(key) {
+        return None;
+    }
+    visiting.push(key.clone());
+    let base = resolve_reference(&(target.clone(), reference_axis(key.1)), references, rects, resolved, visiting);
+    visiting.pop();
+    let cells = (base? as u64 * *percent as u64 / 100) as u32;
+    resolved.insert(key.clone(), cells);
+    Some(cells)
+}
+
+/// Replaces the resolved relative sizes of the tree by fixed sizes.
+fn fix_sizes(root: &mut Element, resolved: &HashMap<(String, &'static str), u32>) {
+    sizes_mut(root, &mut |name, property, size| {
+        if let Some(cells) = resolved.get(&(name.to_string(), property)) {
+            *size = SizeConstraint::Fixed(*cells);
+        }
+    });
+}
+
+/// Replaces the responsive sizes of the tree by the sizes chosen for an area of `width` by `height` cells.
+fn choose_sizes(root: &mut Element, width: u32, height: u32) {
+    sizes_mut(root, &mut |_, _, size| {
+        if let SizeConstraint::Responsive { .. } = size {
+            *size = size.for_area(width, height).clone();
+        }
+    });
+}
+
+/// Calls `f` with the element name, property and size of every size stored in the tree.
+fn sizes_mut(element: &mut Element, f: &mut impl FnMut(&str, &'static str, &mut SizeConstraint)) {
+    let mut fix_all = |name: &str, sizes: &mut SizeConstraints| {
+        f(name, "width", &mut sizes.width);
+        f(name, "height", &mut sizes.height);
+        f(name, "left", &mut sizes.left);
+        f(name, "top", &mut sizes.top);
+    };
+    let children = match element {
+        Element::Form(form) => &mut form.children,
+        Element::Panel(panel) => {
+            fix_all(&panel.name, &mut panel.size_constraints);
+            &mut panel.children
+        }
+        Element::Modal(modal) => {
+            fix_all(&modal.name, &mut modal.size_constraints);
+            &mut modal.children
+        }
+        Element::Tab(tab) => &mut tab.children,
+        Element::Tabs(tabs) => {
+            fix_all(&tabs.name, &mut tabs.size_constraints);
+            for tab in &mut tabs.children {
+                for child in &mut tab.children {
+                    sizes_mut(child, f);
+                }
+            }
+            return;
+        }
+        Element::Grid(grid) => {
+            fix_all(&grid.name, &mut grid.size_constraints);
+            for column in &mut grid.columns {
+                f(&column.name, "width", &mut column.width);
+            }
+            return;
+        }
+        Element::Column(column) => {
+            f(&column.name, "width", &mut column.width);
+            return;
+        }
+        Element::Label(label) => {
+            fix_all(&label.name, &mut label.size_constraints);
+            return;
+        }
+        Element::TextInput(input) => {
+            fix_all(&input.name, &mut input.size_constraints);
+            return;
+        }
+        Element::Button(button) => {
+            fix_all(&button.name, &mut button.size_constraints);
+            return;
+        }
+        Element::Checkbox(checkbox) => {
+            fix_all(&checkbox.name, &mut checkbox.size_constraints);
+            return;
+        }
+        Element::CheckboxGroup(group) => {
+            fix_all(&group.name, &mut group.size_constraints);
+            &mut group.children
+        }
+        Element::StatusBar(status_bar) => &mut status_bar.children,
+        Element::RadioGroup(group) => {
+            fix_all(&group.name, &mut group.size_constraints);
+            return;
+        }
+        Element::Dropdown(dropdown) => {
+            fix_all(&dropdown.name, &mut dropdown.size_constraints);
+            return;
+        }
+        // Radios and menus have no size, custom elements report theirs through their implementation.
+        Element::Radio(_) | Element::MenuBar(_) | Element::MenuItem(_) | Element::Custom(_) => return,
+    };
+    for child in children {
+        sizes_mut(child, f);
+    }
+}
+
+/// Returns the smallest width and height the UI can be arranged in without cutting off content.
+///
+/// Fixed sizes count as they are. `Auto`, percentage and relative sizes count with the
+/// natural size of their content: the display width of titles and texts, one row per line
+/// of a label; containers add up their children along their layout direction.
+/// Labels count unwrapped, as wrapping trades width for height.
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Panel top { height = 3 }
+///         @Panel bottom { height = 4 }
+///     }
+/// "#).unwrap();
+/// // Both panels plus the border of the form, which also sets the width.
+/// assert_eq!(glyph::layout::min_size(&ui), (4, 9));
+/// ```
+///
+/// Sizes too large to add up are `u32::MAX`, also when scrolled content needs them:
+///
+/// ```
+/// use glyph::layout::{Rect, min_size, scroll_contents};
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Panel canvas {
+///             layout = free-form
+///             scroll = both
+///             @Button far { left = 4294967295 top = 4294967295 width = 4294967295 height = 4294967295 }
+///         }
+///     }
+/// "#).unwrap();
+/// assert_eq!(min_size(&ui), (u32::MAX, u32::MAX));
+/// let content = scroll_contents(&ui, Rect::new(0, 0, 40, 10))["canvas"];
+/// assert_eq!((content.x + content.width, content.y + content.height), (u32::MAX, u32::MAX));
+/// ```
+pub fn min_size(ui: &UI) -> (u32, u32) {
+    min_slot(&ui.root)
+}
+
+/// Returns the minimum size of an element's slot, margins included.
+fn min_slot(element: &Element) -> (u32, u32) {
+    let (size, margins) = constraints(element);
+    let (width, height) = natural_size(element);
+    let width = match size.width {
+        SizeConstraint::Fixed(cells) => cells,
+        _ => width,
+    };
+    let height = match size.height {
+        SizeConstraint::Fixed(cells) => cells,
+        _ => height,
+    };
+    (width.saturating_add(horizontal_margins(&margins)), height.saturating_add(vertical_margins(&margins)))
+}
+
+/// Returns the size the content of an element needs.
+fn natural_size(element: &Element) -> (u32, u32) {
+    let width = |s: &str| text::display_width(s) as u32;
+    match element {
+        Element::Form(form) => boxed(&form.title, min_children(&form.children, &form.layout)),
+        Element::Panel(panel) if panel.collapsed => (width(&panel.title) + 2, 1),
+        Element::Panel(panel) if panel.border == BorderStyle::None => min_children(&panel.children, &panel.layout),
+        Element::Panel(panel) => boxed(&panel.title, min_children(&panel.children, &panel.layout)),
+        Element::Modal(modal) => boxed(&modal.title, min_children(&modal.children, &Layout::TopToBottom)),
+        Element::Label(label) => {
+            let text = label.plain_text();
+            let lines: Vec<&str> = text.trim().lines().map(str::trim).collect();
+            (lines.iter().map(|line| width(line)).max().unwrap_or(0), lines.len() as u32)
+        }
+        Element::TextInput(input) => (width(&input.placeholder).max(width(&input.default_text)), 1),
+        Element::Button(button) => (width(&button.text) + 2, 1),
+        Element::Checkbox(checkbox) => (width(&checkbox.label) + 4, 1),
+        Element::CheckboxGroup(group) => min_children(&group.children, &group.layout),
+        Element::RadioGroup(group) => {
+            let radios = group
+                .children
+                .iter()
+                .map(|radio| (radio_width(radio).saturating_add(horizontal_margins(&radio.margins)), 1));
+            match group.layout {
+                Layout::LeftToRight | Layout::RightToLeft => {
+                    radios.fold((0, 0), |(w, h), (rw, rh)| (w.saturating_add(rw), h.max(rh)))
+                }
+                _ => radios.fold((0, 0), |(w, h), (rw, rh)| (w.max(rw), h + rh)),
+            }
+        }
+        Element::Radio(radio) => (radio_width(radio), 1),
+        Element::Dropdown(dropdown) => (width(&dropdown.selection().join(", ")) + 4, 1),
+        Element::Grid(grid) => {
+            let columns: u32 = grid
+                .columns
+                .iter()
+                .map(|column| match column.width {
+                    SizeConstraint::Fixed(cells) => cells,
+                    _ => width(&column.title),
+                })
+                .fold(0, u32::saturating_add);
+            (columns.saturating_add(2), 2 + u32::from(grid.show_header))
+        }
+        Element::Column(column) => (width(&column.title), 1),
+        Element::Tabs(tabs) => {
+            let content = tabs
+                .children
+                .iter()
+                .map(|tab| min_children(&tab.children, &Layout::TopToBottom))
+                .fold((0, 0), |(w, h), (tw, th)| (w.max(tw), h.max(th)));
+            let labels: Vec<u32> = tabs.children.iter().map(|tab| width(&tab_label(tab)) + 2).collect();
+            match tabs.tab_position {
+                TabPosition::Top | TabPosition::Bottom => {
+                    // Headers are separated by a `|`.
+                    let header = labels.iter().sum::<u32>() + (labels.len() as u32).saturating_sub(1);
+                    (content.0.max(header), content.1.saturating_add(1))
+                }
+                TabPosition::Left | TabPosition::Right => {
+                    let header = labels.iter().max().copied().unwrap_or(0);
+                    (content.0.saturating_add(header), content.1.max(labels.len() as u32))
+                }
+            }
+        }
+        Element::Tab(tab) => min_children(&tab.children, &Layout::TopToBottom),
+        Element::MenuBar(menu_bar) => (menu_bar.items.iter().map(menu_item_width).sum(), 1),
+        Element::MenuItem(item) => (menu_item_width(item), 1),
+        Element::StatusBar(status_bar) => (min_children(&status_bar.children, &Layout::LeftToRight).0, 1),
+        Element::Custom(_) => (0, 0),
+    }
+}
+
+/// Adds a one cell border around `inner`, widened to fit the title.
+fn boxed(title: &str, inner: (u32, u32)) -> (u32, u32) {
+    (inner.0.saturating_add(2).max(text::display_width(title) as u32 + 2), inner.1.saturating_add(2))
+}
+
+/// Returns the size the children need together in the given layout.
+fn min_children(children: &[Element], layout: &Layout) -> (u32, u32) {
+    let mut size = (0, 0);
+    for child in children {
+        let (width, height) = min_slot(child);
+        size = match (child, layout) {
+            // Centered modals float above the other children, menu bars and status bars sit on top
+            // of them and below them.
+            (Element::Modal(modal), _) if modal.center => (size.0.max(width), size.1.max(height)),
+            (Element::MenuBar(_) | Element::StatusBar(_), _) => (size.0.max(width), size.1.saturating_add(height)),
+            (_, Layout::LeftToRight | Layout::RightToLeft) => (size.0.saturating_add(width), size.1.max(height)),
+            (_, Layout::TopToBottom | Layout::BottomToTop) => (size.0.max(width), size.1.saturating_add(height)),
+            (_, Layout::FreeForm) => {
+                let position = constraints(child).0;
+                let offset = |position: &SizeConstraint| match position {
+                    SizeConstraint::Fixed(cells) => *cells,
+                    _ => 0,
+                };
+                (
+                    size.0.max(offset(&position.left).saturating_add(width)),
+                    size.1.max(offset(&position.top).saturating_add(height)),
+                )
+            }
+        };
+    }
+    size
+}
+
+/// Shrinks fixed sizes that do not fit into their container when laid out in `area`.
+///
+/// In every left to right or top to bottom container whose children need more cells
+/// along the layout's axis than it has, the fixed sizes of the children are scaled down
+/// to add up to exactly the cells that are left after margins, percentages and sizes
+/// that can not change, such as the title row of a collapsed panel.
+///
+/// Every size keeps one cell and the remaining space is shared in proportion to the
+/// cells above that. Shares are rounded down, the cells left over then go one each
+/// to the sizes with the largest remainder, to earlier children on ties.
+/// When there are more fixed children than cells, each keeps its one cell and the
+/// container still overflows. Free-form containers and grid columns are left alone.
+///
+/// ```
+/// use glyph::layout::{Rect, arrange, shrink_to_fit};
+///
+/// let mut ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         layout = left-to-right
+///         @Panel a { width = 40 }
+///         @Panel b { width = 40 }
+///         @Panel c { width = 40 }
+///     }
+/// "#).unwrap();
+/// // The form's border leaves 100 cells for 120 cells of panels.
+/// let area = Rect::new(0, 0, 102, 10);
+/// shrink_to_fit(&mut ui, area);
+/// let rects = arrange(&ui, area);
+/// let widths: Vec<u32> = ["a", "b", "c"].iter().map(|name| rects[*name].width).collect();
+/// assert_eq!(widths, [34, 33, 33]);
+/// assert_eq!(widths.iter().sum::<u32>(), 100);
+/// ```
+pub fn shrink_to_fit(ui: &mut UI, area: Rect) {
+    shrink_element(&mut ui.root, area);
+}
+
+fn shrink_element(element: &mut Element, area: Rect) {
+    match element {
+        Element::Form(form) => shrink_children(&mut form.children, area.inner(), &form.layout),
+        Element::Panel(panel) if !panel.collapsed => {
+            let content = panel_content_area(panel, area);
+            shrink_children(&mut panel.children, content, &panel.layout);
+        }
+        Element::Modal(modal) => shrink_children(&mut modal.children, area.inner(), &Layout::TopToBottom),
+        Element::Tabs(tabs) => {
+            let content = tab_content_area(tabs, area);
+            for tab in &mut tabs.children {
+                shrink_children(&mut tab.children, content, &Layout::TopToBottom);
+            }
+        }
+        Element::Tab(tab) => shrink_children(&mut tab.children, area, &Layout::TopToBottom),
+        Element::CheckboxGroup(group) => shrink_children(&mut group.children, area, &group.layout),
+        Element::StatusBar(status_bar) => shrink_children(&mut status_bar.children, area, &Layout::LeftToRight),
+        _ => {}
+    }
+}
+
+/// Shrinks the children along the layout's axis, then the children of every child in its new area.
+fn shrink_children(children: &mut [Element], area: Rect, layout: &Layout) {
+    match layout {
+        Layout::LeftToRight | Layout::RightToLeft => shrink_axis(children, area.width, true),
+        Layout::TopToBottom | Layout::BottomToTop => shrink_axis(children, area.height, false),
+        Layout::FreeForm => {}
+    }
+    let mut rects = HashMap::new();
+    arrange_children(children, area, layout, &mut rects);
+    for child in children {
+        if let Some(child_area) = rects.get(child.name()).copied() {
+            shrink_element(child, child_area);
+        }
+    }
+}
+
+fn shrink_axis(children: &mut [Element], total: u32, horizontal: bool) {
+    fn along(size: &mut SizeConstraints, horizontal: bool) -> &mut SizeConstraint {
+        if horizontal { &mut size.width } else { &mut size.height }
+    }
+    let mut taken = 0u64;
+    let mut shrinkable = Vec::new();
+    for (index, child) in children.iter_mut().enumerate() {
+        if matches!(child, Element::Modal(modal) if modal.center) {
+            continue;
+        }
+        let (mut size, margins) = constraints(child);
+        let margins = if horizontal { horizontal_margins(&margins) } else { vertical_margins(&margins) };
+        let size = along(&mut size, horizontal).clone();
+        // The stored size only shrinks when it is the one layout uses.
+        let stored = size_constraints_mut(child).map(|stored| along(stored, horizontal).clone());
+        match size {
+            SizeConstraint::Fixed(cells) if stored == Some(SizeConstraint::Fixed(cells)) => {
+                taken += margins as u64;
+                shrinkable.push((index, cells));
+            }
+            other => taken += resolve_outer(&other, margins, total).unwrap_or(0) as u64,
+        }
+    }
+    let wanted: u64 = shrinkable.iter().map(|(_, cells)| *cells as u64).sum();
+    if taken + wanted <= total as u64 {
+        return;
+    }
+    let available = (total as u64).saturating_sub(taken);
+    let sizes: Vec<u32> = shrinkable.iter().map(|(_, cells)| *cells).collect();
+    for ((index, _), cells) in shrinkable.iter().zip(scale_down(&sizes, available)) {
+        if let Some(size) = size_constraints_mut(&mut children[*index]) {
+            *along(size, horizontal) = SizeConstraint::Fixed(cells);
+        }
+    }
+}
+
+/// Scales `sizes` down to add up to `available`, see [`shrink_to_fit`] for the rounding.
+fn scale_down(sizes: &[u32], available: u64) -> Vec<u32> {
+    let spare = available.saturating_sub(sizes.len() as u64);
+    let above: u64 = sizes.iter().map(|cells| cells.saturating_sub(1) as u64).sum();
+    if spare == 0 || above == 0 {
+        return vec![1; sizes.len()];
+    }
+    let shares: Vec<(u64, u64)> = sizes
+        .iter()
+        .map(|cells| {
+            let above_one = cells.saturating_sub(1) as u64 * spare;
+            (above_one / above, above_one % above)
+        })
+        .collect();
+    let mut result: Vec<u32> = shares.iter().map(|(share, _)| 1 + *share as u32).collect();
+    let leftover = spare - shares.iter().map(|(share, _)| share).sum::<u64>();
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|index| core::cmp::Reverse(shares[*index].1));
+    for index in order.into_iter().take(leftover as usize) {
+        result[index] += 1;
+    }
+    result
+}
+
+/// Returns the size constraints stored on an element, `None` for kinds without them.
+fn size_constraints_mut(element: &mut Element) -> Option<&mut SizeConstraints> {
+    match element {
+        Element::Panel(panel) => Some(&mut panel.size_constraints),
+        Element::Label(label) => Some(&mut label.size_constraints),
+        Element::TextInput(input) => Some(&mut input.size_constraints),
+        Element::Button(button) => Some(&mut button.size_constraints),
+        Element::Checkbox(checkbox) => Some(&mut checkbox.size_constraints),
+        Element::CheckboxGroup(group) => Some(&mut group.size_constraints),
+        Element::RadioGroup(group) => Some(&mut group.size_constraints),
+        Element::Dropdown(dropdown) => Some(&mut dropdown.size_constraints),
+        Element::Grid(grid) => Some(&mut grid.size_constraints),
+        Element::Modal(modal) => Some(&mut modal.size_constraints),
+        Element::Tabs(tabs) => Some(&mut tabs.size_constraints),
+        _ => None,
+    }
+}
+
+/// Remembers the result of [`arrange`] for the last tree structure and area.
+///
+/// Only what affects the layout is part of the structure, so changing
+/// the text of a label or the checked state of a checkbox keeps the cached result.
+/// The children of a scrolling panel are laid out at their natural size, so there
+/// a change to a text that changes the size the children need does not.
+#[derive(Debug, Clone, Default)]
+pub struct LayoutCache {
+    key: Option<(u64, Rect)>,
+    rects: HashMap<String, Rect>,
+    solves: usize,
+}
+
+impl LayoutCache {
+    pub fn new() -> Self {
+        LayoutCache::default()
+    }
+
+    /// Returns how many times the layout was actually computed.
+    pub fn solves(&self) -> usize {
+        self.solves
+    }
+}
+
+/// Computes the layout like [`arrange`], reusing the cached result
+/// while neither the tree structure nor the area changed.
+///
+/// ```
+/// use glyph::layout::{LayoutCache, Rect, layout_cached};
+///
+/// let mut ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Label status { text = "Loading" }
+///     }
+/// "#).unwrap();
+/// let mut cache = LayoutCache::new();
+/// let area = Rect::new(0, 0, 40, 10);
+/// layout_cached(&ui, area, &mut cache);
+///
+/// if let glyph::Element::Form(form) = &mut ui.root
+///     && let glyph::Element::Label(label) = &mut form.children[0]
+/// {
+///     label.text = "Done".to_string();
+/// }
+/// let rects = layout_cached(&ui, area, &mut cache);
+/// assert_eq!(rects["status"].width, 38);
+/// assert_eq!(cache.solves(), 1);
+///
+/// layout_cached(&ui, Rect::new(0, 0, 80, 24), &mut cache);
+/// assert_eq!(cache.solves(), 2);
+/// ```
+///
+/// Inside a scrolling panel, a longer text needs more room and is laid out again:
+///
+/// ```
+/// use glyph::layout::{LayoutCache, Rect, arrange, layout_cached, scroll_contents};
+///
+/// let mut ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Panel log {
+///             scroll = vertical
+///             height = 4
+///             @Label line { text = "one" }
+///         }
+///     }
+/// "#).unwrap();
+/// let mut cache = LayoutCache::new();
+/// let area = Rect::new(0, 0, 40, 10);
+/// layout_cached(&ui, area, &mut cache);
+///
+/// let glyph::Element::Label(label) = ui.find_mut("line").unwrap() else { unreachable!() };
+/// label.text = "one\ntwo\nthree\nfour\nfive".to_string();
+/// let rects = layout_cached(&ui, area, &mut cache).clone();
+/// assert_eq!(cache.solves(), 2);
+/// assert_eq!(rects, arrange(&ui, area));
+/// assert_eq!(scroll_contents(&ui, area)["log"].height, 5);
+/// ```
+pub fn layout_cached<'a>(ui: &UI, area: Rect, cache: &'a mut LayoutCache) -> &'a HashMap<String, Rect> {
+    let key = (structure_hash(ui), area);
+    if cache.key != Some(key) {
+        cache.rects = arrange(ui, area);
+        cache.key = Some(key);
+        cache.solves += 1;
+    }
+    &cache.rects
+}
+
+/// Hashes everything [`arrange`] reads from the tree.
+fn structure_hash(ui: &UI) -> u64 {
+    let mut hasher = StructureHasher(0xcbf2_9ce4_8422_2325);
+    hash_element(&ui.root, &mut hasher);
+    hasher.finish()
+}
+
+/// FNV-1a, which unlike the hasher of `std` is available without it.
+struct StructureHasher(u64);
+
+impl Hasher for StructureHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+fn hash_element(element: &Element, state: &mut StructureHasher) {
+    element.kind_name().hash(state);
+    element.name().hash(state);
+    constraints(element).hash(state);
+    match element {
+        Element::Form(form) => {
+            form.layout.hash(state);
+            hash_children(&form.children, state);
+        }
+        Element::Panel(panel) => {
+            panel.layout.hash(state);
+            panel.collapsed.hash(state);
+            panel.border.hash(state);
+            panel.scroll.hash(state);
+            // Scrolled content is as large as the children need, which depends on their texts.
+            if panel.scroll != ScrollMode::None {
+                min_children(&panel.children, &panel.layout).hash(state);
+            }
+            hash_children(&panel.children, state);
+        }
+        Element::Modal(modal) => {
+            modal.center.hash(state);
+            hash_children(&modal.children, state);
+        }
+        Element::CheckboxGroup(group) => {
+            group.layout.hash(state);
+            hash_children(&group.children, state);
+        }
+        Element::RadioGroup(group) => {
+            group.layout.hash(state);
+            for radio in &group.children {
+                (&radio.name, &radio.margins, &radio.label).hash(state);
+            }
+        }
+        Element::Grid(grid) => {
+            grid.show_header.hash(state);
+            // Auto columns are as wide as their title.
+            for column in &grid.columns {
+                (&column.name, &column.width, &column.title).hash(state);
+            }
+        }
+        Element::Tabs(tabs) => {
+            tabs.tab_position.hash(state);
+            for tab in &tabs.children {
+                (&tab.name, tab_label(tab)).hash(state);
+                hash_children(&tab.children, state);
+            }
+        }
+        Element::Tab(tab) => hash_children(&tab.children, state),
+        Element::MenuBar(menu_bar) => {
+            for item in &menu_bar.items {
+                (&item.name, &item.text).hash(state);
+            }
+        }
+        Element::StatusBar(status_bar) => hash_children(&status_bar.children, state),
+        _ => {}
+    }
+}
+
+fn hash_children(children: &[Element], state: &mut StructureHasher) {
+    children.len().hash(state);
+    for child in children {
+        hash_element(child, state);
+    }
+}
+
+fn arrange_element(element: &Element, area: Rect, rects: &mut HashMap<String, Rect>) {
+    match element {
+        Element::Form(form) => {
+            rects.insert(form.name.to_string(), area);
+            arrange_children(&form.children, area.inner(), &form.layout, rects);
+        }
+        Element::Panel(panel) => {
+            rects.insert(panel.name.to_string(), area);
+            if !panel.collapsed {
+                arrange_children(&panel.children, scroll_content(panel, area), &panel.layout, rects);
+            }
+        }
+        Element::Modal(modal) => {
+            rects.insert(modal.name.to_string(), area);
+            arrange_children(&modal.children, area.inner(), &Layout::TopToBottom, rects);
+        }
+        Element::CheckboxGroup(group) => {
+            rects.insert(group.name.to_string(), area);
+            arrange_children(&group.children, area, &group.layout, rects);
+        }
+        Element::RadioGroup(group) => {
+            rects.insert(group.name.to_string(), area);
+            arrange_radios(group, area, rects);
+        }
+        Element::Grid(grid) => {
+            rects.insert(grid.name.to_string(), area);
+            let inner = area.inner();
+            let header = if grid.show_header { inner.height.min(1) } else { 0 };
+            let mut x = inner.x;
+            for (column, width) in grid.columns.iter().zip(column_widths(grid, inner.width)) {
+                rects.insert(column.name.to_string(), Rect::new(x, inner.y + header, width, inner.height - header));
+                x += width;
+            }
+        }
+        Element::Tabs(tabs) => {
+            rects.insert(tabs.name.to_string(), area);
+            let content = tab_content_area(tabs, area);
+            for tab in &tabs.children {
+                arrange_tab(tab, content, rects);
+            }
+        }
+        Element::Tab(tab) => arrange_tab(tab, area, rects),
+        Element::MenuBar(menu_bar) => {
+            rects.insert(menu_bar.name.to_string(), area);
+            let mut x = area.x;
+            for item in &menu_bar.items {
+                let width = menu_item_width(item).min(area.x + area.width - x);
+                rects.insert(item.name.to_string(), Rect::new(x, area.y, width, area.height.min(1)));
+                x += width;
+            }
+        }
+        Element::MenuItem(item) => {
+            rects.insert(item.name.to_string(), area);
+        }
+        Element::StatusBar(status_bar) => {
+            rects.insert(status_bar.name.to_string(), area);
+            arrange_children(&status_bar.children, area, &Layout::LeftToRight, rects);
+        }
+        Element::Label(label) => {
+            rects.insert(label.name.to_string(), area);
+        }
+        Element::TextInput(input) => {
+            rects.insert(input.name.to_string(), area);
+        }
+        Element::Button(button) => {
+            rects.insert(button.name.to_string(), area);
+        }
+        Element::Checkbox(checkbox) => {
+            rects.insert(checkbox.name.to_string(), area);
+        }
+        Element::Radio(radio) => {
+            rects.insert(radio.name.to_string(), area);
+        }
+        Element::Dropdown(dropdown) => {
+            rects.insert(dropdown.name.to_string(), area);
+        }
+        Element::Column(column) => {
+            rects.insert(column.name.to_string(), area);
+        }
+        // Custom elements are opaque to the solver and are treated as leaves.
+        Element::Custom(custom) => {
+            rects.insert(custom.name.to_string(), area);
+        }
+    }
+}
+
+/// Places the radios of a group one per row, or side by side for a horizontal layout.
+///
+/// Side by side, every radio is as wide as its mark and label. Radios that do not fit
+/// into the group get whatever space is left, which may be none.
+fn arrange_radios(group: &RadioGroup, area: Rect, rects: &mut HashMap<String, Rect>) {
+    match group.layout {
+        Layout::LeftToRight | Layout::RightToLeft => {
+            let widths: Vec<SizeConstraint> = group
+                .children
+                .iter()
+                .map(|radio| radio_width(radio).saturating_add(horizontal_margins(&radio.margins)))
+                .map(SizeConstraint::Fixed)
+                .collect();
+            let mut x = 0;
+            for (radio, width) in group.children.iter().zip(split(area.width, &widths)) {
+                let left = match group.layout {
+                    Layout::RightToLeft => area.width - x - width,
+                    _ => x,
+                };
+                let slot = Rect::new(area.x + left, area.y, width, area.height.min(1));
+                rects.insert(radio.name.to_string(), slot.inset(&radio.margins));
+                x += width;
+            }
+        }
+        // Radios have no position of their own, so a free-form group stacks them as well.
+        Layout::TopToBottom | Layout::BottomToTop | Layout::FreeForm => {
+            for (row, radio) in group.children.iter().enumerate() {
+                let row = row as u32;
+                if row >= area.height {
+                    break;
+                }
+                let top = match group.layout {
+                    Layout::BottomToTop => area.height - row - 1,
+                    _ => row,
+                };
+                let slot = Rect::new(area.x, area.y + top, area.width, 1);
+                rects.insert(radio.name.to_string(), slot.inset(&radio.margins));
+            }
+        }
+    }
+}
+
+/// Returns the number of cells a radio needs for its mark and label.
+fn radio_width(radio: &Radio) -> u32 {
+    // The mark is drawn as `(*) ` in front of the label.
+    text::display_width(&radio.label) as u32 + 4
+}
+
+/// Returns the number of cells a menu item takes in its menu bar, its text padded by a space on each side.
+pub(crate) fn menu_item_width(item: &MenuItem) -> u32 {
+    text::display_width(&item.text) as u32 + 2
+}
+
+fn arrange_tab(tab: &Tab, area: Rect, rects: &mut HashMap<String, Rect>) {
+    rects.insert(tab.name.to_string(), area);
+    arrange_children(&tab.children, area, &Layout::TopToBottom, rects);
+}
+
+/// Returns the text shown in a tab's header.
+pub(crate) fn tab_label(tab: &Tab) -> String {
+    if tab.closable {
+        format!("{} x", tab.title)
+    } else {
+        tab.title.clone()
+    }
+}
+
+/// Returns the area below (or beside) the tab headers of a tab control.
+pub(crate) fn tab_content_area(tabs: &Tabs, area: Rect) -> Rect {
+    let header_width = tabs
+        .children
+        .iter()
+        .map(|tab| text::display_width(&tab_label(tab)) as u32 + 2)
+        .max()
+        .unwrap_or(0)
+        .min(area.width);
+    match tabs.tab_position {
+        TabPosition::Top => Rect::new(area.x, area.y + area.height.min(1), area.width, area.height.saturating_sub(1)),
+        TabPosition::Bottom => Rect::new(area.x, area.y, area.width, area.height.saturating_sub(1)),
+        TabPosition::Left => Rect::new(area.x + header_width, area.y, area.width - header_width, area.height),
+        TabPosition::Right => Rect::new(area.x, area.y, area.width - header_width, area.height),
+    }
+}
+
+/// Returns the area a panel's children are laid out in, inside its border if it has one.
+pub(crate) fn panel_content_area(panel: &Panel, area: Rect) -> Rect {
+    match panel.border {
+        BorderStyle::None => area,
+        _ => area.inner(),
+    }
+}
+
+/// Returns the area a panel's children are laid out in, grown to their natural size along
+/// the axes the panel scrolls. It starts at the top left of the panel's content area.
+fn scroll_content(panel: &Panel, area: Rect) -> Rect {
+    let content = panel_content_area(panel, area);
+    if panel.scroll == ScrollMode::None {
+        return content;
+    }
+    let (width, height) = min_children(&panel.children, &panel.layout);
+    let vertical = matches!(panel.scroll, ScrollMode::Vertical | ScrollMode::Both);
+    let horizontal = matches!(panel.scroll, ScrollMode::Horizontal | ScrollMode::Both);
+    // The content ends where cell positions do, however much the children need.
+    Rect::new(
+        content.x,
+        content.y,
+        if horizontal { content.width.max(width).min(u32::MAX - content.x) } else { content.width },
+        if vertical { content.height.max(height).min(u32::MAX - content.y) } else { content.height },
+    )
+}
+
+fn arrange_children(children: &[Element], area: Rect, layout: &Layout, rects: &mut HashMap<String, Rect>) {
+    // Menu bars take the top row and status bars the bottom row, the other children share the rest.
+    let (menu_bars, children): (Vec<&Element>, Vec<&Element>) =
+        children.iter().partition(|child| matches!(child, Element::MenuBar(_)));
+    let mut area = area;
+    for menu_bar in menu_bars {
+        let row = area.height.min(1);
+        arrange_element(menu_bar, Rect::new(area.x, area.y, area.width, row), rects);
+        area = Rect::new(area.x, area.y + row, area.width, area.height - row);
+    }
+    let (status_bars, children): (Vec<&Element>, Vec<&Element>) =
+        children.into_iter().partition(|child| matches!(child, Element::StatusBar(_)));
+    for status_bar in status_bars {
+        let row = area.height.min(1);
+        arrange_element(status_bar, Rect::new(area.x, area.y + area.height - row, area.width, row), rects);
+        area = Rect::new(area.x, area.y, area.width, area.height - row);
+    }
+    // Centered modals float above the other children instead of taking a slot.
+    let (modals, children): (Vec<&Element>, Vec<&Element>) = children
+        .into_iter()
+        .partition(|child| matches!(child, Element::Modal(modal) if modal.center));
+    for modal in modals {
+        if let Element::Modal(inner) = modal {
+            arrange_element(modal, centered(&inner.size_constraints, area), rects);
+        }
+    }
+    let constraints: Vec<(SizeConstraints, Margins)> = children.iter().map(|child| constraints(child)).collect();
+    match layout {
+        Layout::FreeForm => {
+            for (child, (size, margins)) in children.iter().zip(&constraints) {
+                let left = resolve(&size.left, area.width).unwrap_or(0).min(area.width);
+                let top = resolve(&size.top, area.height).unwrap_or(0).min(area.height);
+                let width = resolve_outer(&size.width, horizontal_margins(margins), area.width)
+                    .unwrap_or(area.width)
+                    .min(area.width - left);
+                let height = resolve_outer(&size.height, vertical_margins(margins), area.height)
+                    .unwrap_or(area.height)
+                    .min(area.height - top);
+                let slot = Rect::new(area.x + left, area.y + top, width, height);
+                arrange_element(child, slot.inset(margins), rects);
+            }
+        }
+        Layout::LeftToRight | Layout::RightToLeft => {
+            let widths: Vec<SizeConstraint> = constraints
+                .iter()
+                .map(|(size, margins)| outer(&size.width, horizontal_margins(margins)))
+                .collect();
+            let mut x = 0;
+            for ((child, (size, margins)), width) in children.iter().zip(&constraints).zip(split(area.width, &widths)) {
+                let height = resolve_outer(&size.height, vertical_margins(margins), area.height)
+                    .unwrap_or(area.height)
+                    .min(area.height);
+                let left = match layout {
+                    Layout::RightToLeft => area.width - x - width,
+                    _ => x,
+                };
+                let slot = Rect::new(area.x + left, area.y, width, height);
+                arrange_element(child, slot.inset(margins), rects);
+                x += width;
+            }
+        }
+        Layout::TopToBottom | Layout::BottomToTop => {
+            let heights: Vec<SizeConstraint> = constraints
+                .iter()
+                .map(|(size, margins)| outer(&size.height, vertical_margins(margins)))
+                .collect();
+            let mut y = 0;
+            for ((child, (size, margins)), height) in children.iter().zip(&constraints).zip(split(area.height, &heights)) {
+                let width = resolve_outer(&size.width, horizontal_margins(margins), area.width)
+                    .unwrap_or(area.width)
+                    .min(area.width);
+                let top = match layout {
+                    Layout::BottomToTop => area.height - y - height,
+                    _ => y,
+                };
+                let slot = Rect::new(area.x, area.y + top, width, height);
+                arrange_element(child, slot.inset(margins), rects);
+                y += height;
+            }
+        }
+    }
+}
+
+/// Returns a rect of the given size in the middle of `area`, `Auto` sizes fill the area.
+fn centered(size: &SizeConstraints, area: Rect) -> Rect {
+    let width = resolve(&size.width, area.width).unwrap_or(area.width).min(area.width);
+    let height = resolve(&size.height, area.height).unwrap_or(area.height).min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Splits `total` cells between the given sizes along one axis.
+/// Fixed sizes are allocated first and percentages of `total` second, each in order until
+/// the space runs out. The remainder is shared between `Fraction` and `Auto` sizes by weight,
+/// where `Auto` weighs `1fr`, and the last of them gets the cells lost to rounding,
+/// so the sizes add up to `total` whenever one of them is flexible.
+///
+/// `Auto` shares the remainder rather than taking what the fractions leave,
+/// which is nothing, so a child without a size does not vanish next to a `1fr` one.
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         layout = left-to-right
+///         @Panel fixed { width = 10 }
+///         @Panel one { width = 1fr }
+///         @Panel two { width = 2fr }
+///     }
+/// "#).unwrap();
+/// // The form's border leaves 40 cells, 30 of which remain after the fixed panel.
+/// let rects = glyph::layout::arrange(&ui, glyph::layout::Rect::new(0, 0, 42, 10));
+/// assert_eq!(rects["fixed"].width, 10);
+/// assert_eq!(rects["one"].width, 10);
+/// assert_eq!(rects["two"].width, 20);
+/// assert_eq!(rects["two"].x, 21);
+///
+/// // 30% of 43 cells rounds down to 12, the fraction takes the 11 cells left.
+/// let mixed = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         layout = left-to-right
+///         @Panel share { width = 30% }
+///         @Panel fixed { width = 20 }
+///         @Panel rest { width = 1fr }
+///     }
+/// "#).unwrap();
+/// let rects = glyph::layout::arrange(&mixed, glyph::layout::Rect::new(0, 0, 45, 10));
+/// let widths = [rects["share"].width, rects["fixed"].width, rects["rest"].width];
+/// assert_eq!(widths, [12, 20, 11]);
+/// assert_eq!(widths.iter().sum::<u32>(), 43);
+///
+/// // When the row is too narrow, the fixed size is kept and the percentage is cut.
+/// let rects = glyph::layout::arrange(&mixed, glyph::layout::Rect::new(0, 0, 25, 10));
+/// assert_eq!([rects["share"].width, rects["fixed"].width, rects["rest"].width], [3, 20, 0]);
+///
+/// // Of 41 cells, 19 remain after `10` and `30%`. The auto panel weighs `1fr` wherever it is written,
+/// // and `2fr` written last also gets the cell lost to rounding.
+/// let auto = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         layout = left-to-right
+///         @Panel auto { }
+///         @Panel fixed { width = 10 }
+///         @Panel share { width = 30% }
+///         @Panel wide { width = 2fr }
+///     }
+/// "#).unwrap();
+/// let rects = glyph::layout::arrange(&auto, glyph::layout::Rect::new(0, 0, 43, 10));
+/// let widths = ["auto", "fixed", "share", "wide"].map(|name| rects[name].width);
+/// assert_eq!(widths, [6, 10, 12, 13]);
+/// assert_eq!(widths.iter().sum::<u32>(), 41);
+/// ```
+fn split(total: u32, sizes: &[SizeConstraint]) -> Vec<u32> {
+    let mut remaining = total;
+    let mut result: Vec<Option<u32>> = vec![None; sizes.len()];
+    // A percentage written first must not take the cells of a fixed size written after it.
+    for fixed in [true, false] {
+        for (index, size) in sizes.iter().enumerate() {
+            if matches!(size, SizeConstraint::Fixed(_)) != fixed {
+                continue;
+            }
+            if let Some(cells) = resolve(size, total) {
+                let cells = cells.min(remaining);
+                remaining -= cells;
+                result[index] = Some(cells);
+            }
+        }
+    }
+    let weight = |size: &SizeConstraint| match size {
+        SizeConstraint::Fraction(weight) => *weight as u64,
+        _ => 1,
+    };
+    let flexible: Vec<usize> = (0..sizes.len()).filter(|index| result[*index].is_none()).collect();
+    let total_weight: u64 = flexible.iter().map(|index| weight(&sizes[*index])).sum();
+    let mut shared = 0;
+    for index in &flexible {
+        let share = (remaining as u64 * weight(&sizes[*index])).checked_div(total_weight).unwrap_or(0) as u32;
+        shared += share;
+        result[*index] = Some(share);
+    }
+    // The last flexible size with any weight absorbs the rounding remainder.
+    if let Some(last) = flexible.iter().rev().find(|index| weight(&sizes[**index]) > 0) {
+        result[*last] = result[*last].map(|cells| cells + (remaining - shared));
+    }
+    result.into_iter().map(|cells| cells.unwrap_or(0)).collect()
+}
+
+/// Resolves the width of every grid column.
+///
+/// Fixed and percentage columns are taken first, in order. `Auto` columns then get
+/// the display width of their widest cell, as far as the remaining space allows.
+/// A column's only cell is its title for now.
+fn column_widths(grid: &Grid, total: u32) -> Vec<u32> {
+    let mut remaining = total;
+    let mut widths: Vec<Option<u32>> = grid
+        .columns
+        .iter()
+        .map(|column| {
+            resolve(&column.width, total).map(|cells| {
+                let cells = cells.min(remaining);
+                remaining -= cells;
+                cells
+            })
+        })
+        .collect();
+    for (column, width) in grid.columns.iter().zip(widths.iter_mut()) {
+        if width.is_none() {
+            let cells = (text::display_width(&column.title) as u32).min(remaining);
+            remaining -= cells;
+            *width = Some(cells);
+        }
+    }
+    widths.into_iter().map(|cells| cells.unwrap_or(0)).collect()
+}
+
+/// Resolves a size constraint relative to the given parent size.
+/// Returns `None` for `Auto`.
+fn resolve(size: &SizeConstraint, total: u32) -> Option<u32> {
+    match size {
+        SizeConstraint::Auto => None,
+        SizeConstraint::Fixed(cells) => Some(*cells),
+        SizeConstraint::Percentage(percent) => Some((total as u64 * *percent as u64 / 100) as u32),
+        // Replaced by fixed sizes before the second pass of `arrange`.
+        SizeConstraint::RelativeTo { .. } => None,
+        // Shares of the remaining space, only `split` gives them cells.
+        SizeConstraint::Fraction(_) => None,
+        // Replaced by fixed sizes in `constraints`, only positions and grid columns get here.
+        SizeConstraint::MinContent | SizeConstraint::MaxContent => None,
+        // Replaced by the size chosen for the area at the start of `arrange`.
+        SizeConstraint::Responsive { .. } => None,
+    }
+}
+
+/// Resolves a size constraint to the size of the slot including the element's margins.
+fn resolve_outer(size: &SizeConstraint, margins: u32, total: u32) -> Option<u32> {
+    resolve(&outer(size, margins), total)
+}
+
+/// Returns the margins on the left and right of an element together, at most `u32::MAX`.
+fn horizontal_margins(margins: &Margins) -> u32 {
+    margins.left.saturating_add(margins.right)
+}
+
+/// Returns the margins above and below an element together, at most `u32::MAX`.
+fn vertical_margins(margins: &Margins) -> u32 {
+    margins.top.saturating_add(margins.bottom)
+}
+
+/// Adds margins to fixed sizes. Percentages and `Auto` already describe the outer slot.
+fn outer(size: &SizeConstraint, margins: u32) -> SizeConstraint {
+    match size {
+        SizeConstraint::Fixed(cells) => SizeConstraint::Fixed(cells.saturating_add(margins)),
+        other => other.clone(),
+    }
+}
+
+pub(crate) fn constraints(element: &Element) -> (SizeConstraints, Margins) {
+    let (size, margins) = match element {
+        // A collapsed panel shrinks to its title row.
+        Element::Panel(panel) if panel.collapsed => (
+            SizeConstraints {
+                height: SizeConstraint::Fixed(1),
+                ..panel.size_constraints.clone()
+            },
+            panel.margins.clone(),
+        ),
+        Element::Panel(panel) => (panel.size_constraints.clone(), panel.margins.clone()),
+        Element::Label(label) => (label.size_constraints.clone(), label.margins.clone()),
+        Element::TextInput(input) => (input.size_constraints.clone(), input.margins.clone()),
+        Element::Button(button) => (button.size_constraints.clone(), button.margins.clone()),
+        Element::Checkbox(checkbox) => (checkbox.size_constraints.clone(), checkbox.margins.clone()),
+        Element::CheckboxGroup(group) => (group.size_constraints.clone(), group.margins.clone()),
+        Element::RadioGroup(group) => (group.size_constraints.clone(), group.margins.clone()),
+        Element::Dropdown(dropdown) => (dropdown.size_constraints.clone(), dropdown.margins.clone()),
+        Element::Grid(grid) => (grid.size_constraints.clone(), grid.margins.clone()),
+        Element::Modal(modal) => (modal.size_constraints.clone(), Margins::default()),
+        Element::Tabs(tabs) => (tabs.size_constraints.clone(), tabs.margins.clone()),
+        Element::Radio(radio) => (SizeConstraints::default(), radio.margins.clone()),
+        Element::Column(column) => (
+            SizeConstraints {
+                width: column.width.clone(),
+                ..SizeConstraints::default()
+            },
+            Margins::default(),
+        ),
+        Element::Custom(custom) => (custom.implementation.size_constraints(), custom.implementation.margins()),
+        Element::Form(_) | Element::Tab(_) | Element::MenuBar(_) | Element::MenuItem(_) | Element::StatusBar(_) => {
+            (SizeConstraints::default(), Margins::default())
+        }
+    };
+    (intrinsic(element, size), margins)
+}
+
+/// Replaces a `min-content` or `max-content` width or height by the fixed size of the content.
+///
+/// The max-content size is the natural size of the content, with the text of labels unwrapped.
+/// Labels can wrap between words, so their min-content width is that of their longest word;
+/// other content does not wrap and is as wide at min-content as at max-content.
+/// Intrinsic positions mean nothing and stay automatic.
+fn intrinsic(element: &Element, mut size: SizeConstraints) -> SizeConstraints {
+    let is_intrinsic = |size: &SizeConstraint| matches!(size, SizeConstraint::MinContent | SizeConstraint::MaxContent);
+    // Measuring walks the whole subtree, so only elements asking for it are measured.
+    if !is_intrinsic(&size.width) && !is_intrinsic(&size.height) {
+        return size;
+    }
+    let (width, height) = natural_size(element);
+    let min_width = match element {
+        Element::Label(label) => {
+            label.plain_text().split_whitespace().map(text::display_width).max().unwrap_or(0) as u32
+        }
+        _ => width,
+    };
+    for (size, min, max) in [(&mut size.width, min_width, width), (&mut size.height, height, height)] {
+        match size {
+            SizeConstraint::MinContent => *size = SizeConstraint::Fixed(min),
+            SizeConstraint::MaxContent => *size = SizeConstraint::Fixed(max),
+            _ => {}
+        }
+    }
+    size
+}
@@ -1,22 +1,195 @@
-use anyhow::Result;
+//! Parses, lowers, lays out and renders `.gl` terminal UI layouts.
+//!
+//! Without the default `std` feature the crate only needs `alloc`: sources can still be parsed,
+//! lowered, validated, laid out and emitted, but nothing touches files or the environment.
+//! `@include` lines are then an error and `env"NAME"` values take [`lower::LowerOptions::env_default`].
+#![cfg_attr(not(feature = "std"), no_std)]
 
-mod parser;
+extern crate alloc;
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use crate::compat::*;
+
+pub mod access;
+pub mod canonical;
+pub mod emit;
+pub mod error;
+pub mod focus;
+pub mod id;
+pub mod interpolate;
+pub mod layout;
+pub mod lower;
+pub mod parser;
+/// Re-exports what typical code working with layouts needs, for `use glyph::prelude::*;`.
+///
+/// Elements are built as structs, filling in the rest of the fields from `Default`:
+///
+/// ```
+/// use glyph::prelude::*;
+///
+/// let ui = UI {
+///     language: Language::Ratatui,
+///     root: Element::Form(Form {
+///         name: "main".into(),
+///         layout: Layout::LeftToRight,
+///         children: vec![Element::Button(Button {
+///             name: "ok".into(),
+///             text: "OK".to_string(),
+///             size_constraints: SizeConstraints { width: SizeConstraint::Fixed(6), ..Default::default() },
+///             ..Default::default()
+///         })],
+///         ..Default::default()
+///     }),
+///     assertions: Vec::new(),
+/// };
+/// assert!(validate_all(&ui).is_empty());
+/// assert_eq!(arrange(&ui, Rect::new(0, 0, 20, 5))["ok"].width, 6);
+///
+/// let parsed = lower(&parse(&to_str(&ui)).unwrap()).unwrap();
+/// assert_eq!(parsed.root.as_form().unwrap().children[0].as_button().unwrap().text, "OK");
+/// ```
+pub mod prelude;
+pub mod render;
+pub mod text;
+pub mod theme;
+pub mod token;
+/// Reports the parse, lower and validate stages with their element counts, depth and timing.
+///
+/// The hooks are compiled in with the `tracing` feature, without it the stages are not measured.
+pub mod trace;
+pub mod validate;
+
+/// Provides what the `std` prelude would, so the modules read the same with and without `std`.
+pub(crate) mod compat {
+    pub(crate) use alloc::boxed::Box;
+    pub(crate) use alloc::string::{String, ToString};
+    pub(crate) use alloc::vec::Vec;
+    pub(crate) use alloc::{format, vec};
+    #[cfg(not(feature = "std"))]
+    pub(crate) use hashbrown::{HashMap, HashSet};
+    #[cfg(feature = "std")]
+    pub(crate) use std::collections::{HashMap, HashSet};
+}
 
 pub mod types;
+pub use error::GlyphError;
 pub use types::*;
 
+type Result<T> = core::result::Result<T, GlyphError>;
+
 pub fn from_str(s: &str) -> Result<UI> {
-    return todo!()
+    lower::lower(&parser::parse(s)?)
 }
 
+/// Parses and lowers a `.gl` source like [`from_str`], so sources can be turned into a UI with `parse`.
+///
+/// Syntax and lowering errors come back as the [`GlyphError`] variants of [`from_str`].
+///
+/// ```
+/// let ui: glyph::UI = "@language ratatui @Form f { @Button ok { text = \"OK\" } }".parse().unwrap();
+/// assert_eq!(ui.root.as_form().unwrap().children[0].as_button().unwrap().text, "OK");
+/// assert!("@language ratatui @Form f { title = }".parse::<glyph::UI>().is_err());
+/// assert!("@language ratatui @Button b { }".parse::<glyph::UI>().is_err());
+/// ```
+impl core::str::FromStr for UI {
+    type Err = GlyphError;
+
+    fn from_str(s: &str) -> Result<UI> {
+        crate::from_str(s)
+    }
+}
+
+/// Reads and lowers a `.gl` file.
+/// Relative asset paths of custom elements resolve against the file's directory.
+///
+/// An `@include "file.gl"` line among the children of an element is replaced by the root
+/// element of that file, whose path resolves against the including file's directory.
+/// The included file may leave out `@language` to use the including file's;
+/// a different `@language` is an error naming both files.
+///
+/// ```
+/// let dir = std::env::temp_dir().join("glyph-doc-include");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// std::fs::write(dir.join("buttons.gl"), "@language ratatui\n@Panel buttons { @Button ok { text = \"OK\" } }").unwrap();
+/// std::fs::write(dir.join("label.gl"), "@Label hint { text = \"Hi\" }").unwrap();
+/// std::fs::write(dir.join("other.gl"), "@language web\n@Label web { text = \"Hi\" }").unwrap();
+///
+/// let main = dir.join("main.gl");
+/// std::fs::write(&main, "@language ratatui\n@Form main {\n    @include \"buttons.gl\"\n    @include \"label.gl\"\n}").unwrap();
+/// let ui = glyph::from_file(main.to_str().unwrap()).unwrap();
+/// let form = ui.root.as_form().unwrap();
+/// assert_eq!(&*form.children[0].as_panel().unwrap().name, "buttons");
+/// assert_eq!(&*form.children[1].as_label().unwrap().name, "hint");
+///
+/// let conflicting = dir.join("conflicting.gl");
+/// std::fs::write(&conflicting, "@language ratatui\n@Form main { @include \"other.gl\" }").unwrap();
+/// let error = glyph::from_file(conflicting.to_str().unwrap()).unwrap_err().to_string();
+/// assert!(error.contains("other.gl` is written for `@language web`"), "{error}");
+/// assert!(error.contains("conflicting.gl`, which is written for `@language ratatui`"), "{error}");
+/// ```
+#[cfg(feature = "std")]
 pub fn from_file(path: &str) -> Result<UI> {
-    return todo!()
+    load_file(Path::new(path), false)
+}
+
+#[cfg(feature = "std")]
+fn load_file(path: &Path, fragment: bool) -> Result<UI> {
+    let src = std::fs::read_to_string(path)?;
+    let options = lower::LowerOptions {
+        base_dir: path.parent().map(|dir| dir.to_path_buf()),
+        path: Some(path.into()),
+        fragment,
+        ..lower::LowerOptions::default()
+    };
+    let document = parser::parse_with_options(&src, &options.parse_options)?;
+    lower::lower_with_options(&document, &options)
+}
+
+/// Reads every `.gl` file of a directory as a component, keyed by its file stem.
+///
+/// Each file is read like [`from_file`], so includes resolve against the directory,
+/// but as a fragment whose root may be any element, see [`lower::LowerOptions::fragment`].
+/// A file that fails to load keeps its error in its entry, the other files load regardless.
+/// Only a directory that can not be listed fails as a whole.
+///
+/// ```
+/// let dir = std::env::temp_dir().join("glyph-doc-library");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// std::fs::write(dir.join("ok_button.gl"), "@language ratatui\n@Button ok { text = \"OK\" }").unwrap();
+/// std::fs::write(dir.join("broken.gl"), "@language ratatui\n@Panel broken { title = }").unwrap();
+/// std::fs::write(dir.join("notes.txt"), "not a layout").unwrap();
+///
+/// let library = glyph::load_library(&dir).unwrap();
+/// assert_eq!(library.len(), 2);
+/// let button = library["ok_button"].as_ref().unwrap();
+/// assert_eq!(button.root.as_button().unwrap().text, "OK");
+/// let error = library["broken"].as_ref().unwrap_err();
+/// assert!(error.to_string().starts_with("failed to parse layout"), "{error}");
+/// ```
+#[cfg(feature = "std")]
+pub fn load_library(dir: &Path) -> Result<HashMap<String, Result<UI>>> {
+    let mut components = HashMap::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().is_none_or(|extension| extension != "gl") {
+            continue;
+        }
+        let Some(stem) = path.file_stem() else {
+            continue;
+        };
+        components.insert(stem.to_string_lossy().into_owned(), load_file(&path, true));
+    }
+    Ok(components)
 }
 
 pub fn to_str(element: &UI) -> String {
-    return todo!()
+    emit::to_source(element)
 }
 
+#[cfg(feature = "std")]
 pub fn to_file(element: &UI, path: &str) -> Result<()> {
-    return todo!()
+    std::fs::write(path, to_str(element))?;
+    Ok(())
 }
\ No newline at end of file
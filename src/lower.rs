@@ -0,0 +1,1686 @@
+use alloc::borrow::Cow;
+use core::cell::RefCell;
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::path::{Component, Path, PathBuf};
+
+use crate::compat::*;
+use crate::parser::{self, Document, Property, Value};
+use crate::types::*;
+use crate::{GlyphError, Result, layout, text, trace, validate};
+
+/// Returns an unlocated [`GlyphError::Lower`] with a `format!` message,
+/// [`lower_element`] locates it at the element it was found on.
+macro_rules! bail {
+    ($($message:tt)*) => {
+        return Err(GlyphError::Lower { message: format!($($message)*), span: None })
+    };
+}
+
+/// Converts a parsed document into the typed UI tree.
+///
+/// The root element of the document must be a `Form`, unless [`LowerOptions::fragment`] is set.
+/// Properties that are not understood by an element kind are kept in its `extra` field.
+pub fn lower(document: &Document) -> Result<UI> {
+    lower_with_options(document, &LowerOptions::default())
+}
+
+/// Represents settings for [`lower_with_options`].
+///
+/// # Fields
+///
+/// - `deny_unknown_kinds` (`bool`) - Reject unknown element kinds instead of lowering them
+///   to [`Element::Custom`] with an [`UnknownElement`] implementation.
+/// - `base_dir` (`Option<PathBuf>`) - Directory relative asset paths are resolved against,
+///   usually the directory of the `.gl` file. Passed on to every [`UnknownElement`].
+///   Only with the `std` feature, like `path`.
+/// - `flags` (`HashMap<String, String>`) - Values the conditions of `@if` blocks are
+///   matched against, see [`lower_with_flags`].
+/// - `env_default` (`Option<String>`) - Value used for `env"NAME"` references to unset
+///   variables. `None` rejects them with an error.
+/// - `path` (`Option<PathBuf>`) - The `.gl` file being lowered, named in errors about
+///   the files it includes.
+/// - `fragment` (`bool`) - Accept any element as the root, for files holding a reusable
+///   piece of a layout. See [`wrap_in_form`] to turn such a piece into a complete UI.
+/// - `keep_source` (`bool`) - Keep the text every element was written as in its `source` field,
+///   for tools such as language servers. Needs the source text, so only [`lower_source`] accepts it.
+///   Elements pulled in with `@include` keep the `@include` line.
+/// - `parse_options` ([`parser::ParseOptions`]) - Settings the files pulled in with `@include`
+///   are parsed with, and the source of [`lower_source`]. Pass those the document was parsed with,
+///   so a `tab_width` applies to the d-strings of included files too.
+///
+/// ```
+/// use glyph::lower::{LowerOptions, lower_with_options};
+///
+/// let document = glyph::parser::parse("@language ratatui\n@Form f { @Widget w { } }").unwrap();
+///
+/// let ui = lower_with_options(&document, &LowerOptions::default()).unwrap();
+/// let form = ui.root.as_form().unwrap();
+/// assert_eq!(&*form.children[0].as_custom().unwrap().name, "w");
+///
+/// let strict = LowerOptions { deny_unknown_kinds: true, ..LowerOptions::default() };
+/// assert!(lower_with_options(&document, &strict).is_err());
+///
+/// #[cfg(feature = "std")]
+/// {
+///     use glyph::parser::{ParseOptions, parse_with_options};
+///
+///     let dir = std::env::temp_dir().join("glyph-doc-include-tabs");
+///     std::fs::create_dir_all(&dir).unwrap();
+///     std::fs::write(dir.join("notes.gl"), "@Label notes { text = d\"\n\tone\n    two\n\t\" }").unwrap();
+///     let src = "@language ratatui\n@Form main {\n    @include \"notes.gl\"\n}";
+///     let text = |parse_options: ParseOptions| {
+///         let document = parse_with_options(src, &parse_options).unwrap();
+///         let options = LowerOptions { base_dir: Some(dir.clone()), parse_options, ..LowerOptions::default() };
+///         let ui = lower_with_options(&document, &options).unwrap();
+///         ui.root.as_form().unwrap().children[0].as_label().unwrap().text.clone()
+///     };
+///     assert_eq!(text(ParseOptions::default()), "\none\ntwo\n");
+///     assert_eq!(text(ParseOptions { tab_width: 8, ..ParseOptions::default() }), "\n    one\ntwo\n    ");
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LowerOptions {
+    pub deny_unknown_kinds: bool,
+    #[cfg(feature = "std")]
+    pub base_dir: Option<PathBuf>,
+    pub flags: HashMap<String, String>,
+    pub env_default: Option<String>,
+    #[cfg(feature = "std")]
+    pub path: Option<PathBuf>,
+    pub fragment: bool,
+    pub keep_source: bool,
+    pub parse_options: parser::ParseOptions,
+}
+
+/// Represents one run of the lowering, with the source text when elements keep theirs.
+struct Lowering<'a> {
+    options: &'a LowerOptions,
+    src: Option<&'a str>,
+}
+
+impl Lowering<'_> {
+    /// Returns the text `element` was written as, when the options keep it.
+    fn source(&self, element: &parser::Element) -> Option<String> {
+        self.src.and_then(|src| src.get(element.span.clone())).map(str::to_string)
+    }
+}
+
+/// Converts a parsed document into the typed UI tree like [`lower`],
+/// keeping the children of an `@if` block only when all of its conditions match.
+///
+/// A condition `key = "value"` matches when `flags` maps `key` to `value`.
+/// Blocks nest, so an inner block needs its own conditions and those of the outer one.
+///
+/// ```
+/// use glyph::lower::{lower, lower_with_flags};
+///
+/// let document = glyph::parser::parse(r#"
+///     @language ratatui
+///     @Form main {
+///         @Button save { text = "Save" }
+///         @if feature = "pro" {
+///             @Button upgrade { text = "Upgrade" }
+///         }
+///     }
+/// "#).unwrap();
+///
+/// let flags = [("feature".to_string(), "pro".to_string())].into_iter().collect();
+/// let pro = lower_with_flags(&document, &flags).unwrap();
+/// assert_eq!(pro.root.as_form().unwrap().children.len(), 2);
+///
+/// let basic = lower(&document).unwrap();
+/// assert_eq!(basic.root.as_form().unwrap().children.len(), 1);
+/// ```
+pub fn lower_with_flags(document: &Document, flags: &HashMap<String, String>) -> Result<UI> {
+    let options = LowerOptions {
+        flags: flags.clone(),
+        ..LowerOptions::default()
+    };
+    lower_with_options(document, &options)
+}
+
+/// Resolves a path written in a layout against the layout's directory.
+///
+/// Absolute paths are returned unchanged. Relative paths are joined to `base`,
+/// then `.` and `..` components are resolved lexically, without touching the file system.
+/// A relative path may therefore point outside of `base`; callers loading layouts
+/// they do not trust should check the result with [`Path::starts_with`].
+///
+/// ```
+/// use std::path::{Path, PathBuf};
+/// use glyph::lower::resolve_path;
+///
+/// let base = Path::new("/app/layouts");
+/// assert_eq!(resolve_path(base, "img/logo.png"), PathBuf::from("/app/layouts/img/logo.png"));
+/// assert_eq!(resolve_path(base, "./../shared/icon.png"), PathBuf::from("/app/shared/icon.png"));
+/// assert_eq!(resolve_path(base, "/usr/share/logo.png"), PathBuf::from("/usr/share/logo.png"));
+/// ```
+#[cfg(feature = "std")]
+pub fn resolve_path(base: &Path, raw: &str) -> PathBuf {
+    let raw = Path::new(raw);
+    if raw.is_absolute() {
+        return raw.to_path_buf();
+    }
+    let mut resolved = base.to_path_buf();
+    for component in raw.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
+/// Converts a parsed document into the typed UI tree like [`lower`].
+///
+/// `env"NAME"` values are replaced by the environment variable `NAME` first,
+/// see [`LowerOptions::env_default`] for unset variables. Without the `std` feature every variable is unset.
+///
+/// ```
+/// use glyph::lower::{LowerOptions, lower, lower_with_options};
+///
+/// // SAFETY: no other thread of this test reads or writes the environment.
+/// unsafe { std::env::set_var("GLYPH_DOC_BUILD_TAG", "v1.2.3") };
+/// let document = glyph::parser::parse(r#"
+///     @language ratatui
+///     @Form main { title = env"GLYPH_DOC_BUILD_TAG" }
+/// "#).unwrap();
+/// #[cfg(feature = "std")]
+/// assert_eq!(lower(&document).unwrap().root.as_form().unwrap().title, "v1.2.3");
+///
+/// let unset = glyph::parser::parse("@language ratatui\n@Form main { title = env\"GLYPH_DOC_UNSET\" }").unwrap();
+/// assert!(lower(&unset).is_err());
+/// let options = LowerOptions { env_default: Some("dev".to_string()), ..LowerOptions::default() };
+/// assert_eq!(lower_with_options(&unset, &options).unwrap().root.as_form().unwrap().title, "dev");
+/// ```
+pub fn lower_with_options(document: &Document, options: &LowerOptions) -> Result<UI> {
+    if options.keep_source {
+        bail!("`keep_source` needs the source text, lower it with `lower_source`");
+    }
+    let lower = || lower_document(document, &Lowering { options, src: None });
+    trace::stage("lower", lower, |result| result.as_ref().map_or((0, 0), trace::ui_shape))
+}
+
+/// Parses and lowers `src` like [`lower_with_options`], the source text is what
+/// [`LowerOptions::keep_source`] keeps for every element.
+///
+/// ```
+/// use glyph::lower::{LowerOptions, lower_source};
+///
+/// let src = "@language ratatui\n@Form main {\n    @Panel box {\n        @Button ok { text = \"OK\" }\n    }\n}";
+/// let options = LowerOptions { keep_source: true, ..LowerOptions::default() };
+/// let ui = lower_source(src, &options).unwrap();
+/// let panel = ui.root.as_form().unwrap().children[0].as_panel().unwrap();
+/// assert_eq!(panel.children[0].as_button().unwrap().source.as_deref(), Some("@Button ok { text = \"OK\" }"));
+/// assert_eq!(panel.source.as_deref(), Some(&src[35..src.len() - 2]));
+/// assert_eq!(ui.root.as_form().unwrap().source.as_deref(), Some(&src[18..]));
+///
+/// let plain = lower_source(src, &LowerOptions::default()).unwrap();
+/// assert_eq!(plain.root.as_form().unwrap().source, None);
+/// ```
+pub fn lower_source(src: &str, options: &LowerOptions) -> Result<UI> {
+    let document = parser::parse_with_options(src, &options.parse_options)?;
+    let lowering = Lowering { options, src: options.keep_source.then_some(src) };
+    let lower = || lower_document(&document, &lowering);
+    trace::stage("lower", lower, |result| result.as_ref().map_or((0, 0), trace::ui_shape))
+}
+
+fn lower_document(document: &Document, lowering: &Lowering) -> Result<UI> {
+    let options = lowering.options;
+    let language = lower_language(&document.language)?;
+    let mut source_root = Cow::Borrowed(&document.root);
+    #[cfg(feature = "std")]
+    if has_include(&source_root) {
+        let dir = options.base_dir.as_deref().unwrap_or(Path::new(""));
+        let mut stack: Vec<PathBuf> = options.path.iter().cloned().collect();
+        resolve_includes(source_root.to_mut(), &document.language, dir, &options.parse_options, &mut stack)?;
+    }
+    #[cfg(not(feature = "std"))]
+    if has_include(&source_root) {
+        bail!("`@include` needs the `std` feature to read the included file");
+    }
+    if has_env(&source_root) {
+        resolve_env(source_root.to_mut(), options)?;
+    }
+    if !document.aliases.is_empty() {
+        let kinds = alias_kinds(&document.aliases)?;
+        resolve_aliases(source_root.to_mut(), &kinds);
+    }
+    let root = lower_element(&source_root, lowering)?;
+    if !options.fragment && !matches!(root, Element::Form(_)) {
+        return Err(GlyphError::Lower {
+            message: format!(
+                "root element `{}` must be a Form, found {}",
+                document.root.name, document.root.kind
+            ),
+            span: Some(document.root.span.clone()),
+        });
+    }
+    check_relative_sizes(&root)?;
+    let assertions = document.assertions.iter().map(lower_assertion).collect::<Result<_>>()?;
+    Ok(UI { language, root, assertions })
+}
+
+/// Wraps an element into a `Form` with the given title, as the root of a Ratatui UI.
+///
+/// The form is named after the element with a `_form` suffix and has the default layout.
+///
+/// ```
+/// use glyph::lower::{LowerOptions, lower, lower_with_options, wrap_in_form};
+///
+/// let document = glyph::parser::parse(r#"
+///     @language ratatui
+///     @Panel settings {
+///         @Checkbox dark { label = "Dark mode" }
+///     }
+/// "#).unwrap();
+/// assert!(lower(&document).is_err());
+/// let fragment = lower_with_options(&document, &LowerOptions { fragment: true, ..LowerOptions::default() }).unwrap();
+/// let ui = wrap_in_form(fragment.root, "Settings");
+/// let form = ui.root.as_form().unwrap();
+/// assert_eq!(&*form.name, "settings_form");
+/// assert_eq!(form.title, "Settings");
+/// let panel = form.children[0].as_panel().unwrap();
+/// assert_eq!(panel.children[0].as_checkbox().unwrap().label, "Dark mode");
+/// ```
+pub fn wrap_in_form(element: Element, title: impl Into<String>) -> UI {
+    let form = Form {
+        name: Name::from(format!("{}_form", element.name()).as_str()),
+        title: title.into(),
+        children: vec![element],
+        ..Form::default()
+    };
+    UI { language: Language::Ratatui, root: Element::Form(form), assertions: Vec::new() }
+}
+
+/// Reads an `@assert` directive, which names the `element` and any of the size bounds.
+fn lower_assertion(assertion: &parser::Assertion) -> Result<Assertion> {
+    let located = |message: String| GlyphError::Lower { message, span: Some(assertion.span.clone()) };
+    let mut lowered = Assertion::default();
+    let mut element = None;
+    for property in &assertion.properties {
+        let bound = match property.name.as_str() {
+            "element" => {
+                match &property.value {
+                    Value::String(name) => element = Some(name.to_string()),
+                    Value::Identifier(name) => element = Some(name.clone()),
+                    other => {
+                        return Err(located(format!("`@assert` expects an element name, found {}", describe(other))));
+                    }
+                }
+                continue;
+            }
+            "min-width" => &mut lowered.min_width,
+            "max-width" => &mut lowered.max_width,
+            "min-height" => &mut lowered.min_height,
+            "max-height" => &mut lowered.max_height,
+            other => return Err(located(format!("unknown `@assert` check `{other}`"))),
+        };
+        match property.value {
+            Value::Number(n) if n >= 0.0 && n % 1.0 == 0.0 => *bound = Some(n as u32),
+            ref other => {
+                return Err(located(format!(
+                    "`@assert` check `{}` expects a whole number of cells, found {}",
+                    property.name,
+                    describe(other)
+                )));
+            }
+        }
+    }
+    lowered.element = element.ok_or_else(|| located("`@assert` is missing `element`".to_string()))?;
+    Ok(lowered)
+}
+
+fn lower_language(language: &parser::Language) -> Result<Language> {
+    if language.name != "language" {
+        bail!("unknown directive `@{}`, expected `@language`", language.name);
+    }
+    Ok(match (language.value.as_str(), &language.url) {
+        ("ratatui", None) => Language::Ratatui,
+        (name, url) => Language::AnyOther {
+            name: name.to_string(),
+            url: url.clone().unwrap_or_default(),
+        },
+    })
+}
+
+/// Lowers a single parsed element and its children with the default [`LowerOptions`].
+///
+/// This is the conversion [`lower`] applies to the root, without the requirement
+/// that it is a `Form`.
+///
+/// ```
+/// let document = glyph::parser::parse("@language ratatui\n@Button ok { text = \"OK\" }").unwrap();
+/// let element = glyph::Element::try_from(&document.root).unwrap();
+/// assert_eq!(element.as_button().unwrap().text, "OK");
+/// ```
+impl TryFrom<&parser::Element<'_>> for Element {
+    type Error = GlyphError;
+
+    fn try_from(element: &parser::Element<'_>) -> Result<Self> {
+        lower_element(element, &Lowering { options: &LowerOptions::default(), src: None })
+    }
+}
+
+/// Attaches the span of `element` to a lowering error that is not located yet.
+fn locate(error: GlyphError, element: &parser::Element) -> GlyphError {
+    match error {
+        GlyphError::Lower { message, span: None } => GlyphError::Lower { message, span: Some(element.span.clone()) },
+        error => error,
+    }
+}
+
+fn lower_element(element: &parser::Element, lowering: &Lowering) -> Result<Element> {
+    lower_kind(element, lowering).map_err(|error| locate(error, element))
+}
+
+fn lower_kind(element: &parser::Element, lowering: &Lowering) -> Result<Element> {
+    let props = Properties::new(element);
+    let children = expand_conditionals(props.children()?, &lowering.options.flags);
+    // A form has one menu bar and one status bar at most, so they go without a name.
+    let name = match element.name.as_str() {
+        "" if element.kind == "MenuBar" => Name::from("menu"),
+        "" if element.kind == "StatusBar" => Name::from("status"),
+        "" if !matches!(element.kind.as_str(), "if" | "include") => bail!("element `@{}` needs a name", element.kind),
+        name => Name::from(name),
+    };
+    let lowered = match element.kind.as_str() {
+        "Form" => {
+            let children = lower_children(&children, lowering)?;
+            let focus_order = props.string_list("focus-order")?;
+            check_focus_order(&name, &children, &focus_order)?;
+            Element::Form(Form {
+                name,
+                id: String::new(),
+                role: props.role()?,
+                title: props.string("title")?,
+                layout: props.layout("layout")?,
+                children,
+                focus_order,
+                events: props.events()?,
+                doc: element.doc.clone(),
+                source: lowering.source(element),
+                extra: props.extra(),
+            })
+        }
+        "Panel" => Element::Panel(Panel {
+            name,
+            id: String::new(),
+            role: props.role()?,
+            title: props.string("title")?,
+            title_align: props.alignment("title-align")?,
+            title_brackets: props.bool("title-brackets")?,
+            layout: props.layout("layout")?,
+            children: lower_children(&children, lowering)?,
+            size_constraints: props.size_constraints()?,
+            margins: props.margins()?,
+            collapsible: props.bool("collapsible")?,
+            collapsed: props.bool("collapsed")?,
+            border: props.border_style("border")?,
+            scroll: props.scroll_mode("scroll")?,
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "Label" => {
+            let text = props.string("text")?;
+            let markup = props.bool("markup")?;
+            Element::Label(Label {
+                name,
+                id: String::new(),
+                role: props.role()?,
+                spans: if markup { text::parse_markup(&text) } else { Vec::new() },
+                text,
+                markup,
+                word_wrap: props.bool("word-wrap")?,
+                align: props.alignment("align")?,
+                size_constraints: props.size_constraints()?,
+                margins: props.margins()?,
+                events: props.events()?,
+                doc: element.doc.clone(),
+                source: lowering.source(element),
+                extra: props.extra(),
+            })
+        }
+        "TextInput" => Element::TextInput(TextInput {
+            name,
+            id: String::new(),
+            role: props.role()?,
+            placeholder: props.string("placeholder")?,
+            default_text: props.string("text")?,
+            size_constraints: props.size_constraints()?,
+            margins: props.margins()?,
+            read_only: props.bool("read-only")?,
+            disabled: props.bool("disabled")?,
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "Button" => Element::Button(Button {
+            name,
+            id: String::new(),
+            role: props.role()?,
+            text: props.string("text")?,
+            size_constraints: props.size_constraints()?,
+            margins: props.margins()?,
+            disabled: props.bool("disabled")?,
+            variant: props.button_variant("variant")?,
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "Checkbox" => Element::Checkbox(Checkbox {
+            name,
+            id: String::new(),
+            role: props.role()?,
+            label: props.string("label")?,
+            checked: props.bool("checked")?,
+            size_constraints: props.size_constraints()?,
+            margins: props.margins()?,
+            disabled: props.bool("disabled")?,
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "CheckboxGroup" => Element::CheckboxGroup(CheckboxGroup {
+            name,
+            id: String::new(),
+            role: props.role()?,
+            children: children
+                .iter()
+                .map(|child| match lower_element(child, lowering)? {
+                    checkbox @ Element::Checkbox(_) => Ok(checkbox),
+                    _ => Err(unexpected_child(element, child, "Checkbox")),
+                })
+                .collect::<Result<_>>()?,
+            min_checked: props.u32("min-checked")?,
+            max_checked: props.u32("max-checked")?,
+            layout: props.layout("layout")?,
+            size_constraints: props.size_constraints()?,
+            margins: props.margins()?,
+            disabled: props.bool("disabled")?,
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "RadioGroup" => Element::RadioGroup(RadioGroup {
+            name,
+            id: String::new(),
+            role: props.role()?,
+            children: children
+                .iter()
+                .map(|child| match lower_element(child, lowering)? {
+                    Element::Radio(radio) => Ok(radio),
+                    _ => Err(unexpected_child(element, child, "Radio")),
+                })
+                .collect::<Result<_>>()?,
+            selected_radio: props.string("selected")?,
+            layout: props.layout("layout")?,
+            size_constraints: props.size_constraints()?,
+            margins: props.margins()?,
+            disabled: props.bool("disabled")?,
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "Radio" => Element::Radio(Radio {
+            value: props.value_string("value")?.unwrap_or_else(|| name.to_string()),
+            name,
+            id: String::new(),
+            role: props.role()?,
+            label: props.string("label")?,
+            margins: props.margins()?,
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "Dropdown" => {
+            let multi = props.bool("multi")?;
+            Element::Dropdown(Dropdown {
+                name,
+                id: String::new(),
+                role: props.role()?,
+                options: lower_options(element, &children, lowering)?,
+                multi,
+                selected_option: if multi { String::new() } else { props.string("selected")? },
+                selected_options: if multi { props.string_list("selected")? } else { Vec::new() },
+                size_constraints: props.size_constraints()?,
+                margins: props.margins()?,
+                disabled: props.bool("disabled")?,
+                events: props.events()?,
+                doc: element.doc.clone(),
+                source: lowering.source(element),
+                extra: props.extra(),
+            })
+        }
+        "Grid" => Element::Grid(Grid {
+            name,
+            id: String::new(),
+            role: props.role()?,
+            columns: children
+                .iter()
+                .map(|child| match lower_element(child, lowering)? {
+                    Element::Column(column) => Ok(column),
+                    _ => Err(unexpected_child(element, child, "Column")),
+                })
+                .collect::<Result<_>>()?,
+            size_constraints: props.size_constraints()?,
+            margins: props.margins()?,
+            show_header: props.bool_or("show-header", true)?,
+            frozen: props.u32("frozen")?.unwrap_or(0),
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "Column" => Element::Column(Column {
+            name,
+            id: String::new(),
+            role: props.role()?,
+            title: props.string("title")?,
+            width: props.size("width")?,
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "Modal" => Element::Modal(Modal {
+            name,
+            id: String::new(),
+            role: props.role()?,
+            title: props.string("title")?,
+            children: lower_children(&children, lowering)?,
+            size_constraints: props.size_constraints()?,
+            center: props.bool_or("center", true)?,
+            backdrop: props.bool("backdrop")?,
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "Tabs" => {
+            let tabs: Vec<Tab> = children
+                .iter()
+                .map(|child| match lower_element(child, lowering)? {
+                    Element::Tab(tab) => Ok(tab),
+                    _ => Err(unexpected_child(element, child, "Tab")),
+                })
+                .collect::<Result<_>>()?;
+            let order = props.string_list("order")?;
+            check_tab_order(&name, &tabs, &order)?;
+            Element::Tabs(Tabs {
+                name,
+                id: String::new(),
+                role: props.role()?,
+                children: tabs,
+                order,
+                selected_tab: props.string("selected")?,
+                tab_position: props.tab_position("tab-position")?,
+                size_constraints: props.size_constraints()?,
+                margins: props.margins()?,
+                events: props.events()?,
+                doc: element.doc.clone(),
+                source: lowering.source(element),
+                extra: props.extra(),
+            })
+        }
+        "Tab" => Element::Tab(Tab {
+            name,
+            id: String::new(),
+            role: props.role()?,
+            title: props.string("title")?,
+            children: lower_children(&children, lowering)?,
+            closable: props.bool("closable")?,
+            order: props.u32("order")?,
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "MenuBar" => Element::MenuBar(MenuBar {
+            name,
+            id: String::new(),
+            role: props.role()?,
+            items: lower_menu_items(element, &children, lowering)?,
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "MenuItem" => Element::MenuItem(MenuItem {
+            name,
+            id: String::new(),
+            role: props.role()?,
+            text: props.string("text")?,
+            items: lower_menu_items(element, &children, lowering)?,
+            disabled: props.bool("disabled")?,
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "StatusBar" => Element::StatusBar(StatusBar {
+            name,
+            id: String::new(),
+            role: props.role()?,
+            children: lower_status_items(element, &children, lowering)?,
+            events: props.events()?,
+            doc: element.doc.clone(),
+            source: lowering.source(element),
+            extra: props.extra(),
+        }),
+        "Option" => bail!("option `{}` must be placed inside a Dropdown", element.name),
+        "if" => bail!("`@if` blocks can only appear among the children of an element"),
+        "include" => bail!("`@include` can only appear among the block children of an element"),
+        kind if lowering.options.deny_unknown_kinds => match suggest_kind(kind) {
+            Some(known) => bail!(
+                "unknown element kind `{kind}` for element `{}`, did you mean `{known}`?",
+                element.name
+            ),
+            None => bail!("unknown element kind `{kind}` for element `{}`", element.name),
+        },
+        _ => {
+            // Unknown elements keep their children as written, so they are not checked here.
+            return Ok(Element::Custom(CustomElement {
+                name,
+                id: String::new(),
+                role: props.role()?,
+                implementation: Box::new(UnknownElement {
+                    source: element.clone().into_owned(),
+                    size_constraints: props.size_constraints()?,
+                    margins: props.margins()?,
+                    #[cfg(feature = "std")]
+                    base_dir: lowering.options.base_dir.clone(),
+                }),
+            }));
+        }
+    };
+    if !accepts_children(&element.kind)
+        && let Some(child) = children.first()
+    {
+        bail!(
+            "element `{}` ({}) can not have children, found `{}`",
+            element.name,
+            element.kind,
+            child.name
+        );
+    }
+    Ok(lowered)
+}
+
+/// Fails if a size is relative to an element that does not exist,
+/// or if relative sizes depend on each other in a cycle.
+fn check_relative_sizes(root: &Element) -> Result<()> {
+    let references = layout::relative_references(root);
+    let mut names = HashSet::new();
+    validate::visit(root, &mut |node| {
+        names.insert(node.name());
+    });
+    let mut keys: Vec<&(String, &str)> = references.keys().collect();
+    keys.sort();
+    for key in keys {
+        let (target, _) = &references[key];
+        if !names.contains(target.as_str()) {
+            let message = format!("`{}` of `{}` is relative to `{target}`, which does not exist", key.1, key.0);
+            return Err(GlyphError::Layout(message));
+        }
+        let mut path = vec![key.clone()];
+        let mut next = (target.clone(), layout::reference_axis(key.1));
+        while let Some((target, _)) = references.get(&next) {
+            if path.contains(&next) {
+                path.push(next);
+                let path: Vec<String> = path.iter().map(|(name, property)| format!("{name}.{property}")).collect();
+                let message = format!("relative sizes depend on each other in a cycle: {}", path.join(" -> "));
+                return Err(GlyphError::Layout(message));
+            }
+            let axis = layout::reference_axis(next.1);
+            path.push(next);
+            next = (target.clone(), axis);
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether the element or one of its children is an `@include` line.
+pub(crate) fn has_include(element: &parser::Element) -> bool {
+    element.kind == "include" || element.children.iter().any(has_include)
+}
+
+/// Replaces every `@include "file"` among the children of `element` by the root element of that file.
+///
+/// Paths resolve against `dir`, paths in included files against their own directory.
+/// An included file without `@language` uses the including file's, a different one is an error.
+/// Included files are parsed with `parse_options`.
+/// `stack` holds the files currently being included, the first one is the including layout if known.
+#[cfg(feature = "std")]
+fn resolve_includes(
+    element: &mut parser::Element,
+    language: &parser::Language,
+    dir: &Path,
+    parse_options: &parser::ParseOptions,
+    stack: &mut Vec<PathBuf>,
+) -> Result<()> {
+    for child in &mut element.children {
+        if child.kind != "include" {
+            resolve_includes(child, language, dir, parse_options, stack)?;
+            continue;
+        }
+        let span = child.span.clone();
+        let located = |message: String| GlyphError::Lower { message, span: Some(span.clone()) };
+        let raw = match child.get("path") {
+            Some(Value::String(raw)) => raw,
+            _ => return Err(located("`@include` expects a file path".to_string())),
+        };
+        let path = resolve_path(dir, raw);
+        let including = stack
+            .last()
+            .map_or("the including layout".to_string(), |file| format!("`{}`", file.display()));
+        if stack.contains(&path) {
+            return Err(located(format!("`{}` is included by {including} while it is being included", path.display())));
+        }
+        let src = std::fs::read_to_string(&path)
+            .map_err(|error| located(format!("can not read included file `{}`: {error}", path.display())))?;
+        let included = parser::parse_include_with_options(&src, parse_options)
+            .map_err(|error| located(format!("can not parse included file `{}`: {error}", path.display())))?;
+        if let Some(own) = &included.language
+            && (own.name != language.name || own.value != language.value || own.url != language.url)
+        {
+            return Err(located(format!(
+                "`{}` is written for `{}` but is included by {including}, which is written for `{}`",
+                path.display(),
+                directive(own),
+                directive(language)
+            )));
+        }
+        // The source of the included file is dropped here, the including layout outlives it.
+        let mut root = included.root.into_owned();
+        let included_dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        stack.push(path);
+        // Spans of errors in the included file point into that file, so they are moved to the `@include` line.
+        resolve_includes(&mut root, language, &included_dir, parse_options, stack)
+            .map_err(|error| located(error.to_string()))?;
+        stack.pop();
+        move_spans(&mut root, &span);
+        *child = root;
+    }
+    Ok(())
+}
+
+/// Points the spans of an included element and everything in it at the `@include` line,
+/// as their own spans are byte ranges of the included file.
+#[cfg(feature = "std")]
+fn move_spans(element: &mut parser::Element, span: &Range<usize>) {
+    fn move_value(value: &mut Value, span: &Range<usize>) {
+        match value {
+            Value::List(items) | Value::Concat(items) => items.iter_mut().for_each(|item| move_value(item, span)),
+            Value::Element(element) => move_spans(element, span),
+            _ => {}
+        }
+    }
+    element.span = span.clone();
+    for property in &mut element.properties {
+        move_value(&mut property.value, span);
+    }
+    for child in &mut element.children {
+        move_spans(child, span);
+    }
+}
+
+/// Fails if the `order` of a tab control lists a title no tab has, or a title twice.
+fn check_tab_order(name: &str, tabs: &[Tab], order: &[String]) -> Result<()> {
+    for (index, title) in order.iter().enumerate() {
+        if order[..index].contains(title) {
+            bail!("`order` of Tabs `{name}` lists `{title}` twice");
+        }
+        if !tabs.iter().any(|tab| tab.title == *title) {
+            let titles: Vec<&str> = tabs.iter().map(|tab| tab.title.as_str()).collect();
+            bail!(
+                "`order` of Tabs `{name}` lists `{title}`, which is the title of none of its tabs, expected one of: {}",
+                titles.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Fails if the `focus-order` of a form lists a name twice, or a name that is no interactive element of the form.
+fn check_focus_order(name: &str, children: &[Element], order: &[String]) -> Result<()> {
+    let mut kinds = HashMap::new();
+    for child in children {
+        validate::visit(child, &mut |node| {
+            let interactive = matches!(node, validate::Node::Element(element) if crate::focus::is_interactive(element));
+            kinds.insert(node.name(), (node.kind(), interactive));
+        });
+    }
+    for (index, listed) in order.iter().enumerate() {
+        if order[..index].contains(listed) {
+            bail!("`focus-order` of Form `{name}` lists `{listed}` twice");
+        }
+        match kinds.get(listed.as_str()) {
+            None => bail!("`focus-order` of Form `{name}` lists `{listed}`, which is not an element of the form"),
+            Some((kind, false)) => {
+                bail!("`focus-order` of Form `{name}` lists `{listed}`, which is a {kind} and can not take the focus")
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Resolves every `@alias` line to the built-in kind it stands for, following aliases of aliases.
+///
+/// Aliases may not hide a built-in kind or be defined twice,
+/// and must end at a built-in kind without going around in a cycle.
+fn alias_kinds(aliases: &[parser::Alias]) -> Result<HashMap<String, &'static str>> {
+    let located =
+        |alias: &parser::Alias, message: String| GlyphError::Lower { message, span: Some(alias.span.clone()) };
+    let mut targets: HashMap<&str, &str> = HashMap::new();
+    for alias in aliases {
+        if KINDS.contains(&alias.name.as_str()) || matches!(alias.name.as_str(), "if" | "include") {
+            return Err(located(alias, format!("alias `{}` would hide the built-in kind of that name", alias.name)));
+        }
+        if targets.insert(&alias.name, &alias.kind).is_some() {
+            return Err(located(alias, format!("alias `{}` is defined more than once", alias.name)));
+        }
+    }
+    let mut kinds = HashMap::new();
+    for alias in aliases {
+        let mut path = vec![alias.name.as_str()];
+        let mut kind = alias.kind.as_str();
+        while let Some(next) = targets.get(kind) {
+            let repeated = path.contains(&kind);
+            path.push(kind);
+            if repeated {
+                return Err(located(alias, format!("aliases form a cycle: {}", path.join(" -> "))));
+            }
+            kind = next;
+        }
+        let Some(known) = KINDS.iter().find(|known| **known == kind) else {
+            let message = match suggest_kind(kind) {
+                Some(known) => {
+                    format!("alias `{}` stands for unknown kind `{kind}`, did you mean `{known}`?", alias.name)
+                }
+                None => format!("alias `{}` stands for unknown kind `{kind}`", alias.name),
+            };
+            return Err(located(alias, message));
+        };
+        kinds.insert(alias.name.clone(), *known);
+    }
+    Ok(kinds)
+}
+
+/// Returns the root of the document with its aliased kinds replaced by the kinds they stand for.
+/// Aliases that do not resolve leave the root as written, lowering reports them.
+pub(crate) fn alias_resolved_root<'a, 'src>(document: &'a Document<'src>) -> Cow<'a, parser::Element<'src>> {
+    let mut root = Cow::Borrowed(&document.root);
+    if !document.aliases.is_empty()
+        && let Ok(kinds) = alias_kinds(&document.aliases)
+    {
+        resolve_aliases(root.to_mut(), &kinds);
+    }
+    root
+}
+
+/// Replaces the aliased kinds of the element and its children by the kinds they stand for.
+fn resolve_aliases(element: &mut parser::Element, kinds: &HashMap<String, &'static str>) {
+    fn resolve_value(value: &mut Value, kinds: &HashMap<String, &'static str>) {
+        match value {
+            Value::List(items) | Value::Concat(items) => {
+                for item in items {
+                    resolve_value(item, kinds);
+                }
+            }
+            Value::Element(element) => resolve_aliases(element, kinds),
+            _ => {}
+        }
+    }
+    if let Some(kind) = kinds.get(&element.kind) {
+        element.kind = kind.to_string();
+    }
+    for property in &mut element.properties {
+        resolve_value(&mut property.value, kinds);
+    }
+    for child in &mut element.children {
+        resolve_aliases(child, kinds);
+    }
+}
+
+/// Writes a language directive the way it appears in the source.
+#[cfg(feature = "std")]
+fn directive(language: &parser::Language) -> String {
+    match &language.url {
+        Some(url) => format!("@{} {}(\"{url}\")", language.name, language.value),
+        None => format!("@{} {}", language.name, language.value),
+    }
+}
+
+/// Returns whether the element or one of its children uses an `env"NAME"` value.
+pub(crate) fn has_env(element: &parser::Element) -> bool {
+    fn value_has_env(value: &Value) -> bool {
+        match value {
+            Value::Env(_) => true,
+            Value::List(items) | Value::Concat(items) => items.iter().any(value_has_env),
+            Value::Responsive(cases, otherwise) => {
+                cases.iter().any(|(_, value)| value_has_env(value)) || value_has_env(otherwise)
+            }
+            Value::Element(element) => has_env(element),
+            _ => false,
+        }
+    }
+    element.properties.iter().any(|property| value_has_env(&property.value)) || element.children.iter().any(has_env)
+}
+
+/// Returns the value of the environment variable `name`.
+#[cfg(feature = "std")]
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Returns no value, there is no environment without `std`.
+#[cfg(not(feature = "std"))]
+fn env_var(_: &str) -> Option<String> {
+    None
+}
+
+/// Replaces every `env"NAME"` value of the element and its children with a string.
+fn resolve_env(element: &mut parser::Element, options: &LowerOptions) -> Result<()> {
+    fn resolve_value(value: &mut Value, span: &Range<usize>, options: &LowerOptions) -> Result<()> {
+        match value {
+            Value::Env(name) => {
+                let resolved = env_var(name).or_else(|| options.env_default.clone());
+                let Some(resolved) = resolved else {
+                    return Err(GlyphError::Lower {
+                        message: format!("environment variable `{name}` is not set"),
+                        span: Some(span.clone()),
+                    });
+                };
+                *value = Value::String(Cow::Owned(resolved));
+            }
+            Value::List(items) | Value::Concat(items) => {
+                for item in items {
+                    resolve_value(item, span, options)?;
+                }
+            }
+            Value::Responsive(cases, otherwise) => {
+                for (_, value) in cases {
+                    resolve_value(value, span, options)?;
+                }
+                resolve_value(otherwise, span, options)?;
+            }
+            Value::Element(element) => resolve_env(element, options)?,
+            _ => {}
+        }
+        Ok(())
+    }
+    for property in &mut element.properties {
+        resolve_value(&mut property.value, &element.span, options)?;
+    }
+    for child in &mut element.children {
+        resolve_env(child, options)?;
+    }
+    Ok(())
+}
+
+/// Replaces every `@if` block among the children by its own children
+/// when all of its conditions match the flags, and drops it otherwise.
+fn expand_conditionals<'a>(
+    children: Vec<&'a parser::Element<'a>>,
+    flags: &HashMap<String, String>,
+) -> Vec<&'a parser::Element<'a>> {
+    let mut expanded = Vec::new();
+    for child in children {
+        if child.kind != "if" {
+            expanded.push(child);
+            continue;
+        }
+        let matches = child.properties.iter().all(|condition| match &condition.value {
+            Value::String(value) => flags.get(&condition.name).is_some_and(|flag| flag == value),
+            _ => false,
+        });
+        if matches {
+            expanded.extend(expand_conditionals(child.children.iter().collect(), flags));
+        }
+    }
+    expanded
+}
+
+fn lower_children(children: &[&parser::Element], lowering: &Lowering) -> Result<Vec<Element>> {
+    children.iter().map(|child| lower_element(child, lowering)).collect()
+}
+
+/// Lowers the options of a dropdown, the options of an `@OptGroup` carry its label as their group.
+fn lower_options(
+    element: &parser::Element,
+    children: &[&parser::Element],
+    lowering: &Lowering,
+) -> Result<Vec<DropdownOption>> {
+    let mut options = Vec::new();
+    for child in children {
+        match child.kind.as_str() {
+            "Option" => options.push(lower_option(child, None, lowering).map_err(|error| locate(error, child))?),
+            "OptGroup" => options.extend(lower_group(child, lowering).map_err(|error| locate(error, child))?),
+            _ => return Err(unexpected_child(element, child, "Option or OptGroup")),
+        }
+    }
+    Ok(options)
+}
+
+/// Lowers the options of an `@OptGroup`, which is not kept as an element of its own.
+fn lower_group(group: &parser::Element, lowering: &Lowering) -> Result<Vec<DropdownOption>> {
+    let props = Properties::new(group);
+    if !group.name.is_empty() {
+        bail!("`@OptGroup` takes no name, it is named by its `label`");
+    }
+    let label = props.string("label")?;
+    if label.is_empty() {
+        bail!("`@OptGroup` needs a `label`");
+    }
+    let children = expand_conditionals(props.children()?, &lowering.options.flags);
+    if let Some(property) = props.extra().first() {
+        bail!("`@OptGroup` only takes a `label`, found `{}`", property.name);
+    }
+    children
+        .iter()
+        .map(|child| match child.kind.as_str() {
+            "Option" => lower_option(child, Some(&label), lowering).map_err(|error| locate(error, child)),
+            _ => Err(unexpected_child(group, child, "Option")),
+        })
+        .collect()
+}
+
+fn lower_option(element: &parser::Element, group: Option<&str>, lowering: &Lowering) -> Result<DropdownOption> {
+    let props = Properties::new(element);
+    let label = props.string("label")?;
+    let value = match props.get("value") {
+        None => DropdownOptionValue::StringValue(label.clone()),
+        Some(Value::String(s) | Value::DString(s)) => DropdownOptionValue::StringValue(s.to_string()),
+        Some(Value::Concat(parts)) => DropdownOptionValue::StringValue(props.concat("value", parts)?),
+        Some(Value::Number(n)) if n % 1.0 == 0.0 => DropdownOptionValue::NumberValue(*n as i64),
+        Some(Value::Number(n)) => DropdownOptionValue::FloatValue(*n),
+        Some(Value::Identifier(id)) if id == "true" || id == "false" => DropdownOptionValue::BoolValue(id == "true"),
+        Some(other) => return Err(props.mismatch("value", "a string, number or boolean", other)),
+    };
+    Ok(DropdownOption {
+        name: element.name.as_str().into(),
+        id: String::new(),
+        label,
+        value,
+        group: group.map(String::from),
+        doc: element.doc.clone(),
+        source: lowering.source(element),
+        extra: props.extra(),
+    })
+}
+
+fn lower_menu_items(
+    element: &parser::Element,
+    children: &[&parser::Element],
+    lowering: &Lowering,
+) -> Result<Vec<MenuItem>> {
+    children
+        .iter()
+        .map(|child| match lower_element(child, lowering)? {
+            Element::MenuItem(item) => Ok(item),
+            _ => Err(unexpected_child(element, child, "MenuItem")),
+        })
+        .collect()
+}
+
+/// Lowers the children of a status bar, which are labels or a single text input.
+fn lower_status_items(
+    element: &parser::Element,
+    children: &[&parser::Element],
+    lowering: &Lowering,
+) -> Result<Vec<Element>> {
+    let items: Vec<Element> = children
+        .iter()
+        .map(|child| match lower_element(child, lowering)? {
+            item @ (Element::Label(_) | Element::TextInput(_)) => Ok(item),
+            _ => Err(unexpected_child(element, child, "Label or TextInput")),
+        })
+        .collect::<Result<_>>()?;
+    let inputs = items.iter().filter(|item| matches!(item, Element::TextInput(_))).count();
+    if inputs > 0 && items.len() > 1 {
+        bail!("status bar `{}` holds labels or a single text input, found {} children", element.name, items.len());
+    }
+    Ok(items)
+}
+
+const KINDS: &[&str] = &[
+    "Form", "Panel", "Label", "TextInput", "Button", "Checkbox", "CheckboxGroup", "RadioGroup", "Radio",
+    "Dropdown", "Option", "Grid", "Column", "Modal", "Tabs", "Tab", "MenuBar", "MenuItem", "StatusBar",
+];
+
+/// Returns the known kind closest to `kind`, if it is close enough to be a typo.
+fn suggest_kind(kind: &str) -> Option<&'static str> {
+    KINDS
+        .iter()
+        .map(|known| (edit_distance(&kind.to_lowercase(), &known.to_lowercase()), *known))
+        .filter(|(distance, known)| *distance <= known.len() / 3)
+        .min()
+        .map(|(_, known)| known)
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb { diagonal } else { 1 + diagonal.min(above).min(row[j]) };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+fn accepts_children(kind: &str) -> bool {
+    matches!(
+        kind,
+        "Form"
+            | "Panel"
+            | "CheckboxGroup"
+            | "RadioGroup"
+            | "Dropdown"
+            | "Grid"
+            | "Modal"
+            | "Tabs"
+            | "Tab"
+            | "MenuBar"
+            | "MenuItem"
+            | "StatusBar"
+    )
+}
+
+fn unexpected_child(parent: &parser::Element, child: &parser::Element, expected: &str) -> GlyphError {
+    let parent = match parent.name.as_str() {
+        "" => format!("element `@{}`", parent.kind),
+        name => format!("element `{name}` ({})", parent.kind),
+    };
+    let message = format!("{parent} only accepts {expected} children, found `{}` ({})", child.name, child.kind);
+    GlyphError::Lower { message, span: Some(child.span.clone()) }
+}
+
+/// Alternative spellings of property names and the canonical name they stand for.
+///
+/// | Canonical        | Aliases                |
+/// |------------------|------------------------|
+/// | `margins-left`   | `margin-left`, `ml`    |
+/// | `margins-right`  | `margin-right`, `mr`   |
+/// | `margins-top`    | `margin-top`, `mt`     |
+/// | `margins-bottom` | `margin-bottom`, `mb`  |
+/// | `width`          | `w`                    |
+/// | `height`         | `h`                    |
+/// | `read-only`      | `readonly`             |
+/// | `word-wrap`      | `wrap`                 |
+///
+/// Lowering treats an alias exactly like the canonical name. Giving both on the
+/// same element is reported by [`crate::validate::check_duplicate_properties`].
+///
+/// ```
+/// let short = glyph::from_str("@language ratatui\n@Form f { @Button b { ml = 2 } }").unwrap();
+/// let long = glyph::from_str("@language ratatui\n@Form f { @Button b { margins-left = 2 } }").unwrap();
+/// assert_eq!(glyph::to_str(&short), glyph::to_str(&long));
+/// let button = short.root.as_form().unwrap().children[0].as_button().unwrap().clone();
+/// assert_eq!(button.margins.left, 2);
+/// assert!(button.extra.is_empty());
+/// ```
+pub const PROPERTY_ALIASES: &[(&str, &str)] = &[
+    ("margin-left", "margins-left"),
+    ("ml", "margins-left"),
+    ("margin-right", "margins-right"),
+    ("mr", "margins-right"),
+    ("margin-top", "margins-top"),
+    ("mt", "margins-top"),
+    ("margin-bottom", "margins-bottom"),
+    ("mb", "margins-bottom"),
+    ("w", "width"),
+    ("h", "height"),
+    ("readonly", "read-only"),
+    ("wrap", "word-wrap"),
+];
+
+/// Properties that hold a number of cells or a count.
+///
+/// They also accept the number written as a string, `width = "20"` is read as `width = 20`.
+/// [`validate::check_quoted_numbers`] reports such strings.
+pub const NUMERIC_PROPERTIES: &[&str] = &[
+    "width", "height", "left", "top",
+    "margins-left", "margins-right", "margins-top", "margins-bottom",
+    "frozen", "order", "min-checked", "max-checked",
+];
+
+/// Returns the number a string value spells, read where a number is expected.
+pub(crate) fn quoted_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(s) => s.parse::<f64>().ok().filter(|n| n.is_finite()),
+        _ => None,
+    }
+}
+
+/// Returns the canonical name for a property name, see [`PROPERTY_ALIASES`].
+pub fn canonical_property_name(name: &str) -> &str {
+    PROPERTY_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name, |(_, canonical)| canonical)
+}
+
+/// Identifiers accepted by the `layout` property, in the order errors list them.
+///
+/// ```
+/// let error = glyph::from_str("@language ratatui\n@Form main { layout = sideways }").unwrap_err();
+/// assert!(error.to_string().ends_with(
+///     "unknown layout `sideways` on element `main`, expected one of: \
+///      left-to-right, right-to-left, top-to-bottom, bottom-to-top, free-form"
+/// ));
+/// ```
+pub const LAYOUTS: &[(&str, Layout)] = &[
+    ("left-to-right", Layout::LeftToRight),
+    ("right-to-left", Layout::RightToLeft),
+    ("top-to-bottom", Layout::TopToBottom),
+    ("bottom-to-top", Layout::BottomToTop),
+    ("free-form", Layout::FreeForm),
+];
+
+/// Identifiers accepted by the `align` property of labels and the `title-align` property of panels.
+pub const ALIGNMENTS: &[(&str, Alignment)] = &[
+    ("left", Alignment::Left),
+    ("center", Alignment::Center),
+    ("right", Alignment::Right),
+];
+
+/// Identifiers accepted by the `border` property of panels.
+pub const BORDER_STYLES: &[(&str, BorderStyle)] = &[
+    ("plain", BorderStyle::Plain),
+    ("rounded", BorderStyle::Rounded),
+    ("double", BorderStyle::Double),
+    ("thick", BorderStyle::Thick),
+    ("none", BorderStyle::None),
+];
+
+/// Identifiers accepted by the `variant` property of buttons.
+pub const BUTTON_VARIANTS: &[(&str, ButtonVariant)] = &[
+    ("default", ButtonVariant::Default),
+    ("primary", ButtonVariant::Primary),
+    ("secondary", ButtonVariant::Secondary),
+    ("danger", ButtonVariant::Danger),
+];
+
+/// Identifiers accepted by the `scroll` property of panels.
+pub const SCROLL_MODES: &[(&str, ScrollMode)] = &[
+    ("none", ScrollMode::None),
+    ("vertical", ScrollMode::Vertical),
+    ("horizontal", ScrollMode::Horizontal),
+    ("both", ScrollMode::Both),
+];
+
+/// Identifiers accepted by the `tab-position` property of tab controls.
+pub const TAB_POSITIONS: &[(&str, TabPosition)] = &[
+    ("top", TabPosition::Top),
+    ("bottom", TabPosition::Bottom),
+    ("left", TabPosition::Left),
+    ("right", TabPosition::Right),
+];
+
+/// Typed access to the properties of a parsed element.
+/// Aliases are looked up by their canonical name, see [`PROPERTY_ALIASES`].
+/// When a property is given more than once, the last value wins.
+/// Every name that is looked up is remembered, so the remaining
+/// properties can be collected with [`Properties::extra`].
+struct Properties<'a> {
+    element: &'a parser::Element<'a>,
+    properties: Vec<(&'a str, &'a Property<'a>)>,
+    used: RefCell<Vec<String>>,
+}
+
+impl<'a> Properties<'a> {
+    fn new(element: &'a parser::Element) -> Self {
+        Properties {
+            element,
+            properties: element
+                .properties
+                .iter()
+                .map(|property| (canonical_property_name(&property.name), property))
+                .collect(),
+            used: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&'a Value<'a>> {
+        self.used.borrow_mut().push(name.to_string());
+        self.properties
+            .iter()
+            .rev()
+            .find(|(canonical, _)| *canonical == name)
+            .map(|(_, property)| &property.value)
+    }
+
+    /// Collects `on-<event> = "handler"` properties by event name.
+    /// Event names are not validated.
+    ///
+    /// ```
+    /// let ui = glyph::from_str(r#"
+    ///     @language ratatui
+    ///     @Form main {
+    ///         @Button ok { text = "OK" on-click = "submit" on-focus = show_hint }
+    ///     }
+    /// "#).unwrap();
+    /// let button = ui.root.as_form().unwrap().children[0].as_button().unwrap();
+    /// let expected = [("click", "submit"), ("focus", "show_hint")]
+    ///     .map(|(event, handler)| (event.to_string(), handler.to_string()));
+    /// assert_eq!(button.events, expected.into_iter().collect());
+    /// ```
+    fn events(&self) -> Result<HashMap<String, String>> {
+        let mut events = HashMap::new();
+        for property in &self.element.properties {
+            let Some(event) = property.name.strip_prefix("on-") else {
+                continue;
+            };
+            self.used.borrow_mut().push(property.name.clone());
+            let handler = match &property.value {
+                Value::String(s) => s.to_string(),
+                Value::Identifier(s) => s.clone(),
+                other => return Err(self.mismatch(&property.name, "a handler name", other)),
+            };
+            events.insert(event.to_string(), handler);
+        }
+        Ok(events)
+    }
+
+    /// Returns the block children followed by the elements of a `children = [...]` list.
+    ///
+    /// ```
+    /// let ui = glyph::from_str(r#"
+    ///     @language ratatui
+    ///     @Form main {
+    ///         children = [@Label first { text = "1" }, @Label second { text = "2" }]
+    ///         @Label block { text = "0" }
+    ///     }
+    /// "#).unwrap();
+    /// let names: Vec<&str> = ui.root.as_form().unwrap().children.iter().map(|child| child.name()).collect();
+    /// assert_eq!(names, ["block", "first", "second"]);
+    /// ```
+    fn children(&self) -> Result<Vec<&'a parser::Element<'a>>> {
+        let mut children: Vec<&parser::Element> = self.element.children.iter().collect();
+        match self.get("children") {
+            None => {}
+            Some(Value::List(items)) => {
+                for item in items {
+                    match item {
+                        Value::Element(child) => children.push(child),
+                        other => return Err(self.mismatch("children", "a list of elements", other)),
+                    }
+                }
+            }
+            Some(other) => return Err(self.mismatch("children", "a list of elements", other)),
+        }
+        Ok(children)
+    }
+
+    /// Returns the properties that have not been looked up, in source order.
+    /// They outlive the source, so their text is copied.
+    fn extra(&self) -> Vec<Property<'static>> {
+        let used = self.used.borrow();
+        self.properties
+            .iter()
+            .filter(|(canonical, _)| !used.iter().any(|name| name == canonical))
+            .map(|(_, property)| (*property).clone().into_owned())
+            .collect()
+    }
+
+    fn mismatch(&self, name: &str, expected: &str, found: &Value) -> GlyphError {
+        GlyphError::Lower {
+            message: format!(
+                "property `{name}` of element `{}` expects {expected}, found {}",
+                self.element.name,
+                describe(found)
+            ),
+            span: None,
+        }
+    }
+
+    fn string(&self, name: &str) -> Result<String> {
+        match self.get(name) {
+            None => Ok(String::new()),
+            Some(Value::String(s) | Value::DString(s)) => Ok(s.to_string()),
+            Some(Value::Concat(parts)) => self.concat(name, parts),
+            Some(other) => Err(self.mismatch(name, "a string", other)),
+        }
+    }
+
+    /// Reads a list of strings, a single string is a list of one.
+    /// Identifiers in the list count as the string they spell.
+    fn string_list(&self, name: &str) -> Result<Vec<String>> {
+        match self.get(name) {
+            None => Ok(Vec::new()),
+            Some(Value::String(s) | Value::DString(s)) => Ok(vec![s.to_string()]),
+            Some(Value::List(items)) => items
+                .iter()
+                .map(|item| match item {
+                    Value::String(s) | Value::DString(s) => Ok(s.to_string()),
+                    Value::Identifier(s) => Ok(s.clone()),
+                    other => Err(self.mismatch(name, "a list of strings", other)),
+                })
+                .collect(),
+            Some(other) => Err(self.mismatch(name, "a list of strings", other)),
+        }
+    }
+
+    /// Reads the accessibility `role`, a string or an identifier such as `navigation`.
+    fn role(&self) -> Result<Option<String>> {
+        match self.get("role") {
+            None => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s.to_string())),
+            Some(Value::Identifier(s)) => Ok(Some(s.clone())),
+            Some(other) => Err(self.mismatch("role", "a role name", other)),
+        }
+    }
+
+    /// Reads a property as a string, also accepting identifiers and numbers.
+    fn value_string(&self, name: &str) -> Result<Option<String>> {
+        match self.get(name) {
+            None => Ok(None),
+            Some(Value::String(s) | Value::DString(s)) => Ok(Some(s.to_string())),
+            Some(Value::Identifier(s)) => Ok(Some(s.clone())),
+            Some(Value::Concat(parts)) => Ok(Some(self.concat(name, parts)?)),
+            Some(Value::Number(n)) => Ok(Some(n.to_string())),
+            Some(other) => Err(self.mismatch(name, "a string", other)),
+        }
+    }
+
+    /// Joins the parts of `a + b`, every part must be a string.
+    fn concat(&self, name: &str, parts: &[Value]) -> Result<String> {
+        let mut joined = String::new();
+        for part in parts {
+            match part {
+                Value::String(s) | Value::DString(s) => joined.push_str(s),
+                other => return Err(self.mismatch(name, "only strings to be joined with `+`", other)),
+            }
+        }
+        Ok(joined)
+    }
+
+    fn bool(&self, name: &str) -> Result<bool> {
+        self.bool_or(name, false)
+    }
+
+    fn bool_or(&self, name: &str, default: bool) -> Result<bool> {
+        match self.get(name) {
+            None => Ok(default),
+            Some(Value::Identifier(id)) if id == "true" => Ok(true),
+            Some(Value::Identifier(id)) if id == "false" => Ok(false),
+            Some(other) => Err(self.mismatch(name, "`true` or `false`", other)),
+        }
+    }
+
+    fn u32(&self, name: &str) -> Result<Option<u32>> {
+        match self.get(name) {
+            None => Ok(None),
+            Some(Value::Number(n)) => Ok(Some(self.cells(name, *n)?)),
+            Some(other) => match quoted_number(other) {
+                Some(n) => Ok(Some(self.cells(name, n)?)),
+                None => Err(self.mismatch(name, "a number", other)),
+            },
+        }
+    }
+
+    fn cells(&self, name: &str, n: f64) -> Result<u32> {
+        if n < 0.0 {
+            bail!(
+                "property `{name}` of element `{}` must not be negative, found {n}",
+                self.element.name
+            );
+        }
+        if n % 1.0 != 0.0 || n > u32::MAX as f64 {
+            bail!(
+                "property `{name}` of element `{}` expects a whole number of cells, found {n}",
+                self.element.name
+            );
+        }
+        Ok(n as u32)
+    }
+
+    fn size(&self, name: &str) -> Result<SizeConstraint> {
+        match self.get(name) {
+            None => Ok(SizeConstraint::Auto),
+            // Sizes relative to another element are checked for cycles before layout,
+            // which a breakpoint could hide, so the sizes of a responsive value stand alone.
+            Some(Value::Responsive(cases, otherwise)) => {
+                let branch = |value: &Value| match value {
+                    Value::Relative(..) => Err(self.mismatch(name, "a size that does not refer to an element", value)),
+                    value => self.size_value(name, value),
+                };
+                Ok(SizeConstraint::Responsive {
+                    cases: cases
+                        .iter()
+                        .map(|(breakpoint, value)| Ok((breakpoint.clone(), branch(value)?)))
+                        .collect::<Result<_>>()?,
+                    otherwise: Box::new(branch(otherwise)?),
+                })
+            }
+            Some(value) => self.size_value(name, value),
+        }
+    }
+
+    fn size_value(&self, name: &str, value: &Value) -> Result<SizeConstraint> {
+        match value {
+            Value::Identifier(id) if id == "auto" => Ok(SizeConstraint::Auto),
+            Value::Identifier(id) if id == "min-content" => Ok(SizeConstraint::MinContent),
+            Value::Identifier(id) if id == "max-content" => Ok(SizeConstraint::MaxContent),
+            Value::Number(n) => Ok(SizeConstraint::Fixed(self.cells(name, *n)?)),
+            Value::Percentage(p) => Ok(SizeConstraint::Percentage(self.cells(name, *p)?)),
+            Value::Fraction(weight) => Ok(SizeConstraint::Fraction(self.cells(name, *weight)?)),
+            Value::Relative(p, target) => Ok(SizeConstraint::RelativeTo {
+                name: target.clone(),
+                percent: self.cells(name, *p)?,
+            }),
+            other => match quoted_number(other) {
+                Some(n) => Ok(SizeConstraint::Fixed(self.cells(name, n)?)),
+                None => Err(self.mismatch(name, "a size", other)),
+            },
+        }
+    }
+
+    fn size_constraints(&self) -> Result<SizeConstraints> {
+        Ok(SizeConstraints {
+            width: self.size("width")?,
+            height: self.size("height")?,
+            left: self.size("left")?,
+            top: self.size("top")?,
+        })
+    }
+
+    /// Reads `margins-left`, `margins-right`, `margins-top` and `margins-bottom`.
+    /// Negative margins are rejected, see [`Margins`].
+    fn margins(&self) -> Result<Margins> {
+        Ok(Margins {
+            left: self.u32("margins-left")?.unwrap_or(0),
+            right: self.u32("margins-right")?.unwrap_or(0),
+            top: self.u32("margins-top")?.unwrap_or(0),
+            bottom: self.u32("margins-bottom")?.unwrap_or(0),
+        })
+    }
+
+    fn identifier(&self, name: &str) -> Result<Option<&str>> {
+        match self.get(name) {
+            None => Ok(None),
+            Some(Value::Identifier(id)) => Ok(Some(id)),
+            Some(other) => Err(self.mismatch(name, "an identifier", other)),
+        }
+    }
+
+    fn layout(&self, name: &str) -> Result<Layout> {
+        self.keyword(name, "layout", LAYOUTS)
+    }
+
+    fn alignment(&self, name: &str) -> Result<Alignment> {
+        self.keyword(name, "alignment", ALIGNMENTS)
+    }
+
+    fn border_style(&self, name: &str) -> Result<BorderStyle> {
+        self.keyword(name, "border style", BORDER_STYLES)
+    }
+
+    fn button_variant(&self, name: &str) -> Result<ButtonVariant> {
+        self.keyword(name, "button variant", BUTTON_VARIANTS)
+    }
+
+    fn scroll_mode(&self, name: &str) -> Result<ScrollMode> {
+        self.keyword(name, "scroll mode", SCROLL_MODES)
+    }
+
+    fn tab_position(&self, name: &str) -> Result<TabPosition> {
+        self.keyword(name, "tab position", TAB_POSITIONS)
+    }
+
+    /// Reads an identifier naming one of `values`, the default when the property is not given.
+    /// The error for any other identifier lists the accepted ones.
+    fn keyword<T: Clone + Default>(&self, name: &str, what: &str, values: &[(&str, T)]) -> Result<T> {
+        let Some(id) = self.identifier(name)? else {
+            return Ok(T::default());
+        };
+        match values.iter().find(|(keyword, _)| *keyword == id) {
+            Some((_, value)) => Ok(value.clone()),
+            None => {
+                let accepted: Vec<&str> = values.iter().map(|(keyword, _)| *keyword).collect();
+                bail!(
+                    "unknown {what} `{id}` on element `{}`, expected one of: {}",
+                    self.element.name,
+                    accepted.join(", ")
+                )
+            }
+        }
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("string \"{s}\""),
+        Value::DString(_) => "d-string".to_string(),
+        Value::Env(name) => format!("environment variable `{name}`"),
+        Value::Number(n) => format!("number {n}"),
+        Value::Percentage(p) => format!("percentage {p}%"),
+        Value::Fraction(weight) => format!("fraction {weight}fr"),
+        Value::Relative(p, name) => format!("relative size {p}% of `{name}`"),
+        Value::Identifier(id) => format!("identifier `{id}`"),
+        Value::List(_) => "list".to_string(),
+        Value::Concat(_) => "concatenation".to_string(),
+        Value::Responsive(..) => "responsive value".to_string(),
+        Value::Element(element) => format!("element `{}`", element.name),
+    }
+}
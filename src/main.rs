@@ -0,0 +1,51 @@
+use std::process::ExitCode;
+
+use anyhow::{Result, bail};
+
+const USAGE: &str = "usage: glyph fmt [--check] <file>";
+
+/// Runs the command line interface.
+///
+/// `glyph fmt <file>` reformats the file in place. With `--check` the file is left
+/// alone, the formatted source is printed and the exit code is 1 if it differs.
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("error: {error:#}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<ExitCode> {
+    let Some((command, rest)) = args.split_first() else {
+        bail!("{USAGE}");
+    };
+    if command != "fmt" {
+        bail!("unknown command `{command}`\n{USAGE}");
+    }
+    let check = rest.iter().any(|arg| arg == "--check");
+    let files: Vec<&String> = rest.iter().filter(|arg| *arg != "--check").collect();
+    let [path] = files.as_slice() else {
+        bail!("{USAGE}");
+    };
+    let src = std::fs::read_to_string(path)?;
+    let formatted = match glyph::emit::format_source(&src) {
+        Ok(formatted) => formatted,
+        Err(error) => {
+            eprint!("{path}: {}", error.report(&src));
+            return Ok(ExitCode::from(2));
+        }
+    };
+    if formatted == src {
+        return Ok(ExitCode::SUCCESS);
+    }
+    if check {
+        print!("{formatted}");
+        return Ok(ExitCode::FAILURE);
+    }
+    std::fs::write(path, formatted)?;
+    Ok(ExitCode::SUCCESS)
+}
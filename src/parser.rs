@@ -1,52 +1,380 @@
-use anyhow::Result;
+use alloc::borrow::Cow;
+use alloc::rc::Rc;
+use core::cell::Cell;
+use core::fmt;
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::io::Read;
+
+use chumsky::error::RichPattern;
 use chumsky::prelude::*;
 
-use crate::types::*;
+use crate::compat::*;
+
+use crate::types::{Breakpoint, Comparison, Dimension};
+use crate::{GlyphError, Result, trace};
+
+mod handwritten;
 
 // === AST Types ===
-#[derive(Debug, Clone)]
-pub enum Value {
-    String(String),
+/// A property value as written in the source.
+///
+/// String parts joined with `+` are kept as `Concat` and joined while lowering:
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Label greeting { text = "Hello, " + "world" }
+///         @Label multi { text = "Lines:" + d"
+/// one" }
+///     }
+/// "#).unwrap();
+/// let form = ui.root.as_form().unwrap();
+/// assert_eq!(form.children[0].as_label().unwrap().text, "Hello, world");
+/// assert_eq!(form.children[1].as_label().unwrap().text, "Lines:\none");
+/// assert!(glyph::from_str("@language ratatui\n@Form f { title = \"a\" + 1 }").is_err());
+/// ```
+///
+/// A raw d-string `d!"..."` is neither interpolated nor dedented, its text is kept
+/// exactly as written and read as a plain [`Value::String`]:
+///
+/// ```
+/// let ui = glyph::from_str("@language ratatui\n@Form f {\n    @Label cmd { text = d!\"\n    echo ${HOME}\n    ls\" }\n}").unwrap();
+/// let label = ui.root.as_form().unwrap().children[0].as_label().unwrap();
+/// assert_eq!(label.text, "\n    echo ${HOME}\n    ls");
+/// ```
+///
+/// A property written without `= value` is a flag and holds the identifier `true`:
+///
+/// ```
+/// let flag = glyph::from_str("@language ratatui\n@Form f { @TextInput name { read-only } }").unwrap();
+/// let explicit = glyph::from_str("@language ratatui\n@Form f { @TextInput name { read-only = true } }").unwrap();
+/// let read_only = |ui: &glyph::UI| ui.root.as_form().unwrap().children[0].as_text_input().unwrap().read_only;
+/// assert!(read_only(&flag));
+/// assert_eq!(read_only(&flag), read_only(&explicit));
+///
+/// let compact = glyph::from_str("@language ratatui\n@Form f { @Checkbox c: checked label=\"On\" }").unwrap();
+/// assert!(compact.root.as_form().unwrap().children[0].as_checkbox().unwrap().checked);
+/// ```
+///
+/// The text of strings and d-strings borrows from the source, see [`Document`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<'src> {
+    String(Cow<'src, str>),
     Number(f64),
     Percentage(f64),
+    Fraction(f64), // 2fr, a share of the remaining space
     Identifier(String),
-    DString(String), // d"..." interpolated strings
+    DString(Cow<'src, str>), // d"..." interpolated strings
+    List(Vec<Value<'src>>), // [a, b, c]
+    Concat(Vec<Value<'src>>), // "a" + "b", joined while lowering
+    Element(Box<Element<'src>>), // element written as a list item
+    Relative(f64, String), // 50% of sidebar
+    Env(String), // env"NAME", replaced by the variable's value while lowering
+    Responsive(Vec<(Breakpoint, Value<'src>)>, Box<Value<'src>>), // 40 when-width<80 else 60
 }
 
-#[derive(Debug, Clone)]
-pub struct Property {
+impl Value<'_> {
+    /// Copies the borrowed text, so the value no longer depends on the source.
+    pub fn into_owned(self) -> Value<'static> {
+        match self {
+            Value::String(text) => Value::String(Cow::Owned(text.into_owned())),
+            Value::DString(text) => Value::DString(Cow::Owned(text.into_owned())),
+            Value::Number(number) => Value::Number(number),
+            Value::Percentage(percent) => Value::Percentage(percent),
+            Value::Fraction(fraction) => Value::Fraction(fraction),
+            Value::Identifier(identifier) => Value::Identifier(identifier),
+            Value::List(items) => Value::List(items.into_iter().map(Value::into_owned).collect()),
+            Value::Concat(parts) => Value::Concat(parts.into_iter().map(Value::into_owned).collect()),
+            Value::Element(element) => Value::Element(Box::new(element.into_owned())),
+            Value::Relative(percent, name) => Value::Relative(percent, name),
+            Value::Env(name) => Value::Env(name),
+            Value::Responsive(cases, otherwise) => Value::Responsive(
+                cases.into_iter().map(|(breakpoint, value)| (breakpoint, value.into_owned())).collect(),
+                Box::new(otherwise.into_owned()),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property<'src> {
     pub name: String,
-    pub value: Value,
+    pub value: Value<'src>,
 }
 
-#[derive(Debug, Clone)]
-pub struct Element {
+impl Property<'_> {
+    /// Copies the borrowed text of the value, see [`Value::into_owned`].
+    pub fn into_owned(self) -> Property<'static> {
+        Property { name: self.name, value: self.value.into_owned() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element<'src> {
     pub kind: String,        // e.g., "Form", "Panel", "TextInput"
     pub name: String,        // e.g., "main_form", "left_panel"
-    pub properties: Vec<Property>,
-    pub children: Vec<Element>,
+    pub doc: Option<String>, // /// comment lines above the element
+    pub properties: Vec<Property<'src>>,
+    pub children: Vec<Element<'src>>,
+    pub span: Range<usize>, // bytes from the `@` to the end of the element
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Language {
     pub name: String,
     pub value: String,
     pub url: Option<String>,
 }
 
-#[derive(Debug, Clone)]
-pub struct Document {
+impl<'src> Element<'src> {
+    /// Copies the borrowed text of the element and everything below it, see [`Value::into_owned`].
+    pub fn into_owned(self) -> Element<'static> {
+        Element {
+            kind: self.kind,
+            name: self.name,
+            doc: self.doc,
+            properties: self.properties.into_iter().map(Property::into_owned).collect(),
+            children: self.children.into_iter().map(Element::into_owned).collect(),
+            span: self.span,
+        }
+    }
+
+    /// Returns the value of the property with this name.
+    /// When the property is given more than once, the last value wins.
+    pub fn get(&self, name: &str) -> Option<&Value<'src>> {
+        self.get_all(name).last()
+    }
+
+    /// Returns every value given for the property with this name, in source order.
+    pub fn get_all<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a Value<'src>> {
+        self.properties
+            .iter()
+            .filter(move |property| property.name == name)
+            .map(|property| &property.value)
+    }
+
+    /// Returns the block children followed by the elements listed in `children = [...]`.
+    pub fn all_children(&self) -> impl Iterator<Item = &Element<'src>> {
+        let listed = self.get("children").into_iter().flat_map(|value| match value {
+            Value::List(items) => items.as_slice(),
+            _ => &[],
+        });
+        self.children.iter().chain(listed.filter_map(|item| match item {
+            Value::Element(element) => Some(element.as_ref()),
+            _ => None,
+        }))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assertion<'src> {
+    pub properties: Vec<Property<'src>>, // element="sidebar" min-width=20
+    pub span: Range<usize>,
+}
+
+/// Represents an `@alias Card = Panel` line, which lets a file write `@Card` for `@Panel`.
+///
+/// # Fields
+///
+/// - `name` (`String`) - The kind the file writes, `Card`.
+/// - `kind` (`String`) - The kind it stands for, a built-in kind or another alias.
+/// - `span` (`Range<usize>`) - Bytes of the line, from the `@`.
+///
+/// Aliases are written after `@language` and apply to the whole file, including the files it
+/// `@include`s. Lowering replaces them by the kinds they stand for.
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @alias Card = Panel
+///     @alias InfoCard = Card
+///     @Form main {
+///         @Card profile { title = "Profile" }
+///         @InfoCard about { }
+///     }
+/// "#).unwrap();
+/// let form = ui.root.as_form().unwrap();
+/// assert!(matches!(&form.children[0], glyph::Element::Panel(panel) if panel.title == "Profile"));
+/// assert!(matches!(&form.children[1], glyph::Element::Panel(_)));
+///
+/// let unknown = glyph::from_str("@language ratatui\n@alias Card = Pannel\n@Form main { }").unwrap_err();
+/// assert_eq!(unknown.to_string(), "alias `Card` stands for unknown kind `Pannel`, did you mean `Panel`?");
+/// let cycle = glyph::from_str("@language ratatui\n@alias A = B\n@alias B = A\n@Form main { }").unwrap_err();
+/// assert_eq!(cycle.to_string(), "aliases form a cycle: A -> B -> A");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alias {
+    pub name: String,
+    pub kind: String,
+    pub span: Range<usize>,
+}
+
+/// Represents a file pulled in with `@include`, see [`parse_include_with_options`].
+///
+/// # Fields
+///
+/// - `language` (`Option<Language>`) - The file's `@language` directive, if it has one.
+/// - `root` (`Element`) - The element that replaces the `@include` line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Include<'src> {
+    pub language: Option<Language>,
+    pub root: Element<'src>,
+}
+
+/// Represents a parsed `.gl` source.
+///
+/// Strings and d-strings borrow their text from the source, which is why the document
+/// can not outlive it. D-strings whose indentation is stripped, see [`ParseOptions`], hold their
+/// own copy, and so does everything after [`Document::into_owned`].
+///
+/// ```
+/// use std::borrow::Cow;
+/// use glyph::parser::{Value, parse};
+///
+/// let src = "@language ratatui\n@Form f {\n    title = \"Hello\"\n    text = d\"\n        indented\"\n    raw = d!\"\n        kept\"\n}";
+/// let document = parse(src).unwrap();
+/// let value = |name| document.root.get(name).unwrap();
+///
+/// // Strings and raw d-strings point into `src`, without a copy.
+/// assert!(matches!(value("title"), Value::String(Cow::Borrowed("Hello"))));
+/// assert!(matches!(value("raw"), Value::String(Cow::Borrowed("\n        kept"))));
+/// // Stripping the indentation of a d-string makes a new text.
+/// assert!(matches!(value("text"), Value::DString(Cow::Owned(text)) if text == "\nindented"));
+///
+/// let owned = document.clone().into_owned();
+/// assert!(matches!(owned.root.get("title"), Some(Value::String(Cow::Owned(title))) if title == "Hello"));
+/// assert_eq!(owned, document);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document<'src> {
     pub language: Language, // @language ratatui or @language my_lang("url")
-    pub root: Element,
+    pub aliases: Vec<Alias>, // @alias lines after the directive
+    pub root: Element<'src>,
+    pub assertions: Vec<Assertion<'src>>, // @assert lines before or after the root
+}
+
+impl Document<'_> {
+    /// Copies the borrowed text of the document, so it no longer depends on the source.
+    pub fn into_owned(self) -> Document<'static> {
+        Document {
+            language: self.language,
+            aliases: self.aliases,
+            root: self.root.into_owned(),
+            assertions: self
+                .assertions
+                .into_iter()
+                .map(|assertion| Assertion {
+                    properties: assertion.properties.into_iter().map(Property::into_owned).collect(),
+                    span: assertion.span,
+                })
+                .collect(),
+        }
+    }
 }
 
 // === Parser ===
-pub fn parser<'a>() -> impl Parser<'a, &'a str, Document, extra::Err<Rich<'a, char>>> {
-    let ident = text::ident::<&'a str, extra::Err<Rich<'a, char>>>().padded();
+
+/// Value of a property written without `= value`, the same as writing `= true`.
+fn flag<'a>() -> Value<'a> {
+    Value::Identifier("true".to_string())
+}
+
+/// Builds a responsive value from `first when-c1 else v1 when-c2 else v2`, where each
+/// breakpoint chooses the value before it. A value without breakpoints is kept as it is.
+fn responsive<'a>(first: Value<'a>, rest: Vec<(Breakpoint, Value<'a>)>) -> Value<'a> {
+    let mut chosen = first;
+    let mut cases = Vec::with_capacity(rest.len());
+    for (breakpoint, next) in rest {
+        cases.push((breakpoint, core::mem::replace(&mut chosen, next)));
+    }
+    if cases.is_empty() { chosen } else { Value::Responsive(cases, Box::new(chosen)) }
+}
+
+type Grammar<'a, T> = Boxed<'a, 'a, &'a str, T, extra::Err<Rich<'a, char>>>;
+
+/// A UTF-8 byte order mark, which some editors write at the start of a file.
+const BOM: char = '\u{FEFF}';
+
+pub fn parser<'a>() -> impl Parser<'a, &'a str, Document<'a>, extra::Err<Rich<'a, char>>> {
+    document_parser(usize::MAX)
+}
+
+/// Parser for documents that skips at most `max_errors` broken items, see [`grammar`].
+fn document_parser<'a>(max_errors: usize) -> impl Parser<'a, &'a str, Document<'a>, extra::Err<Rich<'a, char>>> {
+    let (_, directive, alias, element, assertion) = grammar(max_errors);
+    // Parse directive first, then aliases and the root element, assertions may come before or after it.
+    // A leading byte order mark is skipped rather than stripped, so spans still index the source as given.
+    just(BOM)
+        .or_not()
+        .ignore_then(directive)
+        .then(alias.repeated().collect::<Vec<_>>())
+        .then(assertion.clone().repeated().collect::<Vec<_>>())
+        .then(element)
+        .then(assertion.repeated().collect::<Vec<_>>())
+        .map(|((((language, aliases), mut assertions), root), after)| {
+            assertions.extend(after);
+            Document { language, aliases, root, assertions }
+        })
+}
+
+/// Parser for files pulled in with `@include`, whose `@language` directive is optional.
+pub fn include_parser<'a>() -> impl Parser<'a, &'a str, Include<'a>, extra::Err<Rich<'a, char>>> {
+    include_file_parser(usize::MAX)
+}
+
+/// Parser for included files that skips at most `max_errors` broken items, see [`grammar`].
+fn include_file_parser<'a>(max_errors: usize) -> impl Parser<'a, &'a str, Include<'a>, extra::Err<Rich<'a, char>>> {
+    let (ws, directive, _, element, _) = grammar(max_errors);
+    // `@Label hint` reads like a directive too, so a file without one is tried second.
+    let include = directive
+        .then(element.clone())
+        .map(|(language, root)| Include { language: Some(language), root })
+        .or(ws.ignore_then(element).map(|root| Include { language: None, root }));
+    just(BOM).or_not().ignore_then(include)
+}
+
+/// Parsers for whitespace with comments, the `@language` directive, `@alias` lines,
+/// elements and `@assert` lines.
+type Grammars<'a> = (
+    Grammar<'a, ()>,
+    Grammar<'a, Language>,
+    Grammar<'a, Alias>,
+    Grammar<'a, Element<'a>>,
+    Grammar<'a, Assertion<'a>>,
+);
+
+/// Returns the parsers shared by documents and included files, see [`Grammars`].
+///
+/// At most `max_errors` broken items are skipped up to the end of their line. The next one skips
+/// the rest of the source, so no time is spent finding errors that are not reported.
+fn grammar<'a>(max_errors: usize) -> Grammars<'a> {
+    let budget = Rc::new(Cell::new(max_errors));
+    let spent = {
+        let budget = budget.clone();
+        move |_: &()| budget.get() == 0
+    };
+    // Line comments: // ...
+    // Lines starting with `///` are doc comments and are kept, see `doc` below.
+    let comment = just("//")
+        .then(just('/').not())
+        .then(none_of('\n').repeated());
+    let ws = text::whitespace()
+        .then(comment.then(text::whitespace()).repeated())
+        .ignored()
+        .boxed();
+
+    let ident = text::ident::<&'a str, extra::Err<Rich<'a, char>>>().padded_by(ws.clone());
+    // Element name, `r#` allows words such as `true` or `auto` to be used as names
+    let name = just("r#")
+        .or_not()
+        .ignore_then(text::ident::<&'a str, extra::Err<Rich<'a, char>>>())
+        .padded_by(ws.clone());
     // Simple directive: @language ratatui
     let simple_directive = just('@')
-        .ignore_then(ident)
-        .then(ident)
+        .ignore_then(ident.clone())
+        .then(ident.clone())
         .map(|(name, value): (&str, &str)| Language {
             name: name.to_string(),
             value: value.to_string(),
@@ -59,8 +387,8 @@ pub fn parser<'a>() -> impl Parser<'a, &'a str, Document, extra::Err<Rich<'a, ch
         .then_ignore(just('"'));
 
     let directive_with_url = just('@')
-        .ignore_then(ident)
-        .then(ident)
+        .ignore_then(ident.clone())
+        .then(ident.clone())
         .then(just('(').ignore_then(url_string).then_ignore(just(')')))
         .map(|((name, value), url): ((&str, &str), String)| Language {
             name: name.to_string(),
@@ -70,38 +398,58 @@ pub fn parser<'a>() -> impl Parser<'a, &'a str, Document, extra::Err<Rich<'a, ch
 
     let directive = directive_with_url
         .or(simple_directive)
-        .padded();
+        .padded_by(ws.clone());
 
-    // String literals: "..."
+    // String literals: "...", borrowed from the source since there is nothing to unescape
+    let text = none_of('"').repeated().to_slice().map(Cow::Borrowed);
     let string = just('"')
-        .ignore_then(none_of('"').repeated().collect::<String>())
+        .ignore_then(text)
         .then_ignore(just('"'))
         .map(Value::String);
 
     // D-strings: d"..." (multiline with interpolation)
     let dstring = just("d\"")
-        .ignore_then(none_of('"').repeated().collect::<String>())
+        .ignore_then(text)
         .then_ignore(just('"'))
         .map(Value::DString);
 
-    // Numbers with optional percentage
+    // Raw d-strings: d!"..." (multiline, kept exactly as written)
+    let raw_dstring = just("d!\"")
+        .ignore_then(text)
+        .then_ignore(just('"'))
+        .map(Value::String);
+
+    // Environment variable references: env"BUILD_TAG"
+    let env = just("env\"")
+        .ignore_then(none_of('"').repeated().collect::<String>())
+        .then_ignore(just('"'))
+        .map(Value::Env);
+
+    // Numbers with optional sign and a percentage or fraction unit
     let frac = just('.').then(text::digits::<&str, extra::Err<Rich<'a, char>>>(10)).to_slice();
-    let number = text::int::<&str, extra::Err<Rich<'a, char>>>(10)
+    let number = just('-')
+        .or_not()
+        .then(text::int::<&str, extra::Err<Rich<'a, char>>>(10))
         .then(frac.or_not())
         .to_slice()
-        .then(just('%').or_not())
-        .map(|(num_str, pct): (&str, Option<char>)| {
-            let num_str = num_str.trim_end_matches('%');
-            let num: f64 = num_str.parse().unwrap();
-            if pct.is_some() {
-                Value::Percentage(num)
-            } else {
-                Value::Number(num)
+        .then(choice((just("%"), text::ascii::keyword("fr"))).or_not())
+        .try_map(|(num_str, unit): (&str, Option<&str>), span| {
+            let num: f64 = num_str
+                .parse()
+                .map_err(|_| Rich::custom(span, format!("invalid number `{num_str}`")))?;
+            // Over-long literals parse to infinity, which no property can use
+            if !num.is_finite() {
+                return Err(Rich::custom(span, format!("number `{num_str}` is out of range")));
+            }
+            match unit {
+                Some("%") => Ok(Value::Percentage(num)),
+                Some(_) => Ok(Value::Fraction(num)),
+                None => Ok(Value::Number(num)),
             }
         });
 
-    // Identifier values (like: left-to-right, free-form, true)
-    let ident_value = text::ident::<&str, extra::Err<Rich<'a, char>>>()
+    // Kebab-case identifiers (like: left-to-right, free-form, margins-left)
+    let kebab_ident = text::ident::<&str, extra::Err<Rich<'a, char>>>()
         .then(just('-').ignore_then(text::ident::<&str, extra::Err<Rich<'a, char>>>()).repeated().collect::<Vec<_>>())
         .map(|(first, rest): (&str, Vec<&str>)| {
             let mut s = first.to_string();
@@ -109,57 +457,744 @@ pub fn parser<'a>() -> impl Parser<'a, &'a str, Document, extra::Err<Rich<'a, ch
                 s.push('-');
                 s.push_str(part);
             }
-            Value::Identifier(s)
+            s
         });
 
-    let value = choice((dstring, string, number, ident_value)).padded();
+    // Identifier values (like: left-to-right, free-form, true)
+    let ident_value = kebab_ident.map(Value::Identifier);
+
+    // Percentage of another element's size: 50% of sidebar
+    let relative = number
+        .clone()
+        .then_ignore(text::inline_whitespace().at_least(1))
+        .then_ignore(text::ascii::keyword("of"))
+        .then_ignore(text::inline_whitespace().at_least(1))
+        .then(text::ident::<&str, extra::Err<Rich<'a, char>>>())
+        .try_map(|(value, name): (Value<'a>, &str), span| match value {
+            Value::Percentage(percent) => Ok(Value::Relative(percent, name.to_string())),
+            _ => Err(Rich::custom(span, "only percentages can be relative to another element")),
+        });
+
+    let value_atom = choice((raw_dstring, dstring, env, string, relative, number, ident_value)).boxed();
 
-    // Property: name = value
-    let property = ident
-        .then_ignore(just('=').padded())
-        .then(value)
-        .map(|(name, value): (&str, Value)| Property { name: name.to_string(), value });
+    // Condition on the size of the area: when-width<80
+    let breakpoint = just("when-")
+        .ignore_then(choice((
+            text::ascii::keyword("width").to(Dimension::Width),
+            text::ascii::keyword("height").to(Dimension::Height),
+        )))
+        .then(
+            choice((
+                just("<=").to(Comparison::LessOrEqual),
+                just(">=").to(Comparison::GreaterOrEqual),
+                just('<').to(Comparison::Less),
+                just('>').to(Comparison::Greater),
+            ))
+            .padded_by(text::inline_whitespace()),
+        )
+        .then(text::int::<&str, extra::Err<Rich<'a, char>>>(10).try_map(|cells: &str, span| {
+            cells.parse::<u32>().map_err(|_| Rich::custom(span, format!("breakpoint `{cells}` is out of range")))
+        }))
+        .map(|((dimension, comparison), cells)| Breakpoint { dimension, comparison, cells });
+
+    // Property on a single line, used by the compact element form
+    let inline_property = kebab_ident
+        .then(
+            just('=')
+                .padded_by(text::inline_whitespace())
+                .ignore_then(value_atom.clone())
+                .or_not()
+        )
+        .map(|(name, value): (String, Option<Value<'a>>)| Property { name, value: value.unwrap_or_else(flag) })
+        .boxed();
+
+    // Doc comments: consecutive `///` lines directly above an element
+    let doc = just("///")
+        .ignore_then(none_of('\n').repeated().collect::<String>())
+        .then_ignore(ws.clone())
+        .repeated()
+        .collect::<Vec<String>>()
+        .map(|lines| {
+            if lines.is_empty() {
+                return None;
+            }
+            let lines: Vec<&str> = lines
+                .iter()
+                .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end())
+                .collect();
+            Some(lines.join("\n"))
+        });
 
     // Recursive element definition
     let element = recursive(|element| {
-        let properties_and_children = property.clone()
+        // List: [item, item] where items are values or elements, the commas are optional
+        let list = just('[')
+            .ignore_then(
+                choice((element.clone().map(|e| Value::Element(Box::new(e))), value_atom.clone()))
+                    .padded_by(ws.clone())
+                    .then_ignore(just(',').or_not())
+                    .repeated()
+                    .collect::<Vec<_>>()
+                    .padded_by(ws.clone())
+            )
+            .then_ignore(just(']'))
+            .map(Value::List);
+        // Concatenation: "Hello " + d"world"
+        let concat = value_atom
+            .clone()
+            .separated_by(just('+').padded_by(ws.clone()))
+            .at_least(1)
+            .collect::<Vec<_>>()
+            .map(|mut parts| if parts.len() == 1 { parts.remove(0) } else { Value::Concat(parts) });
+        // Responsive: 40 when-width<80 else 60, each breakpoint choosing the value before it
+        let responsive = concat
+            .clone()
+            .then(
+                text::inline_whitespace()
+                    .at_least(1)
+                    .ignore_then(breakpoint.clone())
+                    .then_ignore(text::inline_whitespace().at_least(1))
+                    .then_ignore(text::ascii::keyword("else"))
+                    .then_ignore(text::inline_whitespace().at_least(1))
+                    .then(concat.clone())
+                    .repeated()
+                    .collect::<Vec<_>>(),
+            )
+            .map(|(first, rest): (Value<'a>, Vec<(Breakpoint, Value<'a>)>)| responsive(first, rest));
+        let value = choice((list.boxed(), responsive.boxed())).padded_by(ws.clone());
+
+        // Property: name = value, or a bare name as a flag
+        let property = kebab_ident
+            .padded_by(ws.clone())
+            .then(just('=').padded_by(ws.clone()).ignore_then(value).or_not())
+            .map(|(name, value): (String, Option<Value<'a>>)| Property { name, value: value.unwrap_or_else(flag) });
+
+        // A broken item is reported and skipped up to the end of its line,
+        // so one parse reports every broken line instead of only the first.
+        // Once the error budget is spent, the next broken item skips the rest of the source instead.
+        let skip_line = empty()
+            .filter({
+                let spent = spent.clone();
+                move |unit| !spent(unit)
+            })
+            .ignore_then(none_of("{}()\n").repeated().at_least(1))
+            .map({
+                let budget = budget.clone();
+                move |_| budget.set(budget.get() - 1)
+            });
+        let skip_rest = empty()
+            .filter(spent.clone())
+            .ignore_then(none_of("{}()\n").rewind())
+            .ignore_then(any().repeated());
+        let properties_and_children = property
             .map(Either::Left)
-            .or(element.map(Either::Right))
-            .padded()
+            .or(element.clone().map(Either::Right))
+            .map(Some)
+            .recover_with(via_parser(skip_line.or(skip_rest).map(|_| None)))
+            .padded_by(ws.clone())
             .repeated()
             .collect::<Vec<_>>();
 
         // @Kind name { ... } or @Kind name ( ... )
+        // The name may be left out, lowering decides which kinds do without one.
         let block = |open: char, close: char| {
-            just('@')
-                .ignore_then(ident)
-                .then(ident)
+            let header = just('@')
+                .ignore_then(ident.clone())
+                .then(name.clone().or_not().map(Option::unwrap_or_default))
                 .then(
                     just(open)
-                        .padded()
+                        .padded_by(ws.clone())
                         .ignore_then(properties_and_children.clone())
-                        .then_ignore(just(close).padded())
+                        // Blocks left open by skipping the rest of the source close at its end.
+                        .then_ignore(just(close).ignored().or(empty().filter(spent.clone()).ignore_then(end())))
                 )
-                .map(|((kind, name), items): ((&str, &str), Vec<Either<Property, Element>>)| {
+                .map_with(|element, extra| (element, extra.span().into_range()));
+            doc.clone()
+                .then(header)
+                .then_ignore(ws.clone())
+                .map(|(doc, (((kind, name), items), span))| {
                     let (props, children): (Vec<_>, Vec<_>) = items
                         .into_iter()
+                        .flatten()
                         .partition(|e| matches!(e, Either::Left(_)));
                     Element {
                         kind: kind.to_string(),
                         name: name.to_string(),
+                        doc,
                         properties: props.into_iter().filter_map(|e| e.left()).collect(),
                         children: children.into_iter().filter_map(|e| e.right()).collect(),
+                        span,
                     }
                 })
         };
 
-        block('{', '}').or(block('(', ')'))
+        // @Kind name: prop=value prop=value
+        // Compact form for leaf elements, the properties must stay on one line.
+        let compact = doc
+            .clone()
+            .then(
+                just('@')
+                    .ignore_then(ident.clone())
+                    .then(name.clone())
+                    .then_ignore(just(':').then(text::inline_whitespace()))
+                    .then(
+                        inline_property
+                            .clone()
+                            .separated_by(text::inline_whitespace().at_least(1))
+                            .at_least(1)
+                            .collect::<Vec<_>>()
+                    )
+                    .map_with(|element, extra| (element, extra.span().into_range()))
+            )
+            .map(|(doc, (((kind, name), properties), span))| Element {
+                kind: kind.to_string(),
+                name: name.to_string(),
+                doc,
+                properties,
+                children: Vec::new(),
+                span,
+            });
+
+        // @if feature = "pro" platform = "mac" { ... }
+        // Kept as an element of kind `if` with the conditions as its properties,
+        // lowering includes its children only when every condition matches a flag.
+        let conditional = just('@')
+            .ignore_then(text::ascii::keyword("if"))
+            .ignore_then(
+                kebab_ident
+                    .padded_by(ws.clone())
+                    .then_ignore(just('=').padded_by(ws.clone()))
+                    .then(string)
+                    .map(|(name, value): (String, Value<'a>)| Property { name, value })
+                    .repeated()
+                    .at_least(1)
+                    .collect::<Vec<_>>()
+            )
+            .then(
+                just('{')
+                    .padded_by(ws.clone())
+                    .ignore_then(element.clone().padded_by(ws.clone()).repeated().collect::<Vec<_>>())
+                    .then_ignore(just('}'))
+            )
+            .map_with(|(properties, children), extra| Element {
+                kind: "if".to_string(),
+                name: String::new(),
+                doc: None,
+                properties,
+                children,
+                span: extra.span().into_range(),
+            })
+            .then_ignore(ws.clone());
+
+        // @include "sidebar.gl"
+        // Kept as an element of kind `include` with the path as its only property,
+        // lowering replaces it by the root element of that file.
+        let include = just('@')
+            .ignore_then(text::ascii::keyword("include"))
+            .ignore_then(text::inline_whitespace().at_least(1))
+            .ignore_then(string)
+            .map_with(|path, extra| Element {
+                kind: "include".to_string(),
+                name: String::new(),
+                doc: None,
+                properties: vec![Property {
+                    name: "path".to_string(),
+                    value: path,
+                }],
+                children: Vec::new(),
+                span: extra.span().into_range(),
+            })
+            .then_ignore(ws.clone());
+
+        choice((conditional.boxed(), include.boxed(), block('{', '}').boxed(), block('(', ')').boxed(), compact.boxed()))
     });
 
-    // Parse directive first, then the root element
-    directive
-        .then(element)
-        .map(|(language, root)| Document { language, root })
+    // @assert element="sidebar" min-width=20, on a single line
+    let assertion = just('@')
+        .ignore_then(text::ascii::keyword("assert"))
+        .ignore_then(text::inline_whitespace().at_least(1))
+        .ignore_then(
+            inline_property
+                .separated_by(text::inline_whitespace().at_least(1))
+                .at_least(1)
+                .collect::<Vec<_>>()
+        )
+        .map_with(|properties, extra| Assertion {
+            properties,
+            span: extra.span().into_range(),
+        })
+        .padded_by(ws.clone());
+
+    // @alias Card = Panel, on a single line
+    let alias = just('@')
+        .ignore_then(text::ascii::keyword("alias"))
+        .ignore_then(text::inline_whitespace().at_least(1))
+        .ignore_then(text::ident::<&'a str, extra::Err<Rich<'a, char>>>())
+        .then_ignore(just('=').padded_by(text::inline_whitespace()))
+        .then(text::ident::<&'a str, extra::Err<Rich<'a, char>>>())
+        .map_with(|(name, kind): (&str, &str), extra| Alias {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            span: extra.span().into_range(),
+        })
+        .padded_by(ws.clone());
+
+    (ws, directive.boxed(), alias.boxed(), element.boxed(), assertion.boxed())
+}
+
+/// Parses a `.gl` source into its document AST.
+///
+/// Element names may be written as raw identifiers, `r#form` is stored as `form`.
+///
+/// ```
+/// let ui = glyph::from_str("@language ratatui\n@Form r#form { }").unwrap();
+/// assert_eq!(&*ui.root.as_form().unwrap().name, "form");
+/// ```
+pub fn parse(src: &str) -> Result<Document<'_>> {
+    parse_with_options(src, &ParseOptions::default())
+}
+
+/// Represents settings for [`parse_with_options`].
+///
+/// # Fields
+///
+/// - `max_errors` (`usize`) - Number of errors reported before giving up, unlimited by default.
+/// - `max_input_bytes` (`usize`) - Largest source accepted, in bytes, unlimited by default.
+///   Larger sources are rejected before parsing starts.
+/// - `tab_width` (`usize`) - Columns a tab in the indentation of a d-string moves to the next
+///   multiple of, 4 by default.
+/// - `engine` (`Engine`) - The parser that reads the source, chumsky by default.
+///
+/// The lines of a d-string after its first share their indentation with the layout around them,
+/// so the indentation common to all of them is stripped. Tabs are expanded first, which makes
+/// tab-indented and space-indented lines line up.
+///
+/// When the error budget is spent, parsing stops at the next error and skips the rest of the source.
+/// The reported errors end with an `aborted` error located at the first error left out.
+///
+/// ```
+/// use glyph::GlyphError;
+/// use glyph::parser::{ParseOptions, parse_with_options};
+///
+/// let broken = (0..10).map(|i| format!("p{i} = ?\n")).collect::<String>();
+/// let src = format!("@language ratatui\n@Form f {{\n{broken}}}");
+/// let options = ParseOptions { max_errors: 3, ..ParseOptions::default() };
+/// let Err(GlyphError::Parse(errors)) = parse_with_options(&src, &options) else { panic!() };
+/// assert_eq!(errors.len(), 4);
+/// assert_eq!(errors[3].message, "aborted after 3 errors");
+/// assert!(src[..errors[3].span.start].ends_with("p3 = "));
+///
+/// // Recovering from every broken line of this source takes seconds, stopping after a few is immediate.
+/// #[cfg(feature = "std")]
+/// {
+///     let src = format!("@language ratatui\n@Form f {{\n{}}}", "p = ?\n".repeat(100_000));
+///     let start = std::time::Instant::now();
+///     let Err(GlyphError::Parse(errors)) = parse_with_options(&src, &options) else { panic!() };
+///     assert!(start.elapsed() < std::time::Duration::from_secs(1), "{:?}", start.elapsed());
+///     assert_eq!(errors.len(), 4);
+/// }
+///
+/// let limited = ParseOptions { max_input_bytes: 16, ..ParseOptions::default() };
+/// let Err(GlyphError::Parse(errors)) = parse_with_options("@language ratatui\n@Form f { }", &limited) else {
+///     panic!()
+/// };
+/// assert_eq!(errors[0].message, "layout is 29 bytes long, more than the limit of 16 bytes");
+/// assert!(parse_with_options("@language ratatui\n@Form f { }", &ParseOptions::default()).is_ok());
+///
+/// let src = "@language ratatui\n@Form f {\n\t@Label notes { text = d\"\n\tone\n    two\n\t\tthree\n\t\" }\n}";
+/// let text = |options: &ParseOptions| {
+///     let ui = glyph::lower::lower(&parse_with_options(src, options).unwrap()).unwrap();
+///     ui.root.as_form().unwrap().children[0].as_label().unwrap().text.clone()
+/// };
+/// assert_eq!(text(&ParseOptions::default()), "\none\ntwo\n\tthree\n");
+/// assert_eq!(text(&ParseOptions { tab_width: 8, ..ParseOptions::default() }), "\n    one\ntwo\n    \tthree\n    ");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    pub max_errors: usize,
+    pub max_input_bytes: usize,
+    pub tab_width: usize,
+    pub engine: Engine,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            max_errors: usize::MAX,
+            max_input_bytes: usize::MAX,
+            tab_width: 4,
+            engine: Engine::default(),
+        }
+    }
+}
+
+/// Represents the parser [`parse_with_options`] reads a source with.
+///
+/// # Variants
+///
+/// - `Chumsky` - The grammar built with chumsky, see [`parser`]. Reports every broken line.
+/// - `Handwritten` - A recursive-descent parser written by hand, rule for rule the same grammar,
+///   which gives the same [`Document`] for every source chumsky accepts. It reports only the first
+///   error and rejects elements and lists nested more than 128 levels deep. Useful to cross-check
+///   chumsky, for example by fuzzing both, and as a fallback.
+///
+/// ```
+/// use glyph::parser::{Engine, ParseOptions, parse_with_options};
+///
+/// let engine = |engine| ParseOptions { engine, ..ParseOptions::default() };
+/// let (chumsky, handwritten) = (engine(Engine::Chumsky), engine(Engine::Handwritten));
+/// let mut corpus = vec![
+///     "@language ratatui\n@Form main { }".to_string(),
+///     "\u{FEFF}@language my_lang(\"https://example.com\")\n@Form f ( )".to_string(),
+///     r#"
+///     @language ratatui
+///     @alias Card = Panel
+///     @assert element="side" min-width=20
+///     /// The main form.
+///     ///   Second line.
+///     @Form r#form {
+///         title = "Hello, " + d"${user}" + env"TAG"  // a comment
+///         layout = left-to-right
+///         @Card side { width = 30% height = 2fr left = -1.5 top = 50% of main }
+///         @Label l: text="Hi" word-wrap markup
+///         @TextInput name { read-only }
+///         @Dropdown size { options = ["S", "M" 3, @Option l { label = d!"L" }] }
+///         @if feature = "pro" platform = "mac" { @Button b { text = "Pro" } }
+///         @MenuBar { @MenuItem file { items = [] } }
+///     }
+///     @assert element="main" max-width=80
+///     "#.to_string(),
+///     "@language ratatui\n@Form f { title \"x\" }".to_string(),
+///     "@language ratatui\n@Form f { a = # }".to_string(),
+///     "@language ratatui\n@Label l: text=\"x\"\n".to_string(),
+///     "@language ratatui\n@Form f { a = 1 }}".to_string(),
+/// ];
+/// #[cfg(feature = "std")]
+/// corpus.push(std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/examples/form.gl")).unwrap());
+///
+/// for src in &corpus {
+///     // Every prefix of a sample is a broken source the engines must agree on too.
+///     for end in (0..=src.len()).filter(|&end| src.is_char_boundary(end)) {
+///         let prefix = &src[..end];
+///         match (parse_with_options(prefix, &chumsky), parse_with_options(prefix, &handwritten)) {
+///             (Ok(expected), Ok(document)) => assert_eq!(document, expected, "{prefix}"),
+///             (Err(_), Err(_)) => {}
+///             (expected, document) => panic!("engines disagree on {prefix:?}: {expected:?} {document:?}"),
+///         }
+///     }
+/// }
+///
+/// let deep = format!("@language ratatui\n{}{}", "@Panel p { ".repeat(200), "}".repeat(200));
+/// let error = parse_with_options(&deep, &handwritten).unwrap_err();
+/// assert!(error.to_string().ends_with("elements and lists are nested more than 128 levels deep"));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Engine {
+    #[default]
+    Chumsky,
+    Handwritten,
+}
+
+/// Parses a `.gl` source like [`parse`], reporting at most `options.max_errors` errors
+/// and rejecting sources longer than `options.max_input_bytes`.
+///
+/// Numbers too long for any property and malformed numbers are parse errors with either engine:
+///
+/// ```
+/// use glyph::parser::{Engine, ParseOptions, parse_with_options};
+///
+/// let long = format!("@language ratatui\n@Form f {{ @Button b {{ width = {} }} }}", "9".repeat(400));
+/// let malformed = "@language ratatui\n@Form f { @Button b { width = 1.2.3 } }";
+/// for engine in [Engine::Chumsky, Engine::Handwritten] {
+///     let options = ParseOptions { engine, ..ParseOptions::default() };
+///     let error = parse_with_options(&long, &options).unwrap_err();
+///     assert!(error.to_string().starts_with("failed to parse layout:"), "{error}");
+///     assert!(parse_with_options(malformed, &options).is_err());
+/// }
+/// ```
+pub fn parse_with_options<'src>(src: &'src str, options: &ParseOptions) -> Result<Document<'src>> {
+    check_input_size(src, options)?;
+    parse_document(src, options).map_err(|errors| parse_failure(errors, options))
+}
+
+/// Fails if `src` is longer than `options.max_input_bytes`.
+fn check_input_size(src: &str, options: &ParseOptions) -> Result<()> {
+    if src.len() > options.max_input_bytes {
+        let message =
+            format!("layout is {} bytes long, more than the limit of {} bytes", src.len(), options.max_input_bytes);
+        return Err(GlyphError::Parse(vec![ParseError::new(0..src.len(), message)]));
+    }
+    Ok(())
+}
+
+/// Returns the syntax errors as a [`GlyphError::Parse`], cut off with an `aborted` error after `options.max_errors`.
+fn parse_failure(mut errors: Vec<ParseError>, options: &ParseOptions) -> GlyphError {
+    if errors.len() > options.max_errors {
+        let span = errors[options.max_errors].span.clone();
+        errors.truncate(options.max_errors);
+        errors.push(ParseError::new(span, format!("aborted after {} errors", options.max_errors)));
+    }
+    GlyphError::Parse(errors)
+}
+
+/// Represents a single syntax error.
+///
+/// # Fields
+///
+/// - `span` (`Range<usize>`) - Byte range of the input the error was found at.
+/// - `found` (`Option<String>`) - The character found there, `None` at the end of the input.
+/// - `expected` (`Vec<String>`) - Readable names of what would have been accepted,
+///   such as `identifier` or `` `=` ``.
+/// - `message` (`String`) - Complete description of the error.
+///
+/// ```
+/// let errors = glyph::parser::parse_detailed("@language ratatui\n@Form f { title \"x\" }").unwrap_err();
+/// assert_eq!(errors[0].found.as_deref(), Some("\""));
+/// assert!(errors[0].expected.contains(&"`=`".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub span: Range<usize>,
+    pub found: Option<String>,
+    pub expected: Vec<String>,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}: {}", self.span.start, self.span.end, self.message)
+    }
+}
+
+impl ParseError {
+    /// Returns an error about the source rather than a character of it, which expects nothing.
+    fn new(span: Range<usize>, message: String) -> Self {
+        ParseError { span, found: None, expected: Vec::new(), message }
+    }
+
+    /// Renders the error with the source line it was found on, see [`GlyphError::report`].
+    pub fn report(&self, src: &str) -> String {
+        render_span(src, &self.span, &self.message)
+    }
+}
+
+/// Renders a message followed by the first source line of `span`, with the span underlined.
+pub(crate) fn render_span(src: &str, span: &Range<usize>, message: &str) -> String {
+    let start = span.start.min(src.len());
+    let line_start = src[..start].rfind('\n').map_or(0, |newline| newline + 1);
+    let line_end = src[start..].find('\n').map_or(src.len(), |newline| start + newline);
+    // A byte order mark takes no column, it is not part of the text.
+    let line = src[line_start..line_end].trim_start_matches(BOM);
+    let number = src[..line_start].matches('\n').count() + 1;
+    let column = src[line_start..start].trim_start_matches(BOM).chars().count() + 1;
+    let indent = crate::text::display_width(&src[line_start..start]);
+    let underlined = crate::text::display_width(&src[start..span.end.clamp(start, line_end)]).max(1);
+    let gutter = " ".repeat(number.to_string().len());
+    format!(
+        "error: {message}\n{gutter}--> {number}:{column}\n{gutter} |\n{number} | {line}\n{gutter} | {}{}\n",
+        " ".repeat(indent),
+        "^".repeat(underlined)
+    )
+}
+
+/// Parses a `.gl` source, returning every syntax error in structured form on failure.
+pub fn parse_detailed(src: &str) -> core::result::Result<Document<'_>, Vec<ParseError>> {
+    parse_document(src, &ParseOptions::default())
+}
+
+fn parse_document<'src>(
+    src: &'src str,
+    options: &ParseOptions,
+) -> core::result::Result<Document<'src>, Vec<ParseError>> {
+    let parse = || {
+        let mut document = match options.engine {
+            Engine::Chumsky => document_parser(options.max_errors).parse(src).into_result().map_err(|errors| parse_errors(&errors))?,
+            Engine::Handwritten => handwritten::parse(src).map_err(|error| vec![error])?,
+        };
+        dedent_element(&mut document.root, options.tab_width);
+        Ok(document)
+    };
+    trace::stage("parse", parse, |result| result.as_ref().map_or((0, 0), |document| trace::source_shape(&document.root)))
+}
+
+/// Parses a file pulled in with `@include`, like [`parse`] but with an optional `@language`.
+pub fn parse_include(src: &str) -> Result<Include<'_>> {
+    parse_include_with_options(src, &ParseOptions::default())
+}
+
+/// Parses a file pulled in with `@include` like [`parse_include`], with the settings of [`parse_with_options`].
+/// Included files are always read with [`Engine::Chumsky`].
+pub fn parse_include_with_options<'src>(src: &'src str, options: &ParseOptions) -> Result<Include<'src>> {
+    check_input_size(src, options)?;
+    let mut include = include_file_parser(options.max_errors)
+        .parse(src)
+        .into_result()
+        .map_err(|errors| parse_failure(parse_errors(&errors), options))?;
+    dedent_element(&mut include.root, options.tab_width);
+    Ok(include)
+}
+
+/// Dedents every d-string in the element, its listed elements and its children.
+fn dedent_element(element: &mut Element, tab_width: usize) {
+    fn dedent_value(value: &mut Value, tab_width: usize) {
+        match value {
+            Value::DString(text) => {
+                // Text that keeps its indentation stays borrowed from the source.
+                if let Cow::Owned(dedented) = dedent(text, tab_width) {
+                    *text = Cow::Owned(dedented);
+                }
+            }
+            Value::List(items) | Value::Concat(items) => items.iter_mut().for_each(|item| dedent_value(item, tab_width)),
+            Value::Element(element) => dedent_element(element, tab_width),
+            _ => {}
+        }
+    }
+
+    for property in &mut element.properties {
+        dedent_value(&mut property.value, tab_width);
+    }
+    for child in &mut element.children {
+        dedent_element(child, tab_width);
+    }
+}
+
+/// Strips the indentation shared by the lines of a d-string after its first line.
+///
+/// A tab moves to the next multiple of `tab_width` columns, a width of 0 counts as 1.
+/// Lines holding only whitespace do not take part in finding the shared indentation.
+/// Text without shared indentation is returned unchanged, without a copy.
+pub(crate) fn dedent(text: &str, tab_width: usize) -> Cow<'_, str> {
+    let tab_width = tab_width.max(1);
+    let Some((first, rest)) = text.split_once('\n') else {
+        return Cow::Borrowed(text);
+    };
+    let lines: Vec<&str> = rest.split('\n').collect();
+    let common = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indent_columns(line, tab_width))
+        .min()
+        .unwrap_or(0);
+    if common == 0 {
+        return Cow::Borrowed(text);
+    }
+    let mut dedented = first.to_string();
+    for line in lines {
+        dedented.push('\n');
+        dedented.push_str(&strip_columns(line, common, tab_width));
+    }
+    Cow::Owned(dedented)
+}
+
+fn next_column(column: usize, c: char, tab_width: usize) -> usize {
+    if c == '\t' { column / tab_width * tab_width + tab_width } else { column + 1 }
+}
+
+fn indent_columns(line: &str, tab_width: usize) -> usize {
+    line.chars()
+        .take_while(|c| matches!(c, ' ' | '\t'))
+        .fold(0, |column, c| next_column(column, c, tab_width))
+}
+
+/// Removes `columns` columns of indentation, padding with spaces when a tab reaches past them.
+fn strip_columns(line: &str, columns: usize, tab_width: usize) -> String {
+    let mut column = 0;
+    for (i, c) in line.char_indices() {
+        if column >= columns || !matches!(c, ' ' | '\t') {
+            return format!("{}{}", " ".repeat(column.saturating_sub(columns)), &line[i..]);
+        }
+        column = next_column(column, c, tab_width);
+    }
+    " ".repeat(column.saturating_sub(columns))
+}
+
+fn parse_errors(errors: &[Rich<'_, char>]) -> Vec<ParseError> {
+    errors
+            .iter()
+            .map(|error| {
+                let mut expected = Vec::new();
+                for pattern in error.expected() {
+                    let name = match pattern {
+                        RichPattern::Token(token) => format!("`{}`", **token),
+                        RichPattern::Label(label) => label.to_string(),
+                        RichPattern::Identifier(keyword) => format!("`{keyword}`"),
+                        RichPattern::Any => "any character".to_string(),
+                        RichPattern::SomethingElse => "something else".to_string(),
+                        RichPattern::EndOfInput => "end of input".to_string(),
+                        other => other.to_string(),
+                    };
+                    if !expected.contains(&name) {
+                        expected.push(name);
+                    }
+                }
+                ParseError {
+                    span: error.span().into_range(),
+                    found: error.found().map(|c| c.to_string()),
+                    expected,
+                    message: error.reason().to_string(),
+                }
+            })
+            .collect()
+}
+
+/// Parses a `.gl` source given as bytes, such as the contents of a file read without decoding it.
+/// Input that is not valid UTF-8 is reported as an error.
+///
+/// Like every other entry point, a leading UTF-8 byte order mark is skipped, and spans keep
+/// counting it, so they index the bytes as given.
+///
+/// ```
+/// use glyph::GlyphError;
+/// use glyph::parser::{parse, parse_bytes, parse_detailed};
+///
+/// let src = "@language ratatui\n@Form main {\n    @Label hi { text = \"Hi\" }\n}";
+/// let with_bom = [b"\xEF\xBB\xBF", src.as_bytes()].concat();
+///
+/// let plain = glyph::lower::lower(&parse(src).unwrap()).unwrap();
+/// let marked = glyph::lower::lower(&parse_bytes(&with_bom).unwrap()).unwrap();
+/// assert_eq!(marked, plain);
+/// // Spans count the three bytes of the mark.
+/// let span = parse(src).unwrap().root.span;
+/// assert_eq!(parse_bytes(&with_bom).unwrap().root.span, span.start + 3..span.end + 3);
+/// assert_eq!(glyph::from_str(&format!("\u{FEFF}{src}")).unwrap(), plain);
+///
+/// // Errors on the first line point at the same column either way.
+/// let broken = "@language ratatui @Form f { title \"x\" }";
+/// let report = |src: &str| parse_detailed(src).unwrap_err()[0].report(src);
+/// assert_eq!(report(&format!("\u{FEFF}{broken}")), report(broken));
+///
+/// let Err(GlyphError::Parse(errors)) = parse_bytes(b"@language \xFF") else { panic!() };
+/// assert_eq!(errors[0].span, 10..11);
+/// assert_eq!(errors[0].message, "layout is not valid UTF-8");
+/// ```
+pub fn parse_bytes(bytes: &[u8]) -> Result<Document<'_>> {
+    let src = core::str::from_utf8(bytes).map_err(|error| {
+        let offset = error.valid_up_to();
+        GlyphError::Parse(vec![ParseError::new(offset..offset + 1, "layout is not valid UTF-8".to_string())])
+    })?;
+    parse(src)
+}
+
+/// Reads a `.gl` source from any reader and parses it.
+/// Input that is not valid UTF-8 is reported as an error.
+///
+/// The source is gone once this returns, so the document holds its own copy of every text.
+///
+/// ```
+/// use std::io::Cursor;
+/// use glyph::parser::parse_reader;
+///
+/// let document = parse_reader(Cursor::new(b"@language ratatui\n@Form main { title = \"Hi\" }")).unwrap();
+/// assert_eq!(document.root.name, "main");
+///
+/// let error = parse_reader(Cursor::new(b"@language \xFF")).unwrap_err();
+/// assert_eq!(error.to_string(), "failed to parse layout:\n10..11: layout is not valid UTF-8");
+/// ```
+#[cfg(feature = "std")]
+pub fn parse_reader<R: Read>(mut reader: R) -> Result<Document<'static>> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    parse_bytes(&bytes).map(Document::into_owned)
 }
 
 enum Either<L, R> { Left(L), Right(R) }
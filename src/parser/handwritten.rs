@@ -0,0 +1,678 @@
+use alloc::borrow::Cow;
+use core::ops::Range;
+
+use super::{Alias, Assertion, BOM, Document, Element, Language, ParseError, Property, Value, flag, responsive};
+use crate::compat::*;
+use crate::types::{Breakpoint, Comparison, Dimension};
+
+/// Deepest nesting of elements and lists [`parse`] follows. Deeper sources are rejected
+/// instead of running out of stack, the hand-written parser has no stack to grow.
+pub(super) const MAX_DEPTH: usize = 128;
+
+/// Parses a `.gl` source with the recursive-descent parser, see [`super::Engine::Handwritten`].
+///
+/// Every rule mirrors a rule of [`super::parser`]: alternatives are tried in the same order,
+/// repetitions and optional parts are just as greedy, and a failed alternative gives back what it read.
+/// Only the first error is reported, at the furthest position any alternative got to.
+pub(super) fn parse(src: &str) -> Result<Document<'_>, ParseError> {
+    let mut parser = Descent { src, position: 0, depth: 0, too_deep: None, furthest: 0, expected: Vec::new() };
+    match parser.document() {
+        Some(document) => Ok(document),
+        None => Err(parser.error()),
+    }
+}
+
+/// Represents something a failed rule wanted to read, for the error message.
+///
+/// # Variants
+///
+/// - `Token(&str)` - Exactly this text.
+/// - `Label(&str)` - A class of text, such as `identifier`.
+/// - `End` - The end of the input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Expected {
+    Token(&'static str),
+    Label(&'static str),
+    End,
+}
+
+struct Descent<'a> {
+    src: &'a str,
+    position: usize,
+    depth: usize,
+    /// Where the nesting passed [`MAX_DEPTH`], which fails every rule from then on.
+    too_deep: Option<usize>,
+    furthest: usize,
+    expected: Vec<Expected>,
+}
+
+impl<'a> Descent<'a> {
+    fn rest(&self) -> &'a str {
+        &self.src[self.position..]
+    }
+
+    fn error(&self) -> ParseError {
+        let at = self.too_deep.unwrap_or(self.furthest);
+        let found = self.src[at..].chars().next();
+        let span = at..at + found.map_or(0, char::len_utf8);
+        if self.too_deep.is_some() {
+            return ParseError {
+                span,
+                found: found.map(String::from),
+                expected: Vec::new(),
+                message: format!("elements and lists are nested more than {MAX_DEPTH} levels deep"),
+            };
+        }
+        let names = |quote: char| -> Vec<String> {
+            self.expected
+                .iter()
+                .map(|expected| match expected {
+                    Expected::Token(token) => format!("{quote}{token}{quote}"),
+                    Expected::Label(label) => label.to_string(),
+                    Expected::End => "end of input".to_string(),
+                })
+                .collect()
+        };
+        let listed = match names('\'').as_slice() {
+            [] => "something else".to_string(),
+            [only] => only.clone(),
+            [init @ .., last] => format!("{}, or {last}", init.join(", ")),
+        };
+        let found_name = found.map_or("end of input".to_string(), |c| format!("'{c}'"));
+        ParseError {
+            span,
+            found: found.map(String::from),
+            expected: names('`'),
+            message: format!("found {found_name} expected {listed}"),
+        }
+    }
+
+    /// Notes that `expected` was wanted at the current position, if no alternative got further.
+    fn expect(&mut self, expected: Expected) {
+        if self.position > self.furthest {
+            self.furthest = self.position;
+            self.expected.clear();
+        }
+        if self.position == self.furthest && !self.expected.contains(&expected) {
+            self.expected.push(expected);
+        }
+    }
+
+    /// Runs `rule`, giving back what it read when it fails.
+    fn attempt<T>(&mut self, rule: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        if self.too_deep.is_some() {
+            return None;
+        }
+        let start = self.position;
+        let result = rule(self);
+        if result.is_none() {
+            self.position = start;
+        }
+        result
+    }
+
+    /// Runs `rule` one nesting level deeper.
+    fn nested<T>(&mut self, rule: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        if self.depth == MAX_DEPTH {
+            self.too_deep = Some(self.position);
+            return None;
+        }
+        self.depth += 1;
+        let result = self.attempt(rule);
+        self.depth -= 1;
+        result
+    }
+
+    fn eat(&mut self, token: &'static str) -> Option<()> {
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            Some(())
+        } else {
+            self.expect(Expected::Token(token));
+            None
+        }
+    }
+
+    /// Reads characters while `accept` holds, returning what was read.
+    fn take_while(&mut self, accept: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c: char| !accept(c)).unwrap_or(rest.len());
+        self.position += len;
+        &rest[..len]
+    }
+
+    /// Whitespace with `//` comments in between, which `///` doc comments are not.
+    fn ws(&mut self) {
+        loop {
+            self.take_while(is_whitespace);
+            let rest = self.rest();
+            if !rest.starts_with("//") || rest.starts_with("///") {
+                break;
+            }
+            self.take_while(|c| c != '\n');
+        }
+    }
+
+    fn inline_ws(&mut self) {
+        self.take_while(|c| c == ' ' || c == '\t');
+    }
+
+    fn inline_ws1(&mut self) -> Option<()> {
+        if self.take_while(|c| c == ' ' || c == '\t').is_empty() {
+            self.expect(Expected::Label("inline whitespace"));
+            return None;
+        }
+        Some(())
+    }
+
+    fn ident(&mut self) -> Option<&'a str> {
+        match self.rest().chars().next() {
+            Some(c) if unicode_ident::is_xid_start(c) || c == '_' => {
+                let start = self.position;
+                self.position += c.len_utf8();
+                self.take_while(unicode_ident::is_xid_continue);
+                Some(&self.src[start..self.position])
+            }
+            _ => {
+                self.expect(Expected::Label("identifier"));
+                None
+            }
+        }
+    }
+
+    fn padded_ident(&mut self) -> Option<&'a str> {
+        self.ws();
+        let ident = self.ident()?;
+        self.ws();
+        Some(ident)
+    }
+
+    /// An ASCII word that is exactly `keyword`, so `@iffy` does not start with `@if`.
+    fn keyword(&mut self, keyword: &'static str) -> Option<()> {
+        self.attempt(|parser| {
+            let rest = parser.rest();
+            let word = match rest.chars().next() {
+                Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                    parser.take_while(|c| c.is_ascii_alphanumeric() || c == '_')
+                }
+                _ => "",
+            };
+            if word == keyword {
+                return Some(());
+            }
+            parser.position -= word.len();
+            parser.expect(Expected::Token(keyword));
+            None
+        })
+    }
+
+    /// An element name, where `r#` allows words such as `true` or `auto`.
+    fn name(&mut self) -> Option<&'a str> {
+        self.attempt(|parser| {
+            parser.ws();
+            if parser.rest().starts_with("r#") {
+                parser.position += 2;
+            }
+            let name = parser.ident()?;
+            parser.ws();
+            Some(name)
+        })
+    }
+
+    fn kebab_ident(&mut self) -> Option<String> {
+        let start = self.position;
+        self.ident()?;
+        while self
+            .attempt(|parser| {
+                parser.eat("-")?;
+                parser.ident()
+            })
+            .is_some()
+        {}
+        Some(self.src[start..self.position].to_string())
+    }
+
+    /// Text up to the next `"`, after `open`.
+    fn quoted(&mut self, open: &'static str) -> Option<&'a str> {
+        self.attempt(|parser| {
+            parser.eat(open)?;
+            let text = parser.take_while(|c| c != '"');
+            parser.eat("\"")?;
+            Some(text)
+        })
+    }
+
+    fn directive(&mut self) -> Option<Language> {
+        self.attempt(|parser| {
+            parser.ws();
+            let language = parser
+                .attempt(|parser| {
+                    parser.eat("@")?;
+                    let (name, value) = (parser.padded_ident()?, parser.padded_ident()?);
+                    parser.eat("(")?;
+                    let url = parser.quoted("\"")?;
+                    parser.eat(")")?;
+                    Some(Language { name: name.to_string(), value: value.to_string(), url: Some(url.to_string()) })
+                })
+                .or_else(|| {
+                    parser.attempt(|parser| {
+                        parser.eat("@")?;
+                        let (name, value) = (parser.padded_ident()?, parser.padded_ident()?);
+                        Some(Language { name: name.to_string(), value: value.to_string(), url: None })
+                    })
+                })?;
+            parser.ws();
+            Some(language)
+        })
+    }
+
+    fn number(&mut self) -> Option<Value<'a>> {
+        self.attempt(|parser| {
+            let start = parser.position;
+            if parser.rest().starts_with('-') {
+                parser.position += 1;
+            }
+            match parser.rest().chars().next() {
+                Some('1'..='9') => {
+                    parser.take_while(|c| c.is_ascii_digit());
+                }
+                Some('0') => parser.position += 1,
+                _ => {
+                    parser.expect(Expected::Label("int"));
+                    return None;
+                }
+            }
+            parser.attempt(|parser| {
+                parser.eat(".")?;
+                match parser.take_while(|c| c.is_ascii_digit()) {
+                    "" => None,
+                    _ => Some(()),
+                }
+            });
+            let literal = &parser.src[start..parser.position];
+            let unit = if parser.rest().starts_with('%') {
+                parser.position += 1;
+                Some("%")
+            } else {
+                parser.keyword("fr").map(|()| "fr")
+            };
+            // Over-long literals parse to infinity, which no property can use
+            let number: f64 = literal.parse().ok().filter(|number: &f64| number.is_finite())?;
+            Some(match unit {
+                Some("%") => Value::Percentage(number),
+                Some(_) => Value::Fraction(number),
+                None => Value::Number(number),
+            })
+        })
+    }
+
+    /// Percentage of another element's size: `50% of sidebar`.
+    fn relative(&mut self) -> Option<Value<'a>> {
+        self.attempt(|parser| {
+            let value = parser.number()?;
+            parser.inline_ws1()?;
+            parser.keyword("of")?;
+            parser.inline_ws1()?;
+            let name = parser.ident()?;
+            match value {
+                Value::Percentage(percent) => Some(Value::Relative(percent, name.to_string())),
+                _ => None,
+            }
+        })
+    }
+
+    fn value_atom(&mut self) -> Option<Value<'a>> {
+        if let Some(text) = self.quoted("d!\"") {
+            return Some(Value::String(Cow::Borrowed(text)));
+        }
+        if let Some(text) = self.quoted("d\"") {
+            return Some(Value::DString(Cow::Borrowed(text)));
+        }
+        if let Some(name) = self.quoted("env\"") {
+            return Some(Value::Env(name.to_string()));
+        }
+        if let Some(text) = self.quoted("\"") {
+            return Some(Value::String(Cow::Borrowed(text)));
+        }
+        self.relative()
+            .or_else(|| self.number())
+            .or_else(|| self.attempt(Self::kebab_ident).map(Value::Identifier))
+    }
+
+    /// Property on a single line, used by the compact element form and `@assert`.
+    fn inline_property(&mut self) -> Option<Property<'a>> {
+        self.attempt(|parser| {
+            let name = parser.kebab_ident()?;
+            let value = parser.attempt(|parser| {
+                parser.inline_ws();
+                parser.eat("=")?;
+                parser.inline_ws();
+                parser.value_atom()
+            });
+            Some(Property { name, value: value.unwrap_or_else(flag) })
+        })
+    }
+
+    /// At least one inline property, separated by inline whitespace.
+    fn inline_properties(&mut self) -> Option<Vec<Property<'a>>> {
+        let mut properties = vec![self.inline_property()?];
+        while let Some(property) = self.attempt(|parser| {
+            parser.inline_ws1()?;
+            parser.inline_property()
+        }) {
+            properties.push(property);
+        }
+        Some(properties)
+    }
+
+    /// Consecutive `///` lines directly above an element.
+    fn doc(&mut self) -> Option<String> {
+        let mut lines = Vec::new();
+        while self.rest().starts_with("///") {
+            self.position += 3;
+            lines.push(self.take_while(|c| c != '\n'));
+            self.ws();
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        let lines: Vec<&str> = lines.iter().map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end()).collect();
+        Some(lines.join("\n"))
+    }
+
+    fn list(&mut self) -> Option<Value<'a>> {
+        self.nested(|parser| {
+            parser.eat("[")?;
+            parser.ws();
+            let mut items = Vec::new();
+            while let Some(item) = parser.attempt(|parser| {
+                parser.ws();
+                let item = match parser.element() {
+                    Some(element) => Value::Element(Box::new(element)),
+                    None => parser.value_atom()?,
+                };
+                parser.ws();
+                if parser.rest().starts_with(',') {
+                    parser.position += 1;
+                }
+                Some(item)
+            }) {
+                items.push(item);
+            }
+            parser.ws();
+            parser.eat("]")?;
+            Some(Value::List(items))
+        })
+    }
+
+    /// Concatenation: `"Hello " + d"world"`.
+    fn concat(&mut self) -> Option<Value<'a>> {
+        let mut parts = vec![self.value_atom()?];
+        while let Some(part) = self.attempt(|parser| {
+            parser.ws();
+            parser.eat("+")?;
+            parser.ws();
+            parser.value_atom()
+        }) {
+            parts.push(part);
+        }
+        Some(if parts.len() == 1 { parts.remove(0) } else { Value::Concat(parts) })
+    }
+
+    fn property(&mut self) -> Option<Property<'a>> {
+        self.attempt(|parser| {
+            parser.ws();
+            let name = parser.kebab_ident()?;
+            parser.ws();
+            let value = parser.attempt(|parser| {
+                parser.eat("=")?;
+                parser.ws();
+                let value = parser.list().or_else(|| parser.responsive())?;
+                parser.ws();
+                Some(value)
+            });
+            Some(Property { name, value: value.unwrap_or_else(flag) })
+        })
+    }
+
+    /// Responsive value: `40 when-width<80 else 60`, each breakpoint choosing the value before it.
+    fn responsive(&mut self) -> Option<Value<'a>> {
+        let first = self.concat()?;
+        let mut rest = Vec::new();
+        while let Some(case) = self.attempt(|parser| {
+            parser.inline_ws1()?;
+            let breakpoint = parser.breakpoint()?;
+            parser.inline_ws1()?;
+            parser.keyword("else")?;
+            parser.inline_ws1()?;
+            Some((breakpoint, parser.concat()?))
+        }) {
+            rest.push(case);
+        }
+        Some(responsive(first, rest))
+    }
+
+    /// Condition on the size of the area: `when-width<80`.
+    fn breakpoint(&mut self) -> Option<Breakpoint> {
+        self.attempt(|parser| {
+            parser.eat("when-")?;
+            let dimension = match parser.keyword("width") {
+                Some(()) => Dimension::Width,
+                None => parser.keyword("height").map(|()| Dimension::Height)?,
+            };
+            parser.inline_ws();
+            let comparison = [
+                ("<=", Comparison::LessOrEqual),
+                (">=", Comparison::GreaterOrEqual),
+                ("<", Comparison::Less),
+                (">", Comparison::Greater),
+            ]
+            .into_iter()
+            .find_map(|(token, comparison)| parser.eat(token).map(|()| comparison))?;
+            parser.inline_ws();
+            let cells = match parser.rest().chars().next() {
+                Some('1'..='9') => parser.take_while(|c| c.is_ascii_digit()),
+                Some('0') => {
+                    parser.position += 1;
+                    "0"
+                }
+                _ => {
+                    parser.expect(Expected::Label("int"));
+                    return None;
+                }
+            };
+            Some(Breakpoint { dimension, comparison, cells: cells.parse().ok()? })
+        })
+    }
+
+    /// The properties and children of a block, in any order.
+    fn properties_and_children(&mut self) -> (Vec<Property<'a>>, Vec<Element<'a>>) {
+        let (mut properties, mut children) = (Vec::new(), Vec::new());
+        loop {
+            let read = self.attempt(|parser| {
+                parser.ws();
+                match parser.property() {
+                    Some(property) => properties.push(property),
+                    None => children.push(parser.element()?),
+                }
+                parser.ws();
+                Some(())
+            });
+            if read.is_none() {
+                return (properties, children);
+            }
+        }
+    }
+
+    fn element(&mut self) -> Option<Element<'a>> {
+        self.nested(|parser| {
+            parser
+                .conditional()
+                .or_else(|| parser.include())
+                .or_else(|| parser.block("{", "}"))
+                .or_else(|| parser.block("(", ")"))
+                .or_else(|| parser.compact())
+        })
+    }
+
+    /// `@Kind name { ... }` or `@Kind name ( ... )`, the name may be left out.
+    fn block(&mut self, open: &'static str, close: &'static str) -> Option<Element<'a>> {
+        self.attempt(|parser| {
+            let doc = parser.doc();
+            let start = parser.position;
+            parser.eat("@")?;
+            let kind = parser.padded_ident()?;
+            let name = parser.name().unwrap_or_default();
+            parser.ws();
+            parser.eat(open)?;
+            parser.ws();
+            let (properties, children) = parser.properties_and_children();
+            parser.eat(close)?;
+            let span = start..parser.position;
+            parser.ws();
+            Some(element(kind, name, doc, properties, children, span))
+        })
+    }
+
+    /// `@Kind name: prop=value prop=value`, on one line.
+    fn compact(&mut self) -> Option<Element<'a>> {
+        self.attempt(|parser| {
+            let doc = parser.doc();
+            let start = parser.position;
+            parser.eat("@")?;
+            let kind = parser.padded_ident()?;
+            let name = parser.name()?;
+            parser.eat(":")?;
+            parser.inline_ws();
+            let properties = parser.inline_properties()?;
+            Some(element(kind, name, doc, properties, Vec::new(), start..parser.position))
+        })
+    }
+
+    /// `@if feature = "pro" { ... }`, kept as an element of kind `if`.
+    fn conditional(&mut self) -> Option<Element<'a>> {
+        self.attempt(|parser| {
+            let start = parser.position;
+            parser.eat("@")?;
+            parser.keyword("if")?;
+            let condition = |parser: &mut Self| {
+                parser.attempt(|parser| {
+                    parser.ws();
+                    let name = parser.kebab_ident()?;
+                    parser.ws();
+                    parser.eat("=")?;
+                    parser.ws();
+                    let value = Value::String(Cow::Borrowed(parser.quoted("\"")?));
+                    Some(Property { name, value })
+                })
+            };
+            let mut conditions = vec![condition(parser)?];
+            while let Some(next) = condition(parser) {
+                conditions.push(next);
+            }
+            parser.ws();
+            parser.eat("{")?;
+            parser.ws();
+            let mut children = Vec::new();
+            while let Some(child) = parser.attempt(|parser| {
+                parser.ws();
+                let child = parser.element()?;
+                parser.ws();
+                Some(child)
+            }) {
+                children.push(child);
+            }
+            parser.eat("}")?;
+            let span = start..parser.position;
+            parser.ws();
+            Some(element("if", "", None, conditions, children, span))
+        })
+    }
+
+    /// `@include "sidebar.gl"`, kept as an element of kind `include`.
+    fn include(&mut self) -> Option<Element<'a>> {
+        self.attempt(|parser| {
+            let start = parser.position;
+            parser.eat("@")?;
+            parser.keyword("include")?;
+            parser.inline_ws1()?;
+            let path = Value::String(Cow::Borrowed(parser.quoted("\"")?));
+            let span = start..parser.position;
+            parser.ws();
+            let properties = vec![Property { name: "path".to_string(), value: path }];
+            Some(element("include", "", None, properties, Vec::new(), span))
+        })
+    }
+
+    fn assertion(&mut self) -> Option<Assertion<'a>> {
+        self.attempt(|parser| {
+            parser.ws();
+            let start = parser.position;
+            parser.eat("@")?;
+            parser.keyword("assert")?;
+            parser.inline_ws1()?;
+            let properties = parser.inline_properties()?;
+            let span = start..parser.position;
+            parser.ws();
+            Some(Assertion { properties, span })
+        })
+    }
+
+    fn alias(&mut self) -> Option<Alias> {
+        self.attempt(|parser| {
+            parser.ws();
+            let start = parser.position;
+            parser.eat("@")?;
+            parser.keyword("alias")?;
+            parser.inline_ws1()?;
+            let name = parser.ident()?;
+            parser.inline_ws();
+            parser.eat("=")?;
+            parser.inline_ws();
+            let kind = parser.ident()?;
+            let span = start..parser.position;
+            parser.ws();
+            Some(Alias { name: name.to_string(), kind: kind.to_string(), span })
+        })
+    }
+
+    fn document(&mut self) -> Option<Document<'a>> {
+        if self.rest().starts_with(BOM) {
+            self.position += BOM.len_utf8();
+        }
+        let language = self.directive()?;
+        let mut aliases = Vec::new();
+        while let Some(alias) = self.alias() {
+            aliases.push(alias);
+        }
+        let mut assertions = Vec::new();
+        while let Some(assertion) = self.assertion() {
+            assertions.push(assertion);
+        }
+        let root = self.element()?;
+        while let Some(assertion) = self.assertion() {
+            assertions.push(assertion);
+        }
+        if !self.rest().is_empty() {
+            self.expect(Expected::End);
+            return None;
+        }
+        Some(Document { language, aliases, root, assertions })
+    }
+}
+
+fn element<'a>(
+    kind: &str,
+    name: &str,
+    doc: Option<String>,
+    properties: Vec<Property<'a>>,
+    children: Vec<Element<'a>>,
+    span: Range<usize>,
+) -> Element<'a> {
+    Element { kind: kind.to_string(), name: name.to_string(), doc, properties, children, span }
+}
+
+/// Whitespace as chumsky reads it, which leaves out the bidirectional formatting characters.
+fn is_whitespace(c: char) -> bool {
+    c.is_whitespace() && !matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
@@ -0,0 +1,9 @@
+pub use crate::layout::{Rect, arrange};
+pub use crate::lower::{LowerOptions, lower};
+pub use crate::parser::{Document, parse};
+pub use crate::render::{Backend, render};
+pub use crate::types::*;
+pub use crate::validate::{Diagnostic, Severity, validate_all};
+#[cfg(feature = "std")]
+pub use crate::from_file;
+pub use crate::{from_str, to_str};
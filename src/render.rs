@@ -0,0 +1,513 @@
+use crate::compat::*;
+use crate::layout::{self, Rect};
+use crate::text;
+use crate::types::*;
+
+/// Represents a single drawing primitive issued while rendering.
+///
+/// # Variants
+///
+/// - `Box { area, title }` - Outline of an area with an optional title on its top border.
+/// - `Text { x, y, text }` - Single line of text starting at the given cell.
+/// - `DimmedText { x, y, text }` - Text of a disabled element.
+/// - `StyledText { x, y, span }` - Run of label markup with its style.
+/// - `Backdrop { area }` - Dimmed area behind a modal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCall {
+    Box { area: Rect, title: String },
+    Text { x: u32, y: u32, text: String },
+    DimmedText { x: u32, y: u32, text: String },
+    StyledText { x: u32, y: u32, span: TextSpan },
+    Backdrop { area: Rect },
+}
+
+/// Drawing surface used by [`render`].
+///
+/// Backends only need to provide the drawing primitives;
+/// the placement of every element is computed by [`layout::arrange`].
+pub trait Backend {
+    fn draw_box(&mut self, area: Rect, title: &str);
+    fn draw_text(&mut self, x: u32, y: u32, text: &str);
+
+    /// Draws the text of a disabled element. Backends without styling draw it as plain text.
+    fn draw_dimmed_text(&mut self, x: u32, y: u32, text: &str) {
+        self.draw_text(x, y, text);
+    }
+
+    /// Draws a run of label markup in its style. Backends without styling draw it as plain text.
+    fn draw_styled_text(&mut self, x: u32, y: u32, span: &TextSpan) {
+        self.draw_text(x, y, &span.text);
+    }
+
+    /// Draws the border of a panel with the given lines, a ratatui backend would map them to its `BorderType`.
+    /// Backends with a single kind of line draw a plain box. Never called with [`BorderStyle::None`].
+    fn draw_styled_box(&mut self, area: Rect, title: &str, _border: BorderStyle) {
+        self.draw_box(area, title);
+    }
+
+    /// Draws the border of a panel with its title placed along the top border by `align`.
+    /// Backends that can not place titles draw a styled box.
+    fn draw_aligned_box(&mut self, area: Rect, title: &str, border: BorderStyle, _align: Alignment) {
+        self.draw_styled_box(area, title, border);
+    }
+
+    /// Dims what was drawn in `area` so far, before a modal is drawn over it.
+    /// Backends without styling leave the area as it is.
+    fn draw_backdrop(&mut self, _area: Rect) {}
+}
+
+/// Backend that records every draw call instead of drawing it.
+#[derive(Debug, Clone, Default)]
+pub struct RecordingBackend {
+    pub calls: Vec<DrawCall>,
+}
+
+impl Backend for RecordingBackend {
+    fn draw_box(&mut self, area: Rect, title: &str) {
+        self.calls.push(DrawCall::Box {
+            area,
+            title: title.to_string(),
+        });
+    }
+
+    fn draw_text(&mut self, x: u32, y: u32, text: &str) {
+        self.calls.push(DrawCall::Text {
+            x,
+            y,
+            text: text.to_string(),
+        });
+    }
+
+    fn draw_dimmed_text(&mut self, x: u32, y: u32, text: &str) {
+        self.calls.push(DrawCall::DimmedText {
+            x,
+            y,
+            text: text.to_string(),
+        });
+    }
+
+    fn draw_styled_text(&mut self, x: u32, y: u32, span: &TextSpan) {
+        self.calls.push(DrawCall::StyledText { x, y, span: span.clone() });
+    }
+
+    fn draw_backdrop(&mut self, area: Rect) {
+        self.calls.push(DrawCall::Backdrop { area });
+    }
+}
+
+/// Backend that draws into a grid of characters.
+///
+/// Boxes are drawn with box-drawing characters in the panel's border style,
+/// dimmed text is drawn like normal text.
+#[derive(Debug, Clone)]
+pub struct AsciiBackend {
+    width: u32,
+    height: u32,
+    cells: Vec<Vec<char>>,
+}
+
+/// Stands in for the right half of a wide character.
+const CONTINUATION: char = '\0';
+
+impl AsciiBackend {
+    pub fn new(width: u32, height: u32) -> Self {
+        AsciiBackend {
+            width,
+            height,
+            cells: vec![vec![' '; width as usize]; height as usize],
+        }
+    }
+
+    fn put(&mut self, x: u32, y: u32, c: char) {
+        if x < self.width && y < self.height {
+            self.cells[y as usize][x as usize] = c;
+        }
+    }
+
+    /// Returns the grid as newline separated rows without trailing spaces.
+    pub fn to_text(&self) -> String {
+        self.cells
+            .iter()
+            .map(|row| {
+                let row: String = row.iter().filter(|c| **c != CONTINUATION).collect();
+                row.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Returns the horizontal, vertical and top left, top right, bottom left, bottom right characters.
+fn border_chars(border: BorderStyle) -> [char; 6] {
+    match border {
+        BorderStyle::Plain | BorderStyle::None => ['─', '│', '┌', '┐', '└', '┘'],
+        BorderStyle::Rounded => ['─', '│', '╭', '╮', '╰', '╯'],
+        BorderStyle::Double => ['═', '║', '╔', '╗', '╚', '╝'],
+        BorderStyle::Thick => ['━', '┃', '┏', '┓', '┗', '┛'],
+    }
+}
+
+impl Backend for AsciiBackend {
+    fn draw_box(&mut self, area: Rect, title: &str) {
+        self.draw_styled_box(area, title, BorderStyle::Plain);
+    }
+
+    fn draw_styled_box(&mut self, area: Rect, title: &str, border: BorderStyle) {
+        self.draw_aligned_box(area, title, border, Alignment::Left);
+    }
+
+    fn draw_aligned_box(&mut self, area: Rect, title: &str, border: BorderStyle, align: Alignment) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        let [horizontal, vertical, top_left, top_right, bottom_left, bottom_right] = border_chars(border);
+        let right = area.x + area.width - 1;
+        let bottom = area.y + area.height - 1;
+        for x in area.x..=right {
+            self.put(x, area.y, horizontal);
+            self.put(x, bottom, horizontal);
+        }
+        for y in area.y..=bottom {
+            self.put(area.x, y, vertical);
+            self.put(right, y, vertical);
+        }
+        self.put(area.x, area.y, top_left);
+        self.put(right, area.y, top_right);
+        self.put(area.x, bottom, bottom_left);
+        self.put(right, bottom, bottom_right);
+        let title = text::truncate(title, area.width.saturating_sub(2) as usize, false);
+        let free = area.width.saturating_sub(2 + text::display_width(&title) as u32);
+        let offset = match align {
+            Alignment::Left => 0,
+            Alignment::Center => free / 2,
+            Alignment::Right => free,
+        };
+        self.draw_text(area.x + 1 + offset, area.y, &title);
+    }
+
+    fn draw_text(&mut self, x: u32, y: u32, text: &str) {
+        let mut column = x;
+        for c in text.chars() {
+            match text::char_width(c) {
+                0 => continue,
+                1 => self.put(column, y, c),
+                _ => {
+                    self.put(column, y, c);
+                    self.put(column + 1, y, CONTINUATION);
+                }
+            }
+            column += text::char_width(c) as u32;
+        }
+    }
+}
+
+/// Renders the UI into a `width` by `height` character grid and returns its rows.
+///
+/// Meant for documentation and snapshot tests that should not depend on a terminal.
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         title = "Confirm"
+///         layout = left-to-right
+///         @Button ok { text = "OK" width = 6 }
+///         @Button cancel { text = "Cancel" }
+///     }
+/// "#).unwrap();
+/// let expected = "\
+/// ┌Confirm──────────────┐
+/// │[OK]  [Cancel]       │
+/// │                     │
+/// └─────────────────────┘";
+/// assert_eq!(glyph::render::render_ascii(&ui, 23, 4), expected);
+/// ```
+pub fn render_ascii(ui: &UI, width: u32, height: u32) -> String {
+    let mut backend = AsciiBackend::new(width, height);
+    render(ui, Rect::new(0, 0, width, height), &mut backend);
+    backend.to_text()
+}
+
+/// Renders the UI into `area` using the given backend.
+pub fn render(ui: &UI, area: Rect, backend: &mut impl Backend) {
+    let rects = layout::arrange(ui, area);
+    render_element(&ui.root, &rects, backend);
+}
+
+/// Draws a box labelled with the element's name.
+///
+/// Used for elements that can not be drawn otherwise, such as custom elements,
+/// and meant as the fallback arm for backends matching on [`Element`] themselves.
+///
+/// ```
+/// use glyph::layout::{Rect, arrange};
+/// use glyph::render::{DrawCall, RecordingBackend, render};
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Chart sales { height = 5 }
+///     }
+/// "#).unwrap();
+/// let area = Rect::new(0, 0, 30, 10);
+/// let mut backend = RecordingBackend::default();
+/// render(&ui, area, &mut backend);
+///
+/// let placeholder = DrawCall::Box { area: arrange(&ui, area)["sales"], title: "sales".to_string() };
+/// assert_eq!(backend.calls.last(), Some(&placeholder));
+/// ```
+pub fn render_placeholder(name: &str, area: Rect, backend: &mut impl Backend) {
+    backend.draw_box(area, name);
+}
+
+fn render_element(element: &Element, rects: &HashMap<String, Rect>, backend: &mut impl Backend) {
+    let Some(area) = rects.get(element.name()).copied() else {
+        return;
+    };
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+    match element {
+        Element::Form(form) => {
+            backend.draw_box(area, &form.title);
+            render_children(&form.children, area.inner(), rects, backend);
+        }
+        Element::Panel(panel) => {
+            // A collapsed panel is one row high, so only its title bar is drawn.
+            if panel.border != BorderStyle::None {
+                let title = match panel.title.as_str() {
+                    "" => String::new(),
+                    title if panel.title_brackets => format!("[{title}]"),
+                    title => title.to_string(),
+                };
+                backend.draw_aligned_box(area, &title, panel.border, panel.title_align);
+            }
+            if !panel.collapsed {
+                let content = layout::panel_content_area(panel, area);
+                // Without a scroll position to offset by, children of a scrolling panel
+                // that reach past it are left out.
+                let scrolls = panel.scroll != ScrollMode::None;
+                let visible = panel.children.iter().filter(|child| {
+                    !scrolls || rects.get(child.name()).is_some_and(|child_area| content.contains(child_area))
+                });
+                render_children(visible, content, rects, backend);
+            }
+        }
+        Element::Modal(modal) => {
+            backend.draw_box(area, &modal.title);
+            render_children(&modal.children, area.inner(), rects, backend);
+        }
+        Element::Label(label) if label.markup && !label.word_wrap => {
+            for (row, line) in span_lines(&label.spans).iter().take(area.height as usize).enumerate() {
+                let width = line.iter().map(|span| text::display_width(&span.text)).sum::<usize>();
+                let offset = align_offset(label.align, area.width, width);
+                let (mut x, mut free) = (area.x + offset, (area.width - offset) as usize);
+                for span in line {
+                    if free == 0 {
+                        break;
+                    }
+                    let text = text::truncate(&span.text, free, false);
+                    let cells = text::display_width(&text);
+                    backend.draw_styled_text(x, area.y + row as u32, &TextSpan { text, ..span.clone() });
+                    x += cells as u32;
+                    free -= cells;
+                }
+            }
+        }
+        Element::Label(label) => {
+            let text = label.plain_text();
+            let lines: Vec<String> = if label.word_wrap {
+                text::wrap(text.trim(), area.width as usize)
+            } else {
+                text.trim().lines().map(|line| line.trim().to_string()).collect()
+            };
+            for (row, line) in lines.iter().take(area.height as usize).enumerate() {
+                let offset = align_offset(label.align, area.width, text::display_width(line));
+                let line_area = Rect::new(area.x + offset, area.y, area.width - offset, area.height);
+                draw_clipped(backend, line_area, row as u32, line, false);
+            }
+        }
+        Element::TextInput(input) => {
+            let text = if input.default_text.is_empty() {
+                &input.placeholder
+            } else {
+                &input.default_text
+            };
+            draw_clipped(backend, area, 0, text, input.disabled);
+        }
+        Element::Button(button) => {
+            let (open, close) = match button.variant {
+                ButtonVariant::Default => ('[', ']'),
+                ButtonVariant::Primary => ('<', '>'),
+                ButtonVariant::Secondary => ('(', ')'),
+                ButtonVariant::Danger => ('!', '!'),
+            };
+            draw_clipped(backend, area, 0, &format!("{open}{}{close}", button.text), button.disabled);
+        }
+        Element::Checkbox(checkbox) => render_checkbox(checkbox, checkbox.disabled, area, backend),
+        Element::CheckboxGroup(group) => {
+            for checkbox in group.checkboxes() {
+                if let Some(checkbox_area) = rects.get(&*checkbox.name).filter(|area| area.width > 0 && area.height > 0) {
+                    render_checkbox(checkbox, checkbox.disabled || group.disabled, *checkbox_area, backend);
+                }
+            }
+        }
+        Element::RadioGroup(group) => {
+            for radio in &group.children {
+                if let Some(radio_area) = rects.get(&*radio.name) {
+                    render_radio(radio, radio.value == group.selected_radio, group.disabled, *radio_area, backend);
+                }
+            }
+        }
+        Element::Radio(radio) => render_radio(radio, false, false, area, backend),
+        Element::Dropdown(dropdown) => {
+            let summary = dropdown.selection().join(", ");
+            draw_clipped(backend, area, 0, &format!("[{summary} v]"), dropdown.disabled);
+        }
+        Element::Grid(grid) => {
+            backend.draw_box(area, "");
+            let headers = if grid.show_header { grid.columns.as_slice() } else { &[] };
+            // The header row is the inner row above the areas of the columns.
+            let header = area.inner();
+            for column in headers {
+                if let Some(column_area) = rects.get(&*column.name) {
+                    let title_area = Rect::new(column_area.x, header.y, column_area.width, header.height.min(1));
+                    draw_clipped(backend, title_area, 0, &column.title, false);
+                }
+            }
+        }
+        Element::Column(column) => draw_clipped(backend, area, 0, &column.title, false),
+        Element::Tabs(tabs) => {
+            let ordered = tabs.ordered();
+            let header = ordered
+                .iter()
+                .map(|tab| {
+                    if tab.title == tabs.selected_tab {
+                        format!("[{}]", layout::tab_label(tab))
+                    } else {
+                        format!(" {} ", layout::tab_label(tab))
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("|");
+            let content = layout::tab_content_area(tabs, area);
+            let header_width = area.width - content.width;
+            match tabs.tab_position {
+                TabPosition::Top => draw_clipped(backend, area, 0, &header, false),
+                TabPosition::Bottom => draw_clipped(backend, area, area.height - 1, &header, false),
+                TabPosition::Left | TabPosition::Right => {
+                    let column = if content.x == area.x { content.x + content.width } else { area.x };
+                    let header_area = Rect::new(column, area.y, header_width, area.height);
+                    for (row, tab) in ordered.iter().take(area.height as usize).enumerate() {
+                        draw_clipped(backend, header_area, row as u32, &layout::tab_label(tab), false);
+                    }
+                }
+            }
+            let selected = ordered
+                .iter()
+                .find(|tab| tab.title == tabs.selected_tab)
+                .or(ordered.first());
+            if let Some(tab) = selected {
+                render_children(&tab.children, content, rects, backend);
+            }
+        }
+        Element::Tab(tab) => render_children(&tab.children, area, rects, backend),
+        // Submenus open on demand, so only the top level items are drawn.
+        Element::MenuBar(menu_bar) => {
+            for item in &menu_bar.items {
+                if let Some(item_area) = rects.get(&*item.name) {
+                    draw_clipped(backend, *item_area, 0, &format!(" {} ", item.text), item.disabled);
+                }
+            }
+        }
+        Element::MenuItem(item) => draw_clipped(backend, area, 0, &format!(" {} ", item.text), item.disabled),
+        Element::StatusBar(status_bar) => render_children(&status_bar.children, area, rects, backend),
+        Element::Custom(custom) => render_placeholder(&custom.name, area, backend),
+    }
+}
+
+/// Renders the children laid out in `area`, modals last so they end up on top.
+fn render_children<'a>(
+    children: impl IntoIterator<Item = &'a Element>,
+    area: Rect,
+    rects: &HashMap<String, Rect>,
+    backend: &mut impl Backend,
+) {
+    let (modals, others): (Vec<&Element>, Vec<&Element>) =
+        children.into_iter().partition(|child| matches!(child, Element::Modal(_)));
+    for child in others {
+        render_element(child, rects, backend);
+    }
+    for modal in modals {
+        if let Element::Modal(inner) = modal
+            && inner.backdrop
+        {
+            backend.draw_backdrop(area);
+        }
+        render_element(modal, rects, backend);
+    }
+}
+
+fn render_checkbox(checkbox: &Checkbox, disabled: bool, area: Rect, backend: &mut impl Backend) {
+    let mark = if checkbox.checked { 'x' } else { ' ' };
+    draw_clipped(backend, area, 0, &format!("[{mark}] {}", checkbox.label), disabled);
+}
+
+fn render_radio(radio: &Radio, selected: bool, disabled: bool, area: Rect, backend: &mut impl Backend) {
+    let mark = if selected { '*' } else { ' ' };
+    draw_clipped(backend, area, 0, &format!("({mark}) {}", radio.label), disabled);
+}
+
+/// Returns how far a line of `width` cells is moved right to be aligned in `area_width` cells.
+fn align_offset(align: Alignment, area_width: u32, width: usize) -> u32 {
+    let free = area_width.saturating_sub(width as u32);
+    match align {
+        Alignment::Left => 0,
+        Alignment::Center => free / 2,
+        Alignment::Right => free,
+    }
+}
+
+/// Splits the runs of a label into lines, trimmed like the lines of a label without markup.
+fn span_lines(spans: &[TextSpan]) -> Vec<Vec<TextSpan>> {
+    let mut lines = vec![Vec::new()];
+    for span in spans {
+        for (index, part) in span.text.split('\n').enumerate() {
+            if index > 0 {
+                lines.push(Vec::new());
+            }
+            if let Some(line) = lines.last_mut().filter(|_| !part.is_empty()) {
+                line.push(TextSpan { text: part.to_string(), ..span.clone() });
+            }
+        }
+    }
+    let blank = |span: &TextSpan| span.text.trim().is_empty();
+    for line in &mut lines {
+        let start = line.iter().position(|span| !blank(span)).unwrap_or(line.len());
+        let end = line.iter().rposition(|span| !blank(span)).map_or(start, |end| end + 1);
+        *line = line.drain(start..end).collect();
+        if let Some(first) = line.first_mut() {
+            first.text = first.text.trim_start().to_string();
+        }
+        if let Some(last) = line.last_mut() {
+            last.text = last.text.trim_end().to_string();
+        }
+    }
+    let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !line.is_empty()).map_or(start, |end| end + 1);
+    lines.drain(start..end).collect()
+}
+
+/// Draws a line of text on the given row of `area`, cut off at the area's width.
+/// Text of disabled elements is drawn dimmed.
+fn draw_clipped(backend: &mut impl Backend, area: Rect, row: u32, line: &str, dimmed: bool) {
+    if row >= area.height || area.width == 0 {
+        return;
+    }
+    let line = text::truncate(line, area.width as usize, false);
+    if dimmed {
+        backend.draw_dimmed_text(area.x, area.y + row, &line);
+    } else {
+        backend.draw_text(area.x, area.y + row, &line);
+    }
+}
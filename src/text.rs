@@ -0,0 +1,236 @@
+use crate::compat::*;
+use crate::types::TextSpan;
+
+/// Returns the number of terminal cells a character occupies.
+///
+/// Combining marks, zero-width characters and control characters take no cell,
+/// wide characters (CJK ideographs, Hangul, fullwidth forms and most emoji)
+/// take two cells, everything else takes one.
+pub fn char_width(c: char) -> usize {
+    let code = c as u32;
+    if code == 0 || c.is_control() || is_zero_width(code) {
+        0
+    } else if is_wide(code) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Returns the number of terminal cells the text occupies on a single line.
+///
+/// ```
+/// use glyph::text::display_width;
+///
+/// assert_eq!(display_width("abc"), 3);
+/// assert_eq!(display_width("日本"), 4);
+/// assert_eq!(display_width("🙂!"), 3);
+/// assert_eq!(display_width("e\u{301}"), 1);
+/// ```
+pub fn display_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+/// Shortens the text so it fits into `width` cells.
+///
+/// With `ellipsis`, a shortened text ends with `…`, which takes one of the cells.
+/// A wide character that would only fit halfway is dropped.
+///
+/// ```
+/// use glyph::text::truncate;
+///
+/// assert_eq!(truncate("日本語", 5, false), "日本");
+/// assert_eq!(truncate("日本語", 4, true), "日…");
+/// assert_eq!(truncate("🙂🙂", 3, false), "🙂");
+/// assert_eq!(truncate("short", 10, true), "short");
+/// ```
+pub fn truncate(s: &str, width: usize, ellipsis: bool) -> String {
+    if display_width(s) <= width {
+        return s.to_string();
+    }
+    let budget = if ellipsis { width.saturating_sub(1) } else { width };
+    let mut result = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let cells = char_width(c);
+        if used + cells > budget {
+            break;
+        }
+        used += cells;
+        result.push(c);
+    }
+    if ellipsis && width > 0 {
+        result.push('…');
+    }
+    result
+}
+
+/// Breaks the text into lines of at most `width` cells.
+///
+/// Lines are broken at whitespace, words longer than `width` are split.
+/// Existing line breaks are kept. A `width` of zero yields no lines.
+///
+/// ```
+/// use glyph::text::wrap;
+///
+/// assert_eq!(wrap("a wonderful day", 9), ["a", "wonderful", "day"]);
+/// assert_eq!(wrap("日本語テキスト", 6), ["日本語", "テキス", "ト"]);
+/// assert_eq!(wrap("日本", 3), ["日", "本"]);
+/// assert!(wrap("anything", 0).is_empty());
+/// ```
+pub fn wrap(s: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    if width == 0 {
+        return lines;
+    }
+    for paragraph in s.lines() {
+        let mut line = String::new();
+        let mut line_width = 0;
+        for word in paragraph.split_whitespace() {
+            let mut word = word;
+            let mut word_width = display_width(word);
+            if line_width > 0 && line_width + 1 + word_width <= width {
+                line.push(' ');
+                line.push_str(word);
+                line_width += 1 + word_width;
+                continue;
+            }
+            if line_width > 0 {
+                lines.push(core::mem::take(&mut line));
+            }
+            while word_width > width {
+                let head = truncate(word, width, false);
+                // A wide character in a one cell column still has to go somewhere.
+                let head = if head.is_empty() { word.chars().take(1).collect() } else { head };
+                word = &word[head.len()..];
+                word_width = display_width(word);
+                lines.push(head);
+            }
+            line.push_str(word);
+            line_width = word_width;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Splits label markup into runs of one style each.
+///
+/// Text between `*` marks is bold, text between `_` marks is underlined, and the two can be nested.
+/// A mark that is never closed styles the rest of the text. `\*`, `\_` and `\\` stand for the
+/// character itself, any other backslash is kept as written.
+///
+/// ```
+/// use glyph::TextSpan;
+/// use glyph::text::parse_markup;
+///
+/// let span = |text: &str, bold, underline| TextSpan { text: text.to_string(), bold, underline };
+/// assert_eq!(parse_markup("normal *bold* _under_"), [
+///     span("normal ", false, false),
+///     span("bold", true, false),
+///     span(" ", false, false),
+///     span("under", false, true),
+/// ]);
+/// assert_eq!(parse_markup(r"2 \* 3 = *6*"), [span("2 * 3 = ", false, false), span("6", true, false)]);
+/// ```
+pub fn parse_markup(s: &str) -> Vec<TextSpan> {
+    let mut spans = Vec::new();
+    let mut current = TextSpan::default();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        let (bold, underline) = match c {
+            '*' => (!current.bold, current.underline),
+            '_' => (current.bold, !current.underline),
+            '\\' => {
+                match chars.clone().next() {
+                    Some(escaped @ ('*' | '_' | '\\')) => {
+                        chars.next();
+                        current.text.push(escaped);
+                    }
+                    _ => current.text.push('\\'),
+                }
+                continue;
+            }
+            _ => {
+                current.text.push(c);
+                continue;
+            }
+        };
+        let next = TextSpan { text: String::new(), bold, underline };
+        let span = core::mem::replace(&mut current, next);
+        if !span.text.is_empty() {
+            spans.push(span);
+        }
+    }
+    if !current.text.is_empty() {
+        spans.push(current);
+    }
+    spans
+}
+
+fn is_zero_width(code: u32) -> bool {
+    matches!(
+        code,
+        0x0300..=0x036F // combining diacritical marks
+            | 0x0483..=0x0489
+            | 0x0591..=0x05BD
+            | 0x0610..=0x061A
+            | 0x064B..=0x065F
+            | 0x1AB0..=0x1AFF
+            | 0x1DC0..=0x1DFF
+            | 0x200B..=0x200F // zero width space, joiners and direction marks
+            | 0x2028..=0x202E
+            | 0x2060..=0x2064
+            | 0x20D0..=0x20FF
+            | 0xFE00..=0xFE0F // variation selectors
+            | 0xFE20..=0xFE2F
+            | 0xFEFF
+            | 0xE0100..=0xE01EF
+    )
+}
+
+fn is_wide(code: u32) -> bool {
+    matches!(
+        code,
+        0x1100..=0x115F // Hangul Jamo
+            | 0x231A..=0x231B
+            | 0x23E9..=0x23EC
+            | 0x2329..=0x232A
+            | 0x25FD..=0x25FE
+            | 0x2614..=0x2615
+            | 0x26AA..=0x26AB
+            | 0x26BD..=0x26BE
+            | 0x26F5
+            | 0x26FA
+            | 0x26FD
+            | 0x2705
+            | 0x270A..=0x270B
+            | 0x2728
+            | 0x274C
+            | 0x2753..=0x2755
+            | 0x2795..=0x2797
+            | 0x2E80..=0x303E // CJK radicals, symbols and punctuation
+            | 0x3041..=0x33FF // Hiragana, Katakana, CJK compatibility
+            | 0x3400..=0x4DBF // CJK extension A
+            | 0x4E00..=0x9FFF // CJK unified ideographs
+            | 0xA000..=0xA4CF // Yi
+            | 0xA960..=0xA97F
+            | 0xAC00..=0xD7A3 // Hangul syllables
+            | 0xF900..=0xFAFF // CJK compatibility ideographs
+            | 0xFE10..=0xFE19
+            | 0xFE30..=0xFE6F
+            | 0xFF00..=0xFF60 // fullwidth forms
+            | 0xFFE0..=0xFFE6
+            | 0x1F004
+            | 0x1F0CF
+            | 0x1F18E
+            | 0x1F191..=0x1F19A
+            | 0x1F200..=0x1F251
+            | 0x1F300..=0x1F64F // emoji
+            | 0x1F680..=0x1F6FF
+            | 0x1F7E0..=0x1F7EB
+            | 0x1F90C..=0x1F9FF
+            | 0x1FA70..=0x1FAFF
+            | 0x20000..=0x3FFFD // CJK extensions B and beyond
+    )
+}
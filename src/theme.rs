@@ -0,0 +1,112 @@
+use crate::compat::*;
+use crate::types::*;
+
+/// Represents property overrides applied to a UI by element kind.
+///
+/// Unlike properties written in the document, a theme is chosen at runtime,
+/// so the same layout can be shown with different themes.
+///
+/// # Fields
+///
+/// - `kinds` (`HashMap<String, ThemeOverrides>`) - Overrides by kind name, as returned by [`Element::kind_name`].
+#[derive(Debug, Clone, Default)]
+pub struct Theme {
+    pub kinds: HashMap<String, ThemeOverrides>,
+}
+
+/// Represents the properties a theme sets on one element kind.
+///
+/// # Fields
+///
+/// - `margins` (`Option<Margins>`) - Margins for elements that left theirs at the default.
+#[derive(Debug, Clone, Default)]
+pub struct ThemeOverrides {
+    pub margins: Option<Margins>,
+}
+
+/// Applies the theme to every element of the UI.
+///
+/// An override only replaces a property the element left at its default value,
+/// so values written in the document always win.
+///
+/// ```
+/// use glyph::theme::{Theme, ThemeOverrides, apply_theme};
+/// use glyph::Margins;
+///
+/// let mut ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Panel plain { }
+///         @Panel spaced { margins-left = 3 }
+///     }
+/// "#).unwrap();
+///
+/// let one = Margins { left: 1, right: 1, top: 1, bottom: 1 };
+/// let mut theme = Theme::default();
+/// theme.kinds.insert("Panel".to_string(), ThemeOverrides { margins: Some(one.clone()) });
+/// apply_theme(&mut ui, &theme);
+///
+/// let form = ui.root.as_form().unwrap();
+/// assert_eq!(form.children[0].as_panel().unwrap().margins, one);
+/// assert_eq!(form.children[1].as_panel().unwrap().margins.left, 3);
+/// ```
+pub fn apply_theme(ui: &mut UI, theme: &Theme) {
+    apply_element(&mut ui.root, theme);
+}
+
+fn apply_element(element: &mut Element, theme: &Theme) {
+    let overrides = theme.kinds.get(element.kind_name());
+    let (margins, children): (Option<&mut Margins>, &mut [Element]) = match element {
+        Element::Form(form) => (None, &mut form.children),
+        Element::Panel(panel) => (Some(&mut panel.margins), &mut panel.children),
+        Element::Label(label) => (Some(&mut label.margins), &mut []),
+        Element::TextInput(input) => (Some(&mut input.margins), &mut []),
+        Element::Button(button) => (Some(&mut button.margins), &mut []),
+        Element::Checkbox(checkbox) => (Some(&mut checkbox.margins), &mut []),
+        Element::CheckboxGroup(group) => (Some(&mut group.margins), &mut group.children),
+        Element::RadioGroup(group) => {
+            for radio in &mut group.children {
+                apply_radio(radio, theme);
+            }
+            (Some(&mut group.margins), &mut [])
+        }
+        Element::Radio(radio) => {
+            apply_radio(radio, theme);
+            return;
+        }
+        Element::Dropdown(dropdown) => (Some(&mut dropdown.margins), &mut []),
+        Element::Grid(grid) => (Some(&mut grid.margins), &mut []),
+        Element::Column(_) | Element::MenuBar(_) | Element::MenuItem(_) | Element::Custom(_) => (None, &mut []),
+        Element::Modal(modal) => (None, &mut modal.children),
+        Element::Tabs(tabs) => {
+            for tab in &mut tabs.children {
+                for child in &mut tab.children {
+                    apply_element(child, theme);
+                }
+            }
+            (Some(&mut tabs.margins), &mut [])
+        }
+        Element::Tab(tab) => (None, &mut tab.children),
+        Element::StatusBar(status_bar) => (None, &mut status_bar.children),
+    };
+    if let (Some(margins), Some(overrides)) = (margins, overrides) {
+        apply_margins(margins, overrides);
+    }
+    for child in children {
+        apply_element(child, theme);
+    }
+}
+
+fn apply_radio(radio: &mut Radio, theme: &Theme) {
+    if let Some(overrides) = theme.kinds.get("Radio") {
+        apply_margins(&mut radio.margins, overrides);
+    }
+}
+
+fn apply_margins(margins: &mut Margins, overrides: &ThemeOverrides) {
+    if let Some(themed) = &overrides.margins
+        && *margins == Margins::default()
+    {
+        *margins = themed.clone();
+    }
+}
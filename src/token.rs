@@ -0,0 +1,167 @@
+use core::ops::Range;
+
+use crate::compat::*;
+
+/// Represents the kind of a [`Token`], as far as highlighting needs to tell them apart.
+///
+/// # Variants
+///
+/// - `Directive` - `@` with the word after it, such as `@language`, `@Panel` or `@include`.
+/// - `Identifier` - Element names, property names and identifier values such as `left-to-right`.
+/// - `String` - String literals, including d-strings, raw d-strings and `env"NAME"` references.
+/// - `Number` - Numbers with their sign and unit, such as `-2`, `50%` or `1.5fr`.
+/// - `Punctuation` - One of `{ } ( ) [ ] = , + :`.
+/// - `Comment` - A `//` comment up to the end of its line.
+/// - `DocComment` - A `///` doc comment up to the end of its line.
+/// - `Error` - Text that starts no token, or a string that is never closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Directive,
+    Identifier,
+    String,
+    Number,
+    Punctuation,
+    Comment,
+    DocComment,
+    Error,
+}
+
+/// Represents a token of `.gl` source.
+///
+/// # Fields
+///
+/// - `kind` (`TokenKind`) - What the token is.
+/// - `span` (`Range<usize>`) - Byte range of the token in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
+
+/// Splits `src` into tokens for syntax highlighting, without parsing it.
+///
+/// Whitespace is skipped. Anything that starts no token becomes an [`TokenKind::Error`] token and
+/// the tokenizer carries on after it, so sources that are being typed highlight as well
+/// as they can. See [`Tokenizer`] to read the tokens one at a time.
+///
+/// ```
+/// use glyph::token::{TokenKind::*, tokenize};
+///
+/// let src = "@language ratatui\n/// Main\n@Form main { width = 50% title = \"Hi\" # }";
+/// let tokens = tokenize(src);
+/// let kinds: Vec<_> = tokens.iter().map(|token| token.kind).collect();
+/// assert_eq!(kinds, [
+///     Directive, Identifier, DocComment, Directive, Identifier, Punctuation,
+///     Identifier, Punctuation, Number, Identifier, Punctuation, String, Error, Punctuation,
+/// ]);
+/// assert_eq!(&src[tokens[8].span.clone()], "50%");
+/// assert_eq!(&src[tokens[11].span.clone()], "\"Hi\"");
+///
+/// let open = tokenize("text = d\"never closed");
+/// assert_eq!(open.last().unwrap().kind, Error);
+/// assert_eq!(open.last().unwrap().span, 7..21);
+/// ```
+pub fn tokenize(src: &str) -> Vec<Token> {
+    Tokenizer::new(src).collect()
+}
+
+/// Iterator over the tokens of a source, see [`tokenize`].
+#[derive(Debug, Clone)]
+pub struct Tokenizer<'a> {
+    src: &'a str,
+    position: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Tokenizer { src, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.position..]
+    }
+
+    /// Returns the length of the identifier at the start of `text`, words joined by `-` included.
+    fn identifier_len(text: &str) -> usize {
+        let word = |text: &str| match text.chars().next() {
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                text.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(text.len())
+            }
+            _ => 0,
+        };
+        let mut len = word(text);
+        while len > 0 && text[len..].starts_with('-') {
+            match word(&text[len + 1..]) {
+                0 => break,
+                next => len += 1 + next,
+            }
+        }
+        len
+    }
+
+    /// Returns the length of the number at the start of `text`, with its sign and unit.
+    fn number_len(text: &str) -> usize {
+        let digits = |text: &str| text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+        let mut len = usize::from(text.starts_with('-'));
+        len += digits(&text[len..]);
+        if text[len..].starts_with('.') && digits(&text[len + 1..]) > 0 {
+            len += 1 + digits(&text[len + 1..]);
+        }
+        if text[len..].starts_with('%') {
+            len += 1;
+        } else if text[len..].starts_with("fr") && Self::identifier_len(&text[len..]) == 2 {
+            len += 2;
+        }
+        len
+    }
+
+    /// Returns the kind and length of the token at the start of `text`, which is not whitespace.
+    fn token(text: &str) -> (TokenKind, usize) {
+        let line_len = text.find('\n').unwrap_or(text.len());
+        // The closing quote of a string, or an error up to the end of the source.
+        let string = |open: usize| match text[open..].find('"') {
+            Some(close) => (TokenKind::String, open + close + 1),
+            None => (TokenKind::Error, text.len()),
+        };
+        let first = text.chars().next().unwrap_or_default();
+        match first {
+            '/' if text.starts_with("///") => (TokenKind::DocComment, line_len),
+            '/' if text.starts_with("//") => (TokenKind::Comment, line_len),
+            '"' => string(1),
+            '{' | '}' | '(' | ')' | '[' | ']' | '=' | ',' | '+' | ':' => (TokenKind::Punctuation, 1),
+            '@' => match Self::identifier_len(&text[1..]) {
+                0 => (TokenKind::Error, 1),
+                len => (TokenKind::Directive, 1 + len),
+            },
+            '-' | '0'..='9' if Self::number_len(text) > usize::from(first == '-') => {
+                (TokenKind::Number, Self::number_len(text))
+            }
+            _ if text.starts_with("d!\"") => string(3),
+            _ if text.starts_with("r#") && Self::identifier_len(&text[2..]) > 0 => {
+                (TokenKind::Identifier, 2 + Self::identifier_len(&text[2..]))
+            }
+            _ => match Self::identifier_len(text) {
+                0 => (TokenKind::Error, first.len_utf8()),
+                len if text[len..].starts_with('"') && matches!(&text[..len], "d" | "env") => string(len + 1),
+                len => (TokenKind::Identifier, len),
+            },
+        }
+    }
+}
+
+impl Iterator for Tokenizer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        // A byte order mark belongs to no token, editors write one at the start of some files.
+        let text = self.rest().trim_start_matches('\u{FEFF}');
+        let start = self.src.len() - text.trim_start().len();
+        self.position = start;
+        if start == self.src.len() {
+            return None;
+        }
+        let (kind, len) = Self::token(self.rest());
+        self.position += len;
+        Some(Token { kind, span: start..self.position })
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(feature = "tracing")]
+use std::cell::RefCell;
+#[cfg(feature = "tracing")]
+use std::time::{Duration, Instant};
+
+use crate::parser;
+use crate::types::UI;
+use crate::validate;
+
+/// Represents a finished stage of the pipeline, as reported to the subscriber of [`with_subscriber`].
+///
+/// # Fields
+///
+/// - `stage` (`&'static str`) - `parse`, `lower` or `validate`.
+/// - `elements` (`usize`) - Number of elements in the tree the stage produced or checked,
+///   0 when the stage failed.
+/// - `depth` (`usize`) - Levels of nesting of that tree, the root is level 1.
+/// - `elapsed` (`Duration`) - Time the stage took, not counting the measuring of the tree.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone)]
+pub struct Span {
+    pub stage: &'static str,
+    pub elements: usize,
+    pub depth: usize,
+    pub elapsed: Duration,
+}
+
+/// Receives the spans of the stages run inside [`with_subscriber`].
+#[cfg(feature = "tracing")]
+type Subscriber = Box<dyn FnMut(&Span)>;
+
+#[cfg(feature = "tracing")]
+thread_local! {
+    static SUBSCRIBER: RefCell<Option<Subscriber>> = const { RefCell::new(None) };
+}
+
+/// Calls `subscriber` with a [`Span`] for every stage `f` runs on this thread, then returns `f`'s result.
+///
+/// Without a subscriber the stages are not measured at all.
+/// The previous subscriber is restored afterwards, also when `f` panics.
+///
+/// ```
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+///
+/// let spans = Rc::new(RefCell::new(Vec::new()));
+/// let recorded = spans.clone();
+/// glyph::trace::with_subscriber(
+///     move |span| recorded.borrow_mut().push(span.clone()),
+///     || {
+///         let ui = glyph::from_str("@language ratatui\n@Form main { @Panel p { @Button ok { text = \"OK\" } } }").unwrap();
+///         glyph::validate::validate_all(&ui)
+///     },
+/// );
+///
+/// let spans = spans.borrow();
+/// let stages: Vec<&str> = spans.iter().map(|span| span.stage).collect();
+/// assert_eq!(stages, ["parse", "lower", "validate"]);
+/// assert!(spans.iter().all(|span| (span.elements, span.depth) == (3, 3)));
+///
+/// // Outside of `with_subscriber` nothing is reported.
+/// glyph::from_str("@language ratatui\n@Form main { }").unwrap();
+/// assert_eq!(spans.len(), 3);
+/// ```
+#[cfg(feature = "tracing")]
+pub fn with_subscriber<R>(subscriber: impl FnMut(&Span) + 'static, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Subscriber>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SUBSCRIBER.set(self.0.take());
+        }
+    }
+
+    let _restore = Restore(SUBSCRIBER.replace(Some(Box::new(subscriber))));
+    f()
+}
+
+/// Runs one stage of the pipeline and reports it to the subscriber of [`with_subscriber`].
+/// `shape` measures the elements and depth of the tree, only when there is a subscriber.
+#[cfg(feature = "tracing")]
+pub(crate) fn stage<T>(name: &'static str, run: impl FnOnce() -> T, shape: impl FnOnce(&T) -> (usize, usize)) -> T {
+    if SUBSCRIBER.with_borrow(Option::is_none) {
+        return run();
+    }
+    let start = Instant::now();
+    let result = run();
+    let elapsed = start.elapsed();
+    let (elements, depth) = shape(&result);
+    // The subscriber is taken out while it runs, so it may run stages itself without reporting them.
+    if let Some(mut subscriber) = SUBSCRIBER.take() {
+        subscriber(&Span { stage: name, elements, depth, elapsed });
+        SUBSCRIBER.with_borrow_mut(|slot| {
+            slot.get_or_insert(subscriber);
+        });
+    }
+    result
+}
+
+/// Runs one stage of the pipeline, without the `tracing` feature nothing is reported.
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn stage<T>(_: &'static str, run: impl FnOnce() -> T, _: impl FnOnce(&T) -> (usize, usize)) -> T {
+    run()
+}
+
+/// Returns the number of elements and the depth of a parsed element tree.
+pub(crate) fn source_shape(element: &parser::Element) -> (usize, usize) {
+    element.all_children().map(source_shape).fold((1, 1), |(elements, depth), (child_elements, child_depth)| {
+        (elements + child_elements, depth.max(child_depth + 1))
+    })
+}
+
+/// Returns the number of elements and the depth of a UI, see [`validate::complexity`].
+pub(crate) fn ui_shape(ui: &UI) -> (usize, usize) {
+    let complexity = validate::complexity(ui);
+    (complexity.total_elements, complexity.max_depth)
+}
@@ -1,3 +1,12 @@
+use core::any::Any;
+use core::ops::ControlFlow;
+#[cfg(feature = "std")]
+use std::path::PathBuf;
+
+use crate::compat::*;
+use crate::parser::Property;
+use crate::{GlyphError, Result};
+
 /// Represents the schema for the UI structure and additional components.
 /// The functionality of this element is currently not implemented.
 ///
@@ -12,7 +21,7 @@
 /// ```glyph
 /// @language ratatui
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Language {
     Ratatui,
     AnyOther {
@@ -21,16 +30,280 @@ pub enum Language {
     }
 }
 
+/// Represents the name of an element.
+///
+/// A `String` by default. With the `shared-names` feature it is an `Arc<str>`,
+/// so clones of a tree share the name data instead of copying it.
+/// Code that builds elements by hand can write `"ok".into()` to work with both.
+///
+/// ```
+/// let ui = glyph::from_str("@language ratatui\n@Form main { }").unwrap();
+/// let copy = ui.clone();
+/// let (name, copied) = (&ui.root.as_form().unwrap().name, &copy.root.as_form().unwrap().name);
+/// assert_eq!(name, copied);
+/// #[cfg(feature = "shared-names")]
+/// assert!(std::sync::Arc::ptr_eq(name, copied));
+/// ```
+pub type Name = NameRepr;
+
+#[cfg(not(feature = "shared-names"))]
+type NameRepr = String;
+#[cfg(feature = "shared-names")]
+type NameRepr = alloc::sync::Arc<str>;
+
 /// Represents the root UI structure.
 ///
 /// # Fields
 ///
 /// - `language` (`Language`) - The used schema.
 /// - `root` (`Element`) - The root element of the UI.
-#[derive(Debug, Clone)]
+/// - `assertions` (`Vec<Assertion>`) - The `@assert` directives, see [`crate::validate::check_assertions`].
+#[derive(Debug, Clone, PartialEq)]
 pub struct UI {
     pub language: Language,
     pub root: Element,
+    pub assertions: Vec<Assertion>,
+}
+
+impl UI {
+    /// Returns the named element and everything below it as a UI of its own, with the same language.
+    ///
+    /// Radios, columns, tabs and menu items can be extracted too; dropdown options cannot, as they are
+    /// not elements. Only the assertions on elements inside the subtree are kept.
+    ///
+    /// ```
+    /// let ui = glyph::from_str(r#"
+    ///     @language ratatui
+    ///     @Form main {
+    ///         @Panel details {
+    ///             @Label title { text = "Details" }
+    ///             @TextInput notes {}
+    ///         }
+    ///         @Button save { text = "Save" }
+    ///     }
+    ///     @assert element="notes" min-width=10
+    ///     @assert element="save" min-width=4
+    /// "#).unwrap();
+    /// let details = ui.subtree("details").unwrap();
+    /// assert!(matches!(details.language, glyph::types::Language::Ratatui));
+    /// let panel = details.root.as_panel().unwrap();
+    /// assert_eq!(panel.children.len(), 2);
+    /// assert_eq!(panel.children[0].as_label().unwrap().text, "Details");
+    /// assert!(panel.children[1].as_text_input().is_some());
+    /// assert_eq!(details.assertions.len(), 1);
+    /// assert!(ui.subtree("missing").is_none());
+    /// ```
+    pub fn subtree(&self, name: &str) -> Option<UI> {
+        use crate::validate::{Node, try_visit, visit};
+
+        let found = try_visit(&self.root, &mut |node| {
+            if node.name() != name {
+                return ControlFlow::Continue(());
+            }
+            ControlFlow::Break(match node {
+                Node::Element(element) => Some(element.clone()),
+                Node::Radio(radio) => Some(Element::Radio(radio.clone())),
+                Node::Column(column) => Some(Element::Column(column.clone())),
+                Node::Tab(tab) => Some(Element::Tab(tab.clone())),
+                Node::MenuItem(item) => Some(Element::MenuItem(item.clone())),
+                Node::Option(_) => None,
+            })
+        });
+        let ControlFlow::Break(Some(root)) = found else {
+            return None;
+        };
+        let mut names = crate::compat::HashSet::new();
+        visit(&root, &mut |node| {
+            names.insert(node.name().to_string());
+        });
+        let assertions = self.assertions.iter().filter(|assertion| names.contains(&assertion.element)).cloned().collect();
+        Some(UI { language: self.language.clone(), root, assertions })
+    }
+
+    /// Calls `f` for every element of the tree, parents before their children, until it breaks.
+    ///
+    /// Returns the break of `f`, or `Continue` when it walked the whole tree.
+    /// Radios, dropdown options, grid columns, tabs and menu items are not [`Element`]s in the tree
+    /// and are not passed to `f`, the elements on the pages of tabs are.
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    ///
+    /// let ui = glyph::from_str(r#"
+    ///     @language ratatui
+    ///     @Form main {
+    ///         @Panel toolbar {
+    ///             @Label hint { text = "Save first" }
+    ///             @Button save { text = "Save" }
+    ///             @Button quit { text = "Quit" }
+    ///         }
+    ///         @Label status { text = "Ready" }
+    ///     }
+    /// "#).unwrap();
+    /// let mut visited = Vec::new();
+    /// let first = ui.try_walk(|element| {
+    ///     visited.push(element.name().to_string());
+    ///     match element.as_button() {
+    ///         Some(button) => ControlFlow::Break(button.text.clone()),
+    ///         None => ControlFlow::Continue(()),
+    ///     }
+    /// });
+    /// assert_eq!(first, ControlFlow::Break("Save".to_string()));
+    /// assert_eq!(visited, ["main", "toolbar", "hint", "save"]);
+    ///
+    /// let mut count = 0;
+    /// let all = ui.try_walk(|_| {
+    ///     count += 1;
+    ///     ControlFlow::<()>::Continue(())
+    /// });
+    /// assert_eq!((all, count), (ControlFlow::Continue(()), 6));
+    /// ```
+    pub fn try_walk<B>(&self, mut f: impl FnMut(&Element) -> ControlFlow<B>) -> ControlFlow<B> {
+        crate::validate::try_visit(&self.root, &mut |node| match node {
+            crate::validate::Node::Element(element) => f(element),
+            _ => ControlFlow::Continue(()),
+        })
+    }
+
+    /// Returns the first element with the given name, for editing it in place.
+    ///
+    /// Like [`UI::try_walk`], only [`Element`]s of the tree are found, including the ones on
+    /// the pages of tabs. See [`UI::with_element_mut`] for edits that keep the UI valid.
+    ///
+    /// ```
+    /// let mut ui = glyph::from_str(r#"
+    ///     @language ratatui
+    ///     @Form main {
+    ///         @StatusBar status { @Label hint { text = "Ready" } }
+    ///     }
+    /// "#).unwrap();
+    ///
+    /// let glyph::Element::Label(hint) = ui.find_mut("hint").unwrap() else { panic!() };
+    /// hint.text = "Saved".to_string();
+    /// let status = ui.root.as_form().unwrap().children[0].as_status_bar().unwrap();
+    /// assert_eq!(status.children[0].as_label().unwrap().text, "Saved");
+    /// assert!(ui.find_mut("missing").is_none());
+    /// ```
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut Element> {
+        let mut path = Vec::new();
+        find_path(&mut self.root, name, &mut path).then(|| element_at(&mut self.root, &path))
+    }
+
+    /// Edits the first element with the given name with `f`, and keeps the edit only if the UI stays valid.
+    ///
+    /// The UI is validated again after `f` ran. When it has error diagnostics it did not have before,
+    /// the element is put back as it was and the new diagnostics are returned as a
+    /// [`GlyphError::Validate`]. Fails with a [`GlyphError::Element`] without calling `f` when no element has the name.
+    ///
+    /// ```
+    /// use glyph::{Element, GlyphError};
+    ///
+    /// let mut ui = glyph::from_str(r#"
+    ///     @language ratatui
+    ///     @Form main {
+    ///         @RadioGroup size {
+    ///             selected = "small"
+    ///             @Radio small { label = "Small" }
+    ///             @Radio large { label = "Large" }
+    ///         }
+    ///     }
+    /// "#).unwrap();
+    /// let select = |value: &'static str| move |element: &mut Element| {
+    ///     if let Element::RadioGroup(group) = element {
+    ///         group.selected_radio = value.to_string();
+    ///     }
+    /// };
+    ///
+    /// ui.with_element_mut("size", select("large")).unwrap();
+    /// let selected = |ui: &glyph::UI| ui.root.as_form().unwrap().children[0].as_radio_group().unwrap().selected_radio.clone();
+    /// assert_eq!(selected(&ui), "large");
+    ///
+    /// let before = ui.clone();
+    /// let Err(GlyphError::Validate(diagnostics)) = ui.with_element_mut("size", select("huge")) else { panic!() };
+    /// assert_eq!(diagnostics[0].code, "GL007");
+    /// assert_eq!(ui, before);
+    ///
+    /// assert!(matches!(ui.with_element_mut("missing", |_| ()), Err(GlyphError::Element(_))));
+    /// ```
+    pub fn with_element_mut<R>(&mut self, name: &str, f: impl FnOnce(&mut Element) -> R) -> Result<R> {
+        let mut path = Vec::new();
+        if !find_path(&mut self.root, name, &mut path) {
+            return Err(GlyphError::Element(format!("no element is named `{name}`")));
+        }
+        let before = validation_errors(self);
+        let element = element_at(&mut self.root, &path);
+        let backup = element.clone();
+        let result = f(element);
+        let introduced: Vec<_> = validation_errors(self).into_iter().filter(|error| !before.contains(error)).collect();
+        if !introduced.is_empty() {
+            *element_at(&mut self.root, &path) = backup;
+            return Err(GlyphError::Validate(introduced));
+        }
+        Ok(result)
+    }
+}
+
+/// Returns the error diagnostics of [`crate::validate::validate_all`].
+fn validation_errors(ui: &UI) -> Vec<crate::validate::Diagnostic> {
+    let mut diagnostics = crate::validate::validate_all(ui);
+    diagnostics.retain(|diagnostic| diagnostic.severity == crate::validate::Severity::Error);
+    diagnostics
+}
+
+/// Returns the elements directly below `element`, those on the pages of a tab control included.
+fn child_elements_mut(element: &mut Element) -> Vec<&mut Element> {
+    match element {
+        Element::Form(form) => form.children.iter_mut().collect(),
+        Element::Panel(panel) => panel.children.iter_mut().collect(),
+        Element::CheckboxGroup(group) => group.children.iter_mut().collect(),
+        Element::Modal(modal) => modal.children.iter_mut().collect(),
+        Element::Tabs(tabs) => tabs.children.iter_mut().flat_map(|tab| tab.children.iter_mut()).collect(),
+        Element::Tab(tab) => tab.children.iter_mut().collect(),
+        Element::StatusBar(status_bar) => status_bar.children.iter_mut().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Finds the first element with the given name below `element`, leaving the indices
+/// of [`child_elements_mut`] that lead to it in `path`.
+fn find_path(element: &mut Element, name: &str, path: &mut Vec<usize>) -> bool {
+    if element.name() == name {
+        return true;
+    }
+    for (index, child) in child_elements_mut(element).into_iter().enumerate() {
+        path.push(index);
+        if find_path(child, name, path) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+/// Returns the element `path` leads to, see [`find_path`].
+fn element_at<'a>(element: &'a mut Element, path: &[usize]) -> &'a mut Element {
+    match path.split_first() {
+        None => element,
+        Some((index, rest)) => element_at(child_elements_mut(element).swap_remove(*index), rest),
+    }
+}
+
+/// Represents an `@assert` directive on the size an element is laid out with.
+///
+/// # Fields
+///
+/// - `element` (`String`) - The name of the checked element.
+/// - `min_width` (`Option<u32>`) - The smallest allowed width.
+/// - `max_width` (`Option<u32>`) - The largest allowed width.
+/// - `min_height` (`Option<u32>`) - The smallest allowed height.
+/// - `max_height` (`Option<u32>`) - The largest allowed height.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Assertion {
+    pub element: String,
+    pub min_width: Option<u32>,
+    pub max_width: Option<u32>,
+    pub min_height: Option<u32>,
+    pub max_height: Option<u32>,
 }
 
 /// Represents any UI element.
@@ -51,8 +324,19 @@ pub struct UI {
 /// - `Modal(Modal)` - Modal dialog.
 /// - `Tabs(Tabs)` - Tabbed interface.
 /// - `Tab(Tab)` - Single tab.
+/// - `MenuBar(MenuBar)` - Menu bar at the top of a form.
+/// - `MenuItem(MenuItem)` - Entry of a menu.
+/// - `StatusBar(StatusBar)` - Status bar at the bottom of a form.
 /// - `Custom(CustomElement)` - Custom UI element.
-#[derive(Debug, Clone)]
+///
+/// New variants may be added in future releases, so code matching on
+/// `Element` outside of this crate needs a wildcard arm. Backends can use
+/// [`crate::render::render_placeholder`] for variants they do not handle.
+///
+/// Elements are equal when all of their fields are, custom elements compare
+/// their implementations with [`CustomUIElement::dyn_eq`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum Element {
     Form(Form),
     Panel(Panel),
@@ -60,6 +344,7 @@ pub enum Element {
     TextInput(TextInput),
     Button(Button),
     Checkbox(Checkbox),
+    CheckboxGroup(CheckboxGroup),
     RadioGroup(RadioGroup),
     Radio(Radio),
     Dropdown(Dropdown),
@@ -68,9 +353,216 @@ pub enum Element {
     Modal(Modal),
     Tabs(Tabs),
     Tab(Tab),
+    MenuBar(MenuBar),
+    MenuItem(MenuItem),
+    StatusBar(StatusBar),
     Custom(CustomElement),
 }
 
+impl Element {
+    /// Returns the name of the element, whatever its kind.
+    ///
+    /// ```
+    /// use glyph::{Element, Panel};
+    ///
+    /// let panel = Element::Panel(Panel { name: "sidebar".into(), ..Default::default() });
+    /// assert_eq!(panel.name(), "sidebar");
+    /// ```
+    pub fn name(&self) -> &str {
+        match self {
+            Element::Form(form) => &form.name,
+            Element::Panel(panel) => &panel.name,
+            Element::Label(label) => &label.name,
+            Element::TextInput(input) => &input.name,
+            Element::Button(button) => &button.name,
+            Element::Checkbox(checkbox) => &checkbox.name,
+            Element::CheckboxGroup(group) => &group.name,
+            Element::RadioGroup(group) => &group.name,
+            Element::Radio(radio) => &radio.name,
+            Element::Dropdown(dropdown) => &dropdown.name,
+            Element::Grid(grid) => &grid.name,
+            Element::Column(column) => &column.name,
+            Element::Modal(modal) => &modal.name,
+            Element::Tabs(tabs) => &tabs.name,
+            Element::Tab(tab) => &tab.name,
+            Element::MenuBar(menu_bar) => &menu_bar.name,
+            Element::MenuItem(item) => &item.name,
+            Element::StatusBar(status_bar) => &status_bar.name,
+            Element::Custom(custom) => &custom.name,
+        }
+    }
+
+    /// Returns the name of the element kind, as written after `@` in the source.
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Element::Form(_) => "Form",
+            Element::Panel(_) => "Panel",
+            Element::Label(_) => "Label",
+            Element::TextInput(_) => "TextInput",
+            Element::Button(_) => "Button",
+            Element::Checkbox(_) => "Checkbox",
+            Element::CheckboxGroup(_) => "CheckboxGroup",
+            Element::RadioGroup(_) => "RadioGroup",
+            Element::Radio(_) => "Radio",
+            Element::Dropdown(_) => "Dropdown",
+            Element::Grid(_) => "Grid",
+            Element::Column(_) => "Column",
+            Element::Modal(_) => "Modal",
+            Element::Tabs(_) => "Tabs",
+            Element::Tab(_) => "Tab",
+            Element::MenuBar(_) => "MenuBar",
+            Element::MenuItem(_) => "MenuItem",
+            Element::StatusBar(_) => "StatusBar",
+            Element::Custom(_) => "Custom",
+        }
+    }
+
+    pub fn as_form(&self) -> Option<&Form> {
+        match self {
+            Element::Form(form) => Some(form),
+            _ => None,
+        }
+    }
+
+    pub fn as_panel(&self) -> Option<&Panel> {
+        match self {
+            Element::Panel(panel) => Some(panel),
+            _ => None,
+        }
+    }
+
+    pub fn as_label(&self) -> Option<&Label> {
+        match self {
+            Element::Label(label) => Some(label),
+            _ => None,
+        }
+    }
+
+    pub fn as_text_input(&self) -> Option<&TextInput> {
+        match self {
+            Element::TextInput(text_input) => Some(text_input),
+            _ => None,
+        }
+    }
+
+    /// Returns the button if this element is one.
+    ///
+    /// ```
+    /// let ui = glyph::from_str(r#"
+    ///     @language ratatui
+    ///     @Form main { @Button save { text = "Save" } }
+    /// "#)?;
+    /// let form = ui.root.as_form().unwrap();
+    /// let button = form.children.iter().find_map(|child| child.as_button()).unwrap();
+    /// assert_eq!(button.text, "Save");
+    /// # Ok::<(), glyph::GlyphError>(())
+    /// ```
+    pub fn as_button(&self) -> Option<&Button> {
+        match self {
+            Element::Button(button) => Some(button),
+            _ => None,
+        }
+    }
+
+    pub fn as_checkbox(&self) -> Option<&Checkbox> {
+        match self {
+            Element::Checkbox(checkbox) => Some(checkbox),
+            _ => None,
+        }
+    }
+
+    pub fn as_checkbox_group(&self) -> Option<&CheckboxGroup> {
+        match self {
+            Element::CheckboxGroup(group) => Some(group),
+            _ => None,
+        }
+    }
+
+    pub fn as_radio_group(&self) -> Option<&RadioGroup> {
+        match self {
+            Element::RadioGroup(radio_group) => Some(radio_group),
+            _ => None,
+        }
+    }
+
+    pub fn as_radio(&self) -> Option<&Radio> {
+        match self {
+            Element::Radio(radio) => Some(radio),
+            _ => None,
+        }
+    }
+
+    pub fn as_dropdown(&self) -> Option<&Dropdown> {
+        match self {
+            Element::Dropdown(dropdown) => Some(dropdown),
+            _ => None,
+        }
+    }
+
+    pub fn as_grid(&self) -> Option<&Grid> {
+        match self {
+            Element::Grid(grid) => Some(grid),
+            _ => None,
+        }
+    }
+
+    pub fn as_column(&self) -> Option<&Column> {
+        match self {
+            Element::Column(column) => Some(column),
+            _ => None,
+        }
+    }
+
+    pub fn as_modal(&self) -> Option<&Modal> {
+        match self {
+            Element::Modal(modal) => Some(modal),
+            _ => None,
+        }
+    }
+
+    pub fn as_tabs(&self) -> Option<&Tabs> {
+        match self {
+            Element::Tabs(tabs) => Some(tabs),
+            _ => None,
+        }
+    }
+
+    pub fn as_tab(&self) -> Option<&Tab> {
+        match self {
+            Element::Tab(tab) => Some(tab),
+            _ => None,
+        }
+    }
+
+    pub fn as_menu_bar(&self) -> Option<&MenuBar> {
+        match self {
+            Element::MenuBar(menu_bar) => Some(menu_bar),
+            _ => None,
+        }
+    }
+
+    pub fn as_menu_item(&self) -> Option<&MenuItem> {
+        match self {
+            Element::MenuItem(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn as_status_bar(&self) -> Option<&StatusBar> {
+        match self {
+            Element::StatusBar(status_bar) => Some(status_bar),
+            _ => None,
+        }
+    }
+
+    pub fn as_custom(&self) -> Option<&CustomElement> {
+        match self {
+            Element::Custom(custom) => Some(custom),
+            _ => None,
+        }
+    }
+}
+
 /// Represents arrangement options for children inside a container.
 ///
 /// # Variants
@@ -80,11 +572,12 @@ pub enum Element {
 /// - `TopToBottom` - Arrange children from top to bottom.
 /// - `BottomToTop` - Arrange children from bottom to top.
 /// - `FreeForm` - Allow children to be positioned freely
-///                and force usage of absolute positions.
-#[derive(Debug, Clone)]
+///   and force usage of absolute positions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub enum Layout {
     LeftToRight,
     RightToLeft,
+    #[default]
     TopToBottom,
     BottomToTop,
     FreeForm,
@@ -97,12 +590,151 @@ pub enum Layout {
 /// - `Auto` - The size is determined automatically - equivalent to `None`.
 /// - `Fixed(u32)` - The size is a fixed value.
 /// - `Percentage(u32)` - The size is a percentage of the parent element's size.
+/// - `RelativeTo { name, percent }` - The size is a percentage of the named element's
+///   width (for `width` and `left`) or height (for `height` and `top`).
+/// - `Fraction(u32)` - A share of the space left after fixed and percentage sizes, written `2fr`.
+///   The space is divided by weight, an `Auto` size weighs as much as `1fr`.
+/// - `MinContent` - The smallest size the content allows, written `min-content`.
+///   For a label's width, the width of its longest word.
+/// - `MaxContent` - The natural size of the content, written `max-content`.
+///   For a label's width, the width of its longest unwrapped line.
+/// - `Responsive { cases, otherwise }` - A size chosen by the size of the area the UI is arranged in,
+///   written `40 when-width<80 else 60`. The size of the first case whose breakpoint matches is used,
+///   `otherwise` when none does. Layout resolves it before anything else, see [`Breakpoint`].
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         layout = left-to-right
+///         @Label narrow { text = "a wonderful day" width = min-content word-wrap = true }
+///         @Label wide { text = "a wonderful day" width = max-content }
+///     }
+/// "#).unwrap();
+/// let rects = glyph::layout::arrange(&ui, glyph::layout::Rect::new(0, 0, 60, 10));
+/// assert_eq!(rects["narrow"].width, 9);
+/// assert_eq!(rects["wide"].width, 15);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub enum SizeConstraint {
+    #[default]
     Auto,
     Fixed(u32),
     Percentage(u32),
+    RelativeTo { name: String, percent: u32 },
+    Fraction(u32),
+    MinContent,
+    MaxContent,
+    Responsive { cases: Vec<(Breakpoint, SizeConstraint)>, otherwise: Box<SizeConstraint> },
+}
+
+impl SizeConstraint {
+    /// Returns the size chosen for an area of `width` by `height` cells, the size itself when it is not responsive.
+    pub fn for_area(&self, width: u32, height: u32) -> &SizeConstraint {
+        match self {
+            SizeConstraint::Responsive { cases, otherwise } => cases
+                .iter()
+                .find(|(breakpoint, _)| breakpoint.matches(width, height))
+                .map_or(&**otherwise, |(_, size)| size)
+                .for_area(width, height),
+            size => size,
+        }
+    }
+}
+
+/// Represents a condition on the size of the area a UI is arranged in, written `when-width<80`.
+///
+/// # Fields
+///
+/// - `dimension` (`Dimension`) - Side of the area that is compared.
+/// - `comparison` (`Comparison`) - How the side compares to `cells`.
+/// - `cells` (`u32`) - Size the side is compared to.
+///
+/// The area is the one handed to [`arrange`](crate::layout::arrange), usually the whole terminal:
+///
+/// ```
+/// use glyph::layout::{Rect, arrange};
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Panel sidebar {
+///             width = 40 when-width<80 else 60
+///             height = 3 when-height<=24 else 10
+///         }
+///     }
+/// "#).unwrap();
+///
+/// let small = arrange(&ui, Rect::new(0, 0, 70, 24));
+/// assert_eq!((small["sidebar"].width, small["sidebar"].height), (40, 3));
+/// let large = arrange(&ui, Rect::new(0, 0, 120, 40));
+/// assert_eq!((large["sidebar"].width, large["sidebar"].height), (60, 10));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Breakpoint {
+    pub dimension: Dimension,
+    pub comparison: Comparison,
+    pub cells: u32,
+}
+
+impl Breakpoint {
+    /// Returns whether an area of `width` by `height` cells meets the condition.
+    pub fn matches(&self, width: u32, height: u32) -> bool {
+        let side = match self.dimension {
+            Dimension::Width => width,
+            Dimension::Height => height,
+        };
+        match self.comparison {
+            Comparison::Less => side < self.cells,
+            Comparison::LessOrEqual => side <= self.cells,
+            Comparison::Greater => side > self.cells,
+            Comparison::GreaterOrEqual => side >= self.cells,
+        }
+    }
+}
+
+impl core::fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let dimension = match self.dimension {
+            Dimension::Width => "width",
+            Dimension::Height => "height",
+        };
+        let comparison = match self.comparison {
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+        };
+        write!(f, "when-{dimension}{comparison}{}", self.cells)
+    }
+}
+
+/// Represents the side of the area a [`Breakpoint`] compares.
+///
+/// # Variants
+///
+/// - `Width` - The width of the area, written `width`.
+/// - `Height` - The height of the area, written `height`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    Width,
+    Height,
+}
+
+/// Represents how a [`Breakpoint`] compares a side of the area.
+///
+/// # Variants
+///
+/// - `Less` - The side is smaller, written `<`.
+/// - `LessOrEqual` - The side is smaller or the same, written `<=`.
+/// - `Greater` - The side is larger, written `>`.
+/// - `GreaterOrEqual` - The side is larger or the same, written `>=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
 }
 
 /// Represents size constraints for UI elements.
@@ -113,7 +745,7 @@ pub enum SizeConstraint {
 /// - `height` (`SizeConstraint`) - Height of the element.
 /// - `left` (`SizeConstraint`) - Left margin of the element.
 /// - `top` (`SizeConstraint`) - Top margin of the element.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct SizeConstraints {
     pub width: SizeConstraint,
     pub height: SizeConstraint,
@@ -125,18 +757,51 @@ pub struct SizeConstraints {
 ///
 /// # Fields
 ///
+/// - `name` (`String`) - Name of the form.
+/// - `id` (`String`) - Path of the form in the tree, see [`crate::id::assign_ids`].
+/// - `role` (`Option<String>`) - Accessibility role of the form, from the `role` property.
 /// - `title` (`String`) - Title of the form.
 /// - `layout` (`Layout`) - Layout of the form.
 /// - `children` (`Vec<Element>`) - Children elements of the form.
-#[derive(Debug, Clone)]
+/// - `focus_order` (`Vec<String>`) - Names of the interactive elements in the order the keyboard focus
+///   moves through them, empty for the order they are written in. See [`crate::focus::focus_order`].
+/// - `events` (`HashMap<String, String>`) - Handler names by event, from `on-*` properties.
+/// - `doc` (`Option<String>`) - Doc comment written above the form.
+/// - `source` (`Option<String>`) - The form as written, see [`crate::lower::LowerOptions::keep_source`].
+/// - `extra` (`Vec<Property>`) - Properties not modelled by the form, kept for emitting.
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Form {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub title: String,
     pub layout: Layout,
     pub children: Vec<Element>,
+    pub focus_order: Vec<String>,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
 }
 
 /// Represents margins around a UI element.
-#[derive(Debug, Clone)]
+///
+/// Margins are unsigned, so elements can not be shifted outside of the area
+/// assigned to them. Negative margin literals such as `margins-left = -2`
+/// are rejected with an error during lowering instead of being clamped.
+///
+/// # Syntax
+///
+/// ```glyph
+/// margins-left = 2
+/// margins-top = 1
+/// ```
+///
+/// ```
+/// let error = glyph::from_str("@language ratatui\n@Form f { @Button ok { margins-left = -2 } }").unwrap_err();
+/// assert_eq!(error.to_string(), "property `margins-left` of element `ok` must not be negative, found -2");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct Margins {
     pub left: u32,
     pub right: u32,
@@ -145,66 +810,516 @@ pub struct Margins {
 }
 
 /// Represents a panel container for grouping elements.
-#[derive(Debug, Clone)]
+///
+/// A `collapsed` panel only occupies its title row and its children are not laid out.
+/// `collapsible` marks panels a renderer may let the user collapse and expand.
+/// A panel with `border = none` draws neither border nor title and hands its whole area to the children.
+/// A panel with `scroll` lays its children out at their natural size along the scrolled axes,
+/// even where that is larger than the panel, see [`crate::layout::scroll_contents`].
+/// The title sits on the top border where `title-align` puts it, `title-brackets` shows it as `[title]`.
+/// A panel without a title has an unbroken top border, also with `title-brackets`.
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Panel centered { title = "Files" title-align = center title-brackets }
+///         @Panel untitled { title-brackets }
+///     }
+/// "#).unwrap();
+/// let expected = "\
+/// ┌──────────────────┐
+/// │┌────[Files]─────┐│
+/// │└────────────────┘│
+/// │┌────────────────┐│
+/// │└────────────────┘│
+/// └──────────────────┘";
+/// assert_eq!(glyph::render::render_ascii(&ui, 20, 6), expected);
+/// ```
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         layout = left-to-right
+///         @Panel framed { border = rounded @Label a { text = "framed" } }
+///         @Panel bare { border = none @Label b { text = "bare" } }
+///     }
+/// "#).unwrap();
+/// let expected = "\
+/// ┌──────────────────────┐
+/// │╭─────────╮bare       │
+/// ││framed   │           │
+/// │╰─────────╯           │
+/// └──────────────────────┘";
+/// assert_eq!(glyph::render::render_ascii(&ui, 24, 5), expected);
+///
+/// let rects = glyph::layout::arrange(&ui, glyph::layout::Rect::new(0, 0, 24, 5));
+/// assert_eq!(rects["b"], rects["bare"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Panel {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub title: String,
+    pub title_align: Alignment,
+    pub title_brackets: bool,
     pub layout: Layout,
     pub children: Vec<Element>,
     pub size_constraints: SizeConstraints,
     pub margins: Margins,
+    pub collapsible: bool,
+    pub collapsed: bool,
+    pub border: BorderStyle,
+    pub scroll: ScrollMode,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
+}
+
+/// Represents the lines a panel's border is drawn with.
+///
+/// # Variants
+///
+/// - `Plain` - Single thin lines with square corners.
+/// - `Rounded` - Single thin lines with rounded corners.
+/// - `Double` - Double lines.
+/// - `Thick` - Single thick lines.
+/// - `None` - No border, the children get the border cells too.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BorderStyle {
+    #[default]
+    Plain,
+    Rounded,
+    Double,
+    Thick,
+    None,
+}
+
+/// Represents the axes along which a panel's content scrolls.
+///
+/// # Variants
+///
+/// - `None` - The content is fitted into the panel.
+/// - `Vertical` - The content may be taller than the panel.
+/// - `Horizontal` - The content may be wider than the panel.
+/// - `Both` - The content may be taller and wider than the panel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ScrollMode {
+    #[default]
+    None,
+    Vertical,
+    Horizontal,
+    Both,
+}
+
+/// Represents the horizontal alignment of text within its element.
+///
+/// # Variants
+///
+/// - `Left` - Lines start at the left edge.
+/// - `Center` - Lines are centered, an odd leftover cell goes to the right.
+/// - `Right` - Lines end at the right edge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Represents a run of label text drawn in one style, see [`text::parse_markup`](crate::text::parse_markup).
+///
+/// # Fields
+///
+/// - `text` (`String`) - The text of the run, with the markup removed.
+/// - `bold` (`bool`) - Whether the run was written between `*` marks.
+/// - `underline` (`bool`) - Whether the run was written between `_` marks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TextSpan {
+    pub text: String,
+    pub bold: bool,
+    pub underline: bool,
 }
 
 /// Represents a standalone text label.
-#[derive(Debug, Clone)]
+///
+/// With `word_wrap`, every wrapped line is aligned on its own.
+/// With `markup`, the text may mark `*bold*` and `_underlined_` runs, which are kept in `spans`
+/// next to the raw text. Renderers draw the runs without their marks and style them where they
+/// can; wrapped labels are drawn unstyled.
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Label title { text = "Hi" align = center }
+///     }
+/// "#).unwrap();
+/// let expected = "\
+/// ┌──────────┐
+/// │    Hi    │
+/// └──────────┘";
+/// assert_eq!(glyph::render::render_ascii(&ui, 12, 3), expected);
+/// ```
+///
+/// ```
+/// use glyph::render::{DrawCall, RecordingBackend, render};
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Label note { text = "a *b* \*c" markup }
+///     }
+/// "#).unwrap();
+/// assert_eq!(glyph::render::render_ascii(&ui, 10, 3).lines().nth(1), Some("│a b *c  │"));
+///
+/// let mut backend = RecordingBackend::default();
+/// render(&ui, glyph::layout::Rect::new(0, 0, 10, 3), &mut backend);
+/// let bold: Vec<_> = backend.calls.iter().filter_map(|call| match call {
+///     DrawCall::StyledText { x, span, .. } if span.bold => Some((*x, span.text.as_str())),
+///     _ => None,
+/// }).collect();
+/// assert_eq!(bold, [(3, "b")]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Label {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub text: String,
     pub word_wrap: bool,
+    pub align: Alignment,
+    pub markup: bool,
+    pub spans: Vec<TextSpan>,
     pub size_constraints: SizeConstraints,
     pub margins: Margins,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
+}
+
+impl Label {
+    /// Returns the text as it is drawn, which is the text without its markup when `markup` is set.
+    pub fn plain_text(&self) -> String {
+        if self.markup {
+            self.spans.iter().map(|span| span.text.as_str()).collect()
+        } else {
+            self.text.clone()
+        }
+    }
 }
 
 /// Represents a text input field.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct TextInput {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub placeholder: String,
     pub default_text: String,
     pub size_constraints: SizeConstraints,
     pub margins: Margins,
     pub read_only: bool,
+    pub disabled: bool,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
+}
+
+/// Represents the meaning of a button, for renderers to pick its colours by.
+///
+/// The ASCII renderer draws each variant with its own brackets:
+/// `[OK]`, `<OK>`, `(OK)` and `!OK!` in the order below.
+///
+/// # Variants
+///
+/// - `Default` - An ordinary button.
+/// - `Primary` - The main action of its form.
+/// - `Secondary` - An alternative to the main action.
+/// - `Danger` - An action that destroys data or can not be undone.
+///
+/// ```
+/// use glyph::ButtonVariant;
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Button plain { text = "Plain" }
+///         @Button save { text = "Save" variant = primary }
+///         @Button later { text = "Later" variant = secondary }
+///         @Button delete { text = "Delete" variant = danger }
+///         @Button reset { text = "Reset" variant = default }
+///     }
+/// "#).unwrap();
+/// let variants: Vec<ButtonVariant> =
+///     ui.root.as_form().unwrap().children.iter().map(|child| child.as_button().unwrap().variant).collect();
+/// assert_eq!(variants, [
+///     ButtonVariant::Default,
+///     ButtonVariant::Primary,
+///     ButtonVariant::Secondary,
+///     ButtonVariant::Danger,
+///     ButtonVariant::Default,
+/// ]);
+///
+/// let error = glyph::from_str("@language ratatui\n@Form f { @Button b { variant = loud } }").unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "unknown button variant `loud` on element `b`, expected one of: default, primary, secondary, danger"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ButtonVariant {
+    #[default]
+    Default,
+    Primary,
+    Secondary,
+    Danger,
 }
 
 /// Represents a clickable button.
-#[derive(Debug, Clone)]
+///
+/// Like the other interactive elements, a button can be `disabled`:
+/// it is drawn dimmed and keeps the space it was given in the layout.
+/// Its `variant` tells renderers how to style it, see [`ButtonVariant`].
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Button submit { text = "Submit" disabled = true }
+///         @Checkbox terms { label = "I agree" disabled = true }
+///     }
+/// "#).unwrap();
+/// let form = ui.root.as_form().unwrap();
+/// assert!(form.children[0].as_button().unwrap().disabled);
+/// assert!(form.children[1].as_checkbox().unwrap().disabled);
+/// ```
+///
+/// Elements are plain structs, so a parsed element is duplicated and tweaked with struct update
+/// syntax. The copy keeps every field it is not given, `id` and `source` included, which no
+/// longer describe it:
+///
+/// ```
+/// use glyph::{Button, Element};
+///
+/// let mut ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Button ok { text = "OK" variant = primary width = 10 on-click = "submit" }
+///     }
+/// "#).unwrap();
+/// let Element::Form(form) = &mut ui.root else { unreachable!() };
+/// let ok = form.children[0].as_button().unwrap();
+/// let cancel = Button {
+///     name: "cancel".into(),
+///     text: "Cancel".to_string(),
+///     id: String::new(),
+///     source: None,
+///     ..ok.clone()
+/// };
+/// assert_eq!(cancel.variant, ok.variant);
+/// assert_eq!(cancel.size_constraints, ok.size_constraints);
+/// assert_eq!(cancel.events["click"], "submit");
+/// form.children.push(Element::Button(cancel));
+///
+/// let rects = glyph::layout::arrange(&ui, glyph::layout::Rect::new(0, 0, 30, 6));
+/// assert_eq!(rects["cancel"].width, 10);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Button {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub text: String,
     pub size_constraints: SizeConstraints,
     pub margins: Margins,
+    pub disabled: bool,
+    pub variant: ButtonVariant,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
 }
 
 /// Represents a checkbox input.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Checkbox {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub label: String,
     pub checked: bool,
     pub size_constraints: SizeConstraints,
     pub margins: Margins,
+    pub disabled: bool,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
+}
+
+/// Represents a group of checkboxes and how many of them may be checked.
+///
+/// # Fields
+///
+/// - `children` (`Vec<Element>`) - The checkboxes of the group, lowering accepts no other kind.
+/// - `min_checked` (`Option<u32>`) - Fewest checkboxes that must be checked, written `min-checked`.
+/// - `max_checked` (`Option<u32>`) - Most checkboxes that may be checked, written `max-checked`.
+/// - `layout` (`Layout`) - How the checkboxes are arranged, top to bottom by default.
+///
+/// ```
+/// let mut ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @CheckboxGroup toppings {
+///             min-checked = 1
+///             max-checked = 2
+///             @Checkbox cheese { label = "Cheese" checked = true }
+///             @Checkbox ham { label = "Ham" }
+///             @Checkbox olives { label = "Olives" }
+///         }
+///     }
+/// "#).unwrap();
+/// let glyph::Element::Form(form) = &mut ui.root else { unreachable!() };
+/// let glyph::Element::CheckboxGroup(group) = &mut form.children[0] else { unreachable!() };
+/// assert!(group.validate_selection().is_ok());
+///
+/// for child in &mut group.children {
+///     if let glyph::Element::Checkbox(checkbox) = child {
+///         checkbox.checked = false;
+///     }
+/// }
+/// let error = group.validate_selection().unwrap_err().to_string();
+/// assert_eq!(error, "checkbox group `toppings` has 0 checked, at least 1 must be checked");
+///
+/// for child in &mut group.children {
+///     if let glyph::Element::Checkbox(checkbox) = child {
+///         checkbox.checked = true;
+///     }
+/// }
+/// let error = group.validate_selection().unwrap_err().to_string();
+/// assert_eq!(error, "checkbox group `toppings` has 3 checked, at most 2 may be checked");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CheckboxGroup {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
+    pub children: Vec<Element>,
+    pub min_checked: Option<u32>,
+    pub max_checked: Option<u32>,
+    pub layout: Layout,
+    pub size_constraints: SizeConstraints,
+    pub margins: Margins,
+    pub disabled: bool,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
+}
+
+impl CheckboxGroup {
+    /// Returns the checkboxes of the group.
+    pub fn checkboxes(&self) -> impl Iterator<Item = &Checkbox> {
+        self.children.iter().filter_map(Element::as_checkbox)
+    }
+
+    /// Fails if fewer checkboxes are checked than `min_checked` or more than `max_checked`.
+    pub fn validate_selection(&self) -> Result<()> {
+        let checked = self.checkboxes().filter(|checkbox| checkbox.checked).count() as u32;
+        if let Some(min) = self.min_checked
+            && checked < min
+        {
+            return Err(GlyphError::Element(format!(
+                "checkbox group `{}` has {checked} checked, at least {min} must be checked",
+                self.name
+            )));
+        }
+        if let Some(max) = self.max_checked
+            && checked > max
+        {
+            return Err(GlyphError::Element(format!(
+                "checkbox group `{}` has {checked} checked, at most {max} may be checked",
+                self.name
+            )));
+        }
+        Ok(())
+    }
 }
 
 /// Represents a single radio button.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Radio {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub label: String,
     pub value: String,
     pub margins: Margins,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
 }
 
 /// Represents a group of radio buttons.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct RadioGroup {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub children: Vec<Radio>,
     pub selected_radio: String,
+    pub layout: Layout,
     pub size_constraints: SizeConstraints,
     pub margins: Margins,
+    pub disabled: bool,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
+}
+
+impl RadioGroup {
+    /// Selects the radio button with the given value.
+    /// Fails without changing the selection if no contained radio has that value.
+    ///
+    /// ```
+    /// let ui = glyph::from_str(r#"
+    ///     @language ratatui
+    ///     @Form main {
+    ///         @RadioGroup size {
+    ///             @Radio small { label = "Small" value = "s" }
+    ///             @Radio large { label = "Large" value = "l" }
+    ///         }
+    ///     }
+    /// "#).unwrap();
+    /// let mut group = ui.root.as_form().unwrap().children[0].as_radio_group().unwrap().clone();
+    ///
+    /// group.select("l").unwrap();
+    /// assert_eq!(group.selected_radio, "l");
+    ///
+    /// let error = group.select("huge").unwrap_err();
+    /// assert_eq!(error.to_string(), "radio group `size` has no radio with value `huge`, expected one of: s, l");
+    /// assert_eq!(group.selected_radio, "l");
+    /// ```
+    pub fn select(&mut self, value: &str) -> Result<()> {
+        if !self.children.iter().any(|radio| radio.value == value) {
+            let valid: Vec<&str> = self.children.iter().map(|radio| radio.value.as_str()).collect();
+            return Err(GlyphError::Element(format!(
+                "radio group `{}` has no radio with value `{value}`, expected one of: {}",
+                self.name,
+                valid.join(", ")
+            )));
+        }
+        self.selected_radio = value.to_string();
+        Ok(())
+    }
 }
 
 /// Represents one of the possible values for a dropdown option.
@@ -215,7 +1330,26 @@ pub struct RadioGroup {
 /// - `NumberValue(i64)` - Integer value.
 /// - `FloatValue(f64)` - Floating-point value.
 /// - `BoolValue(bool)` - Boolean value.
-#[derive(Debug, Clone)]
+///
+/// The variant follows the written form of the value, a quoted number stays a string:
+///
+/// ```
+/// use glyph::DropdownOptionValue;
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Dropdown answer {
+///             @Option quoted { label = "Quoted" value = "42" }
+///             @Option number { label = "Number" value = 42 }
+///         }
+///     }
+/// "#).unwrap();
+/// let options = &ui.root.as_form().unwrap().children[0].as_dropdown().unwrap().options;
+/// assert!(matches!(&options[0].value, DropdownOptionValue::StringValue(s) if s == "42"));
+/// assert!(matches!(options[1].value, DropdownOptionValue::NumberValue(42)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
 pub enum DropdownOptionValue {
     StringValue(String),
     NumberValue(i64),
@@ -223,48 +1357,504 @@ pub enum DropdownOptionValue {
     BoolValue(bool),
 }
 
+impl From<String> for DropdownOptionValue {
+    fn from(value: String) -> Self {
+        DropdownOptionValue::StringValue(value)
+    }
+}
+
+impl From<&str> for DropdownOptionValue {
+    fn from(value: &str) -> Self {
+        DropdownOptionValue::StringValue(value.to_string())
+    }
+}
+
+impl From<i64> for DropdownOptionValue {
+    fn from(value: i64) -> Self {
+        DropdownOptionValue::NumberValue(value)
+    }
+}
+
+impl From<f64> for DropdownOptionValue {
+    fn from(value: f64) -> Self {
+        DropdownOptionValue::FloatValue(value)
+    }
+}
+
+impl From<bool> for DropdownOptionValue {
+    fn from(value: bool) -> Self {
+        DropdownOptionValue::BoolValue(value)
+    }
+}
+
 /// Represents a single option in a dropdown menu.
-#[derive(Debug, Clone)]
+///
+/// Options written inside `@OptGroup { label = "..." }` carry that label as their `group`,
+/// see [`Dropdown::rows`]. Groups take no name and can not be selected.
+///
+/// ```
+/// let src = r#"
+///     @language ratatui
+///     @Form main {
+///         @Dropdown city {
+///             selected = "Lyon"
+///             @OptGroup { label = "France" @Option paris { label = "Paris" } @Option lyon { label = "Lyon" } }
+///             @Option other { label = "Elsewhere" }
+///             @OptGroup { label = "Italy" @Option rome { label = "Rome" } }
+///         }
+///     }
+/// "#;
+/// let mut ui = glyph::from_str(src).unwrap();
+/// let glyph::Element::Form(form) = &mut ui.root else { unreachable!() };
+/// let glyph::Element::Dropdown(city) = &mut form.children[0] else { unreachable!() };
+/// let groups: Vec<(&str, Option<&str>)> =
+///     city.options.iter().map(|option| (option.label.as_str(), option.group.as_deref())).collect();
+/// assert_eq!(groups, [
+///     ("Paris", Some("France")),
+///     ("Lyon", Some("France")),
+///     ("Elsewhere", None),
+///     ("Rome", Some("Italy")),
+/// ]);
+///
+/// assert!(city.select("France").unwrap_err().to_string().contains("only its options can be selected"));
+/// assert_eq!(city.selected_option, "Lyon");
+///
+/// // The groups are written back as they were read.
+/// assert_eq!(glyph::from_str(&glyph::to_str(&ui)).unwrap(), ui);
+///
+/// let named = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main { @Dropdown d { @OptGroup g { label = "G" @Option o { label = "O" } } } }
+/// "#).unwrap_err();
+/// assert!(named.to_string().contains("`@OptGroup` takes no name"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
 pub struct DropdownOption {
+    pub name: Name,
+    pub id: String,
     pub label: String,
     pub value: DropdownOptionValue,
+    pub group: Option<String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
 }
 
 /// Represents a dropdown menu.
-#[derive(Debug, Clone)]
+///
+/// A `multi` dropdown keeps its selection in `selected_options`, written as a list
+/// such as `selected = ["Red", "Blue"]`, and leaves `selected_option` empty.
+///
+/// ```
+/// let mut ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Dropdown colours {
+///             multi
+///             selected = ["Red", "Blue"]
+///             @Option red { label = "Red" }
+///             @Option green { label = "Green" }
+///             @Option blue { label = "Blue" }
+///         }
+///     }
+/// "#).unwrap();
+/// let glyph::Element::Form(form) = &mut ui.root else { unreachable!() };
+/// let glyph::Element::Dropdown(colours) = &mut form.children[0] else { unreachable!() };
+/// assert!(colours.multi);
+/// assert_eq!(colours.selection(), ["Red", "Blue"]);
+///
+/// colours.select("Green").unwrap();
+/// colours.deselect("Red");
+/// assert_eq!(colours.selected_options, ["Blue", "Green"]);
+/// assert!(colours.select("Purple").is_err());
+/// assert_eq!(colours.selected_options, ["Blue", "Green"]);
+///
+/// let unknown = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main { @Dropdown colours { multi selected = ["Red", "Pink"] @Option red { label = "Red" } } }
+/// "#).unwrap();
+/// let diagnostics = glyph::validate::validate_all(&unknown);
+/// assert_eq!(diagnostics.len(), 1);
+/// assert!(diagnostics[0].message.contains("selects `Pink`"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Dropdown {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub options: Vec<DropdownOption>,
+    pub multi: bool,
     pub selected_option: String,
+    pub selected_options: Vec<String>,
     pub size_constraints: SizeConstraints,
     pub margins: Margins,
+    pub disabled: bool,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
+}
+
+impl Dropdown {
+    /// Selects the option with the given label, a `multi` dropdown adds it to its selection.
+    /// Fails without changing the selection if no option has that label, group headers can not be selected.
+    ///
+    /// ```
+    /// let ui = glyph::from_str(r#"
+    ///     @language ratatui
+    ///     @Form main {
+    ///         @Dropdown fruit {
+    ///             @OptGroup { label = "Citrus" @Option lemon { label = "Lemon" } }
+    ///             @Option apple { label = "Apple" }
+    ///         }
+    ///     }
+    /// "#).unwrap();
+    /// let mut dropdown = ui.root.as_form().unwrap().children[0].as_dropdown().unwrap().clone();
+    ///
+    /// dropdown.select("Apple").unwrap();
+    /// assert_eq!(dropdown.selection(), ["Apple"]);
+    ///
+    /// let error = dropdown.select("Pear").unwrap_err();
+    /// assert_eq!(error.to_string(), "dropdown `fruit` has no option labelled `Pear`, expected one of: Lemon, Apple");
+    /// assert!(dropdown.select("Citrus").is_err());
+    /// assert_eq!(dropdown.selection(), ["Apple"]);
+    /// ```
+    pub fn select(&mut self, label: &str) -> Result<()> {
+        if !self.options.iter().any(|option| option.label == label) {
+            if self.options.iter().any(|option| option.group.as_deref() == Some(label)) {
+                return Err(GlyphError::Element(format!(
+                    "`{label}` is a group of dropdown `{}`, only its options can be selected",
+                    self.name
+                )));
+            }
+            let valid: Vec<&str> = self.options.iter().map(|option| option.label.as_str()).collect();
+            return Err(GlyphError::Element(format!(
+                "dropdown `{}` has no option labelled `{label}`, expected one of: {}",
+                self.name,
+                valid.join(", ")
+            )));
+        }
+        if !self.multi {
+            self.selected_option = label.to_string();
+        } else if !self.selected_options.iter().any(|selected| selected == label) {
+            self.selected_options.push(label.to_string());
+        }
+        Ok(())
+    }
+
+    /// Removes the option with the given label from the selection.
+    pub fn deselect(&mut self, label: &str) {
+        if self.multi {
+            self.selected_options.retain(|selected| selected != label);
+        } else if self.selected_option == label {
+            self.selected_option.clear();
+        }
+    }
+
+    /// Returns the labels of the selected options, at most one unless the dropdown is `multi`.
+    pub fn selection(&self) -> Vec<&str> {
+        if self.multi {
+            self.selected_options.iter().map(String::as_str).collect()
+        } else if self.selected_option.is_empty() {
+            Vec::new()
+        } else {
+            vec![self.selected_option.as_str()]
+        }
+    }
+
+    /// Returns the rows of the open dropdown list, a header before every run of options of the same group.
+    ///
+    /// Renderers indent the options of a group below its header, and skip headers when moving the
+    /// selection, since only options can be selected.
+    ///
+    /// ```
+    /// use glyph::DropdownRow;
+    ///
+    /// let ui = glyph::from_str(r#"
+    ///     @language ratatui
+    ///     @Form main {
+    ///         @Dropdown fruit {
+    ///             @OptGroup { label = "Citrus" @Option lemon { label = "Lemon" } @Option lime { label = "Lime" } }
+    ///             @Option apple { label = "Apple" }
+    ///         }
+    ///     }
+    /// "#).unwrap();
+    /// let dropdown = ui.root.as_form().unwrap().children[0].as_dropdown().unwrap();
+    /// let rows: Vec<String> = dropdown.rows().iter().map(|row| match row {
+    ///     DropdownRow::Group(label) => format!("{label}:"),
+    ///     DropdownRow::Option(option) => option.label.clone(),
+    /// }).collect();
+    /// assert_eq!(rows, ["Citrus:", "Lemon", "Lime", "Apple"]);
+    /// assert!(!dropdown.rows()[0].is_selectable());
+    /// ```
+    pub fn rows(&self) -> Vec<DropdownRow<'_>> {
+        let mut rows = Vec::with_capacity(self.options.len());
+        let mut group = None;
+        for option in &self.options {
+            if option.group.is_some() && option.group != group {
+                rows.push(DropdownRow::Group(option.group.as_deref().unwrap_or_default()));
+            }
+            group.clone_from(&option.group);
+            rows.push(DropdownRow::Option(option));
+        }
+        rows
+    }
+
+    /// Replaces the options with `options`, named after the dropdown and their position.
+    /// The selection is kept if an option with the selected label is still there, otherwise it is cleared.
+    ///
+    /// ```
+    /// use glyph::{DropdownOptionValue, IntoDropdownOptions};
+    ///
+    /// let mut ui = glyph::from_str(r#"
+    ///     @language ratatui
+    ///     @Form main { @Dropdown size { selected = "Small" @Option small { label = "Small" } } }
+    /// "#).unwrap();
+    /// let glyph::Element::Form(form) = &mut ui.root else { unreachable!() };
+    /// let glyph::Element::Dropdown(dropdown) = &mut form.children[0] else { unreachable!() };
+    ///
+    /// dropdown.set_options(vec![("Small", 1), ("Large", 2)]);
+    /// assert_eq!(dropdown.options.len(), 2);
+    /// assert_eq!(&*dropdown.options[1].name, "size_1");
+    /// assert_eq!(dropdown.options[1].label, "Large");
+    /// assert!(matches!(dropdown.options[1].value, DropdownOptionValue::NumberValue(2)));
+    /// assert_eq!(dropdown.selected_option, "Small");
+    ///
+    /// dropdown.set_options(vec![("Medium".to_string(), DropdownOptionValue::BoolValue(true))]);
+    /// assert_eq!(dropdown.selected_option, "");
+    /// ```
+    pub fn set_options(&mut self, options: impl IntoDropdownOptions) {
+        self.options = options
+            .into_dropdown_options()
+            .into_iter()
+            .enumerate()
+            .map(|(index, (label, value))| DropdownOption {
+                name: Name::from(format!("{}_{index}", self.name).as_str()),
+                id: String::new(),
+                label,
+                value,
+                group: None,
+                doc: None,
+                source: None,
+                extra: Vec::new(),
+            })
+            .collect();
+        let options = &self.options;
+        self.selected_options
+            .retain(|selected| options.iter().any(|option| option.label == *selected));
+        if !self.options.iter().any(|option| option.label == self.selected_option) {
+            self.selected_option.clear();
+        }
+    }
+}
+
+/// Represents a row of an open dropdown list, see [`Dropdown::rows`].
+///
+/// # Variants
+///
+/// - `Group(&str)` - Header of a group of options, which can not be selected.
+/// - `Option(&DropdownOption)` - An option.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DropdownRow<'a> {
+    Group(&'a str),
+    Option(&'a DropdownOption),
+}
+
+impl DropdownRow<'_> {
+    /// Returns whether the row is an option rather than a group header.
+    pub fn is_selectable(&self) -> bool {
+        matches!(self, DropdownRow::Option(_))
+    }
+}
+
+/// Converts application data into the labels and values of dropdown options,
+/// see [`Dropdown::set_options`].
+///
+/// Implemented for every collection of `(label, value)` pairs whose value converts
+/// into a [`DropdownOptionValue`]. Other types implement [`DropdownItem`]
+/// and are converted with [`dropdown_items`].
+pub trait IntoDropdownOptions {
+    fn into_dropdown_options(self) -> Vec<(String, DropdownOptionValue)>;
+}
+
+impl<I, L, V> IntoDropdownOptions for I
+where
+    I: IntoIterator<Item = (L, V)>,
+    L: Into<String>,
+    V: Into<DropdownOptionValue>,
+{
+    fn into_dropdown_options(self) -> Vec<(String, DropdownOptionValue)> {
+        self.into_iter().map(|(label, value)| (label.into(), value.into())).collect()
+    }
+}
+
+/// Describes how a value of an application type is shown as a dropdown option.
+///
+/// ```
+/// use glyph::{Dropdown, DropdownItem, DropdownOptionValue, dropdown_items};
+///
+/// enum Colour { Red, Green }
+///
+/// impl DropdownItem for Colour {
+///     fn label(&self) -> String {
+///         match self {
+///             Colour::Red => "Red".to_string(),
+///             Colour::Green => "Green".to_string(),
+///         }
+///     }
+/// }
+///
+/// let mut dropdown = Dropdown { name: "colour".into(), ..Default::default() };
+/// dropdown.set_options(dropdown_items(vec![Colour::Red, Colour::Green]));
+/// assert_eq!(dropdown.options[1].label, "Green");
+/// assert!(matches!(&dropdown.options[1].value, DropdownOptionValue::StringValue(s) if s == "Green"));
+/// ```
+pub trait DropdownItem {
+    fn label(&self) -> String;
+
+    /// Returns the value of the option, the label by default.
+    fn value(&self) -> DropdownOptionValue {
+        DropdownOptionValue::StringValue(self.label())
+    }
+}
+
+/// Converts items into `(label, value)` pairs for [`Dropdown::set_options`].
+pub fn dropdown_items<T: DropdownItem>(items: impl IntoIterator<Item = T>) -> Vec<(String, DropdownOptionValue)> {
+    items.into_iter().map(|item| (item.label(), item.value())).collect()
 }
 
 /// Represents a grid view.
-#[derive(Debug, Clone)]
+///
+/// The column titles fill a header row unless `show_header` is turned off.
+/// The first `frozen` columns are meant to stay in place while the grid scrolls
+/// horizontally; layout and rendering do not scroll yet and only record the count.
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Grid plain { @Column id { title = "Id" } }
+///         @Grid data {
+///             show-header = false
+///             frozen = 1
+///             @Column id { title = "Id" }
+///             @Column name { title = "Name" }
+///         }
+///     }
+/// "#).unwrap();
+/// let form = ui.root.as_form().unwrap();
+/// let plain = form.children[0].as_grid().unwrap();
+/// assert!(plain.show_header);
+/// assert_eq!(plain.frozen, 0);
+/// let data = form.children[1].as_grid().unwrap();
+/// assert!(!data.show_header);
+/// assert_eq!(data.frozen, 1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
 pub struct Grid {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub columns: Vec<Column>,
     pub size_constraints: SizeConstraints,
     pub margins: Margins,
+    pub show_header: bool,
+    pub frozen: u32,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Grid {
+            name: Name::default(),
+            id: String::new(),
+            role: None,
+            columns: Vec::new(),
+            size_constraints: SizeConstraints::default(),
+            margins: Margins::default(),
+            show_header: true,
+            frozen: 0,
+            events: HashMap::new(),
+            doc: None,
+            source: None,
+            extra: Vec::new(),
+        }
+    }
 }
 
 /// Represents a single column in a grid.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Column {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub title: String,
     pub width: SizeConstraint,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
 }
 
 /// Represents a modal dialog.
-#[derive(Debug, Clone)]
+///
+/// A `center`ed modal (the default) does not take part in its parent's layout,
+/// it is placed in the middle of the parent's area using its size constraints
+/// and drawn over its siblings. With `backdrop`, the area behind it is dimmed.
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Label text { text = "Behind" }
+///         @Modal confirm { title = "Sure?" width = 20 height = 6 backdrop = true }
+///     }
+/// "#).unwrap();
+/// let rects = glyph::layout::arrange(&ui, glyph::layout::Rect::new(0, 0, 42, 22));
+/// assert_eq!(rects["confirm"], glyph::layout::Rect::new(11, 8, 20, 6));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
 pub struct Modal {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub title: String,
     pub children: Vec<Element>,
     pub size_constraints: SizeConstraints,
+    pub center: bool,
+    pub backdrop: bool,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
+}
+
+impl Default for Modal {
+    fn default() -> Self {
+        Modal {
+            name: Name::default(),
+            id: String::new(),
+            role: None,
+            title: String::new(),
+            children: Vec::new(),
+            size_constraints: SizeConstraints::default(),
+            center: true,
+            backdrop: false,
+            events: HashMap::new(),
+            doc: None,
+            source: None,
+            extra: Vec::new(),
+        }
+    }
 }
 
 /// Represents the position of tabs in a tab control.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub enum TabPosition {
+    #[default]
     Top,
     Bottom,
     Left,
@@ -272,38 +1862,363 @@ pub enum TabPosition {
 }
 
 /// Represents a tab control.
-#[derive(Debug, Clone)]
+///
+/// `order` lists tab titles in the order they are shown, written `order = ["Details", "Summary"]`.
+/// Lowering rejects titles that match no tab or are listed twice.
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Tabs pages {
+///             order = [Summary, "Details"]
+///             @Tab advanced { title = "Advanced" }
+///             @Tab details { title = "Details" }
+///             @Tab summary { title = "Summary" }
+///         }
+///     }
+/// "#).unwrap();
+/// let text = glyph::render::render_ascii(&ui, 40, 5);
+/// assert_eq!(text.lines().nth(1).unwrap(), "│ Summary | Details | Advanced         │");
+///
+/// let unknown = glyph::from_str("@language ratatui\n@Form f { @Tabs t { order = [Extra] @Tab a { title = \"A\" } } }");
+/// assert_eq!(
+///     unknown.unwrap_err().to_string(),
+///     "`order` of Tabs `t` lists `Extra`, which is the title of none of its tabs, expected one of: A"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Tabs {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub children: Vec<Tab>,
+    pub order: Vec<String>,
     pub selected_tab: String,
     pub tab_position: TabPosition,
     pub size_constraints: SizeConstraints,
     pub margins: Margins,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
+}
+
+impl Tabs {
+    /// Returns the tabs in the order they are shown.
+    ///
+    /// Tabs listed in the `order` of the control come first, in the listed order.
+    /// Tabs with an `order` of their own follow, sorted by it; the others come last.
+    /// Ties keep the declaration order.
+    pub fn ordered(&self) -> Vec<&Tab> {
+        let mut tabs: Vec<&Tab> = self.children.iter().collect();
+        tabs.sort_by_key(|tab| {
+            let listed = self.order.iter().position(|title| *title == tab.title);
+            (listed.unwrap_or(usize::MAX), tab.order.unwrap_or(u32::MAX))
+        });
+        tabs
+    }
 }
 
 /// Represents a single tab.
-#[derive(Debug, Clone)]
+///
+/// A `closable` tab shows an `x` next to its title.
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Tabs tabs {
+///             @Tab general { title = "General" order = 2 }
+///             @Tab advanced { title = "Advanced" order = 1 closable = true }
+///         }
+///     }
+/// "#).unwrap();
+/// let text = glyph::render::render_ascii(&ui, 40, 5);
+/// assert_eq!(text.lines().nth(1).unwrap(), "│ Advanced x | General                 │");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Tab {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub title: String,
     pub children: Vec<Element>,
+    pub closable: bool,
+    pub order: Option<u32>,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
+}
+
+/// Represents the menu bar of a form.
+///
+/// A form gives its menu bar the top row, whatever its position among the children,
+/// and lays out the other children below it. The top level items are shown side by side,
+/// their submenus are not laid out. A menu bar needs no name, it is called `menu` by default.
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Label hello { text = "Hello" }
+///         @MenuBar {
+///             @MenuItem file {
+///                 text = "File"
+///                 @MenuItem open { text = "Open" }
+///                 @MenuItem quit { text = "Quit" disabled = true }
+///             }
+///             @MenuItem help { text = "Help" }
+///         }
+///     }
+/// "#).unwrap();
+/// let menu_bar = ui.root.as_form().unwrap().children[1].as_menu_bar().unwrap();
+/// assert_eq!(&*menu_bar.name, "menu");
+/// let file = &menu_bar.items[0];
+/// assert_eq!(file.text, "File");
+/// let submenu: Vec<&str> = file.items.iter().map(|item| item.text.as_str()).collect();
+/// assert_eq!(submenu, ["Open", "Quit"]);
+/// assert!(file.items[1].disabled);
+/// assert!(menu_bar.items[1].items.is_empty());
+///
+/// let rects = glyph::layout::arrange(&ui, glyph::layout::Rect::new(0, 0, 30, 5));
+/// assert_eq!(rects["menu"], glyph::layout::Rect::new(1, 1, 28, 1));
+/// assert_eq!(rects["file"], glyph::layout::Rect::new(1, 1, 6, 1));
+/// assert_eq!(rects["help"], glyph::layout::Rect::new(7, 1, 6, 1));
+/// assert_eq!(rects["hello"].y, 2);
+/// assert_eq!(glyph::render::render_ascii(&ui, 30, 5).lines().nth(1).unwrap(), "│ File  Help                 │");
+/// assert!(!rects.contains_key("open"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MenuBar {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
+    pub items: Vec<MenuItem>,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
+}
+
+/// Represents an entry of a menu bar, or of the submenu of another entry.
+///
+/// # Fields
+///
+/// - `name` (`String`) - Name of the entry.
+/// - `id` (`String`) - Path of the entry in the tree, see [`crate::id::assign_ids`].
+/// - `role` (`Option<String>`) - Accessibility role of the entry, from the `role` property.
+/// - `text` (`String`) - Text shown for the entry.
+/// - `items` (`Vec<MenuItem>`) - Entries of the submenu, empty for entries without one.
+/// - `disabled` (`bool`) - Whether the entry can not be chosen.
+/// - `events` (`HashMap<String, String>`) - Handler names by event, from `on-*` properties.
+/// - `doc` (`Option<String>`) - Doc comment written above the entry.
+/// - `source` (`Option<String>`) - The entry as written, see [`crate::lower::LowerOptions::keep_source`].
+/// - `extra` (`Vec<Property>`) - Properties not modelled by the entry, kept for emitting.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MenuItem {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
+    pub text: String,
+    pub items: Vec<MenuItem>,
+    pub disabled: bool,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
+}
+
+/// Represents the status bar of a form.
+///
+/// A form gives its status bar the bottom row, whatever its position among the children and
+/// whatever the layout of the form, and lays out the other children above it. The status bar
+/// holds labels, shown side by side, or a single text field. It needs no name, it is called
+/// `status` by default.
+///
+/// # Fields
+///
+/// - `name` (`String`) - Name of the status bar.
+/// - `id` (`String`) - Path of the status bar in the tree, see [`crate::id::assign_ids`].
+/// - `role` (`Option<String>`) - Accessibility role of the status bar, from the `role` property.
+/// - `children` (`Vec<Element>`) - Labels, or a single text input, lowering accepts nothing else.
+/// - `events` (`HashMap<String, String>`) - Handler names by event, from `on-*` properties.
+/// - `doc` (`Option<String>`) - Doc comment written above the status bar.
+/// - `source` (`Option<String>`) - The status bar as written, see [`crate::lower::LowerOptions::keep_source`].
+/// - `extra` (`Vec<Property>`) - Properties not modelled by the status bar, kept for emitting.
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         layout = left-to-right
+///         @StatusBar {
+///             @Label mode { text = "INSERT" }
+///             @Label position { text = "1:1" }
+///         }
+///         @Panel left { }
+///         @Panel right { }
+///     }
+/// "#).unwrap();
+/// let status_bar = ui.root.as_form().unwrap().children[0].as_status_bar().unwrap();
+/// assert_eq!(&*status_bar.name, "status");
+///
+/// let rects = glyph::layout::arrange(&ui, glyph::layout::Rect::new(0, 0, 30, 6));
+/// // The last row inside the border of the form, the panels share the rows above it.
+/// assert_eq!(rects["status"], glyph::layout::Rect::new(1, 4, 28, 1));
+/// assert_eq!(rects["mode"], glyph::layout::Rect::new(1, 4, 14, 1));
+/// assert_eq!(rects["position"], glyph::layout::Rect::new(15, 4, 14, 1));
+/// assert_eq!(rects["left"], glyph::layout::Rect::new(1, 1, 14, 3));
+/// assert_eq!(rects["right"], glyph::layout::Rect::new(15, 1, 14, 3));
+/// assert_eq!(glyph::render::render_ascii(&ui, 30, 6).lines().nth(4).unwrap(), "│INSERT        1:1           │");
+///
+/// let error = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @StatusBar {
+///             @TextInput command { }
+///             @Label hint { text = "Enter a command" }
+///         }
+///     }
+/// "#).unwrap_err();
+/// assert!(error.to_string().contains("holds labels or a single text input"), "{error}");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatusBar {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
+    pub children: Vec<Element>,
+    pub events: HashMap<String, String>,
+    pub doc: Option<String>,
+    pub source: Option<String>,
+    pub extra: Vec<Property<'static>>,
 }
 
-pub trait CustomUIElement: std::fmt::Debug + Send + Sync {
+pub trait CustomUIElement: core::fmt::Debug + Send + Sync {
     fn size_constraints(&self) -> SizeConstraints;
     fn margins(&self) -> Margins;
     fn render(&self);
     fn clone_box(&self) -> Box<dyn CustomUIElement>;
+
+    /// Returns the element as [`Any`], so [`CustomUIElement::dyn_eq`] can downcast the other element.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns whether `other` is equal to this element, used by `PartialEq` for [`CustomElement`].
+    ///
+    /// By default elements of the same type are equal. Implementations with state of their own
+    /// compare it by downcasting `other.as_any()` to their type:
+    ///
+    /// ```
+    /// use std::any::Any;
+    /// use glyph::{CustomElement, CustomUIElement, Margins, SizeConstraints};
+    ///
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// struct Gauge { percent: u32 }
+    ///
+    /// #[derive(Debug, Clone)]
+    /// struct Spacer;
+    ///
+    /// impl CustomUIElement for Gauge {
+    ///     fn size_constraints(&self) -> SizeConstraints { SizeConstraints::default() }
+    ///     fn margins(&self) -> Margins { Margins::default() }
+    ///     fn render(&self) {}
+    ///     fn clone_box(&self) -> Box<dyn CustomUIElement> { Box::new(self.clone()) }
+    ///     fn as_any(&self) -> &dyn Any { self }
+    ///     fn dyn_eq(&self, other: &dyn CustomUIElement) -> bool {
+    ///         other.as_any().downcast_ref::<Gauge>() == Some(self)
+    ///     }
+    /// }
+    ///
+    /// impl CustomUIElement for Spacer {
+    ///     fn size_constraints(&self) -> SizeConstraints { SizeConstraints::default() }
+    ///     fn margins(&self) -> Margins { Margins::default() }
+    ///     fn render(&self) {}
+    ///     fn clone_box(&self) -> Box<dyn CustomUIElement> { Box::new(self.clone()) }
+    ///     fn as_any(&self) -> &dyn Any { self }
+    /// }
+    ///
+    /// let custom = |implementation: Box<dyn CustomUIElement>| CustomElement { name: "w".into(), id: String::new(), role: None, implementation };
+    /// assert_eq!(custom(Box::new(Gauge { percent: 40 })), custom(Box::new(Gauge { percent: 40 })));
+    /// assert_eq!(custom(Box::new(Spacer)), custom(Box::new(Spacer)));
+    /// assert_ne!(custom(Box::new(Gauge { percent: 40 })), custom(Box::new(Gauge { percent: 60 })));
+    /// assert_ne!(custom(Box::new(Gauge { percent: 40 })), custom(Box::new(Spacer)));
+    /// ```
+    fn dyn_eq(&self, other: &dyn CustomUIElement) -> bool {
+        self.as_any().type_id() == other.as_any().type_id()
+    }
 }
 
 #[derive(Debug)]
 pub struct CustomElement {
+    pub name: Name,
+    pub id: String,
+    pub role: Option<String>,
     pub implementation: Box<dyn CustomUIElement>,
 }
 
 impl Clone for CustomElement {
     fn clone(&self) -> Self {
         CustomElement {
+            name: self.name.clone(),
+            id: self.id.clone(),
+            role: self.role.clone(),
             implementation: self.implementation.clone_box(),
         }
     }
-}
\ No newline at end of file
+}
+
+impl PartialEq for CustomElement {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.id == other.id
+            && self.role == other.role
+            && self.implementation.dyn_eq(&*other.implementation)
+    }
+}
+
+/// Represents an element whose kind is not known to glyph.
+///
+/// Lowering wraps such elements in an [`Element::Custom`] unless
+/// [`crate::lower::LowerOptions::deny_unknown_kinds`] is set.
+///
+/// # Fields
+///
+/// - `source` (`parser::Element`) - The element as written, including its properties and children.
+/// - `size_constraints` (`SizeConstraints`) - Size read from the usual size properties.
+/// - `margins` (`Margins`) - Margins read from the usual margin properties.
+/// - `base_dir` (`Option<PathBuf>`) - Directory of the layout, for use with
+///   [`crate::lower::resolve_path`] on properties such as `src`. Only with the `std` feature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownElement {
+    pub source: crate::parser::Element<'static>,
+    pub size_constraints: SizeConstraints,
+    pub margins: Margins,
+    #[cfg(feature = "std")]
+    pub base_dir: Option<PathBuf>,
+}
+
+impl CustomUIElement for UnknownElement {
+    fn size_constraints(&self) -> SizeConstraints {
+        self.size_constraints.clone()
+    }
+
+    fn margins(&self) -> Margins {
+        self.margins.clone()
+    }
+
+    fn render(&self) {}
+
+    fn clone_box(&self) -> Box<dyn CustomUIElement> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn dyn_eq(&self, other: &dyn CustomUIElement) -> bool {
+        other.as_any().downcast_ref::<UnknownElement>() == Some(self)
+    }
+}
@@ -0,0 +1,1239 @@
+use alloc::collections::BTreeMap;
+use core::fmt;
+use core::ops::ControlFlow;
+
+use crate::compat::*;
+use crate::layout::{self, Rect};
+use crate::lower;
+use crate::parser::{self, Document, Property};
+use crate::types::*;
+use crate::GlyphError;
+
+/// Represents how serious a diagnostic is.
+///
+/// # Variants
+///
+/// - `Warning` - The layout works, but probably not as intended.
+/// - `Error` - The layout is invalid.
+///
+/// Severities are ordered by seriousness, so `Warning < Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Represents a problem found while checking a layout.
+///
+/// # Fields
+///
+/// - `code` (`&'static str`) - Stable identifier of the check, see below.
+/// - `severity` (`Severity`) - How serious the problem is.
+/// - `element` (`String`) - Name of the element the problem was found on.
+/// - `message` (`String`) - Human readable description of the problem.
+///
+/// # Codes
+///
+/// Codes never change meaning once assigned, so tooling can filter on them.
+///
+/// | Code    | Check                                              |
+/// |---------|----------------------------------------------------|
+/// | `GL001` | Property does not belong to the element's kind.    |
+/// | `GL002` | Element name is used more than once.               |
+/// | `GL003` | Margins do not fit into the parent's area.         |
+/// | `GL004` | Property is given more than once.                  |
+/// | `GL005` | Property is given under two different aliases.     |
+/// | `GL006` | Percentage size is larger than 100%.               |
+/// | `GL007` | Selection names no contained radio, option or tab. |
+/// | `GL008` | Number is written as a string.                     |
+/// | `GL009` | Container that needs children has none.            |
+/// | `GL010` | Checkbox group limits can never be met.            |
+/// | `GL011` | Tab is missing from the `order` of its control.    |
+/// | `GL012` | `@alias` is never used.                            |
+/// | `GL013` | `@alias` name is defined more than once.           |
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub code: &'static str,
+    pub severity: Severity,
+    pub element: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{severity}[{}]: {}", self.code, self.message)
+    }
+}
+
+/// Returns the properties an element kind understands,
+/// or `None` for kinds that are not known.
+///
+/// `on-*` event bindings are accepted on every element and are not listed, nor is the
+/// accessibility `role`, which every element but dropdown options and their groups accepts.
+pub fn allowed_properties(kind: &str) -> Option<&'static [&'static str]> {
+    let properties: &'static [&'static str] = match kind {
+        "Form" => &["title", "layout", "focus-order", "children"],
+        "Panel" => &[
+            "title", "title-align", "title-brackets", "layout", "collapsible", "collapsed", "border", "scroll",
+            "children",
+            "width", "height", "left", "top",
+            "margins-left", "margins-right", "margins-top", "margins-bottom",
+        ],
+        "Label" => &[
+            "text", "word-wrap", "align", "markup",
+            "width", "height", "left", "top",
+            "margins-left", "margins-right", "margins-top", "margins-bottom",
+        ],
+        "TextInput" => &[
+            "placeholder", "text", "read-only", "disabled",
+            "width", "height", "left", "top",
+            "margins-left", "margins-right", "margins-top", "margins-bottom",
+        ],
+        "Button" => &[
+            "text", "disabled", "variant",
+            "width", "height", "left", "top",
+            "margins-left", "margins-right", "margins-top", "margins-bottom",
+        ],
+        "Checkbox" => &[
+            "label", "checked", "disabled",
+            "width", "height", "left", "top",
+            "margins-left", "margins-right", "margins-top", "margins-bottom",
+        ],
+        "CheckboxGroup" => &[
+            "min-checked", "max-checked", "layout", "disabled", "children",
+            "width", "height", "left", "top",
+            "margins-left", "margins-right", "margins-top", "margins-bottom",
+        ],
+        "RadioGroup" => &[
+            "selected", "layout", "disabled", "children",
+            "width", "height", "left", "top",
+            "margins-left", "margins-right", "margins-top", "margins-bottom",
+        ],
+        "Radio" => &[
+            "label", "value",
+            "margins-left", "margins-right", "margins-top", "margins-bottom",
+        ],
+        "Dropdown" => &[
+            "multi", "selected", "disabled", "children",
+            "width", "height", "left", "top",
+            "margins-left", "margins-right", "margins-top", "margins-bottom",
+        ],
+        "Option" => &["label", "value"],
+        "OptGroup" => &["label", "children"],
+        "Grid" => &[
+            "show-header", "frozen", "children",
+            "width", "height", "left", "top",
+            "margins-left", "margins-right", "margins-top", "margins-bottom",
+        ],
+        "Column" => &["title", "width"],
+        "Modal" => &["title", "center", "backdrop", "children", "width", "height", "left", "top"],
+        "Tabs" => &[
+            "order", "selected", "tab-position", "children",
+            "width", "height", "left", "top",
+            "margins-left", "margins-right", "margins-top", "margins-bottom",
+        ],
+        "Tab" => &["title", "closable", "order", "children"],
+        "MenuBar" => &["children"],
+        "MenuItem" => &["text", "disabled", "children"],
+        "StatusBar" => &["children"],
+        _ => return None,
+    };
+    Some(properties)
+}
+
+/// Reports every property that does not belong to the kind of the element carrying it.
+///
+/// Such properties are not an error for lowering, which keeps them in `extra`,
+/// so the reported severity is up to the caller. Elements of an `@alias` kind are
+/// checked as the kind the alias stands for, as are those of the other document checks.
+///
+/// ```
+/// use glyph::validate::{Severity, check_properties};
+///
+/// let document = glyph::parser::parse(r#"
+///     @language ratatui
+///     @alias Btn = Button
+///     @Form main {
+///         @Btn ok { text = "OK" word-wrap = true }
+///     }
+/// "#).unwrap();
+/// let diagnostics = check_properties(&document, Severity::Warning);
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].code, "GL001");
+/// assert_eq!(diagnostics[0].message, "property `word-wrap` does not belong to Button `ok`");
+///
+/// let document = glyph::parser::parse(r#"
+///     @language ratatui
+///     @Form main {
+///         @Button ok { text = "OK" word-wrap = true }
+///         @Label hint { text = "Hint" word-wrap = true }
+///     }
+/// "#).unwrap();
+/// let diagnostics = check_properties(&document, Severity::Error);
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!((diagnostics[0].code, diagnostics[0].element.as_str()), ("GL001", "ok"));
+/// ```
+pub fn check_properties(document: &Document, severity: Severity) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    check_element_properties(&lower::alias_resolved_root(document), severity, &mut diagnostics);
+    diagnostics
+}
+
+fn check_element_properties(element: &parser::Element, severity: Severity, diagnostics: &mut Vec<Diagnostic>) {
+    if let Some(allowed) = allowed_properties(&element.kind) {
+        for property in &element.properties {
+            let name = lower::canonical_property_name(&property.name);
+            let role = name == "role" && !matches!(element.kind.as_str(), "Option" | "OptGroup");
+            if name.starts_with("on-") || role || allowed.contains(&name) {
+                continue;
+            }
+            diagnostics.push(Diagnostic {
+                code: "GL001",
+                severity,
+                element: element.name.clone(),
+                message: format!(
+                    "property `{}` does not belong to {} `{}`",
+                    property.name, element.kind, element.name
+                ),
+            });
+        }
+    }
+    for child in element.all_children() {
+        check_element_properties(child, severity, diagnostics);
+    }
+}
+
+/// Reports every size or count written as a string, such as `width = "20"`.
+///
+/// Lowering reads such strings as the number they spell, see [`lower::NUMERIC_PROPERTIES`],
+/// so the reported severity is up to the caller. Strings that are not numbers are still
+/// rejected by lowering.
+///
+/// ```
+/// use glyph::SizeConstraint;
+/// use glyph::validate::{Severity, check_quoted_numbers};
+///
+/// let document = glyph::parser::parse(r#"
+///     @language ratatui
+///     @Form main {
+///         @Button ok { text = "OK" width = "20" }
+///     }
+/// "#).unwrap();
+/// let ui = glyph::lower::lower(&document).unwrap();
+/// let button = ui.root.as_form().unwrap().children[0].as_button().unwrap();
+/// assert!(matches!(button.size_constraints.width, SizeConstraint::Fixed(20)));
+///
+/// let diagnostics = check_quoted_numbers(&document, Severity::Warning);
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].to_string(), "warning[GL008]: property `width` of Button `ok` is the string \"20\", write `width = 20` instead");
+///
+/// assert!(glyph::from_str("@language ratatui\n@Form f { @Button b { width = \"wide\" } }").is_err());
+///
+/// let aliased = glyph::parser::parse("@language ratatui\n@alias Btn = Button\n@Form f { @Btn b { width = \"3\" } }").unwrap();
+/// assert_eq!(check_quoted_numbers(&aliased, Severity::Warning)[0].code, "GL008");
+/// ```
+pub fn check_quoted_numbers(document: &Document, severity: Severity) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    check_element_numbers(&lower::alias_resolved_root(document), severity, &mut diagnostics);
+    diagnostics
+}
+
+fn check_element_numbers(element: &parser::Element, severity: Severity, diagnostics: &mut Vec<Diagnostic>) {
+    let allowed = allowed_properties(&element.kind).unwrap_or_default();
+    for property in &element.properties {
+        let name = lower::canonical_property_name(&property.name);
+        if !allowed.contains(&name) || !lower::NUMERIC_PROPERTIES.contains(&name) {
+            continue;
+        }
+        if let parser::Value::String(text) = &property.value
+            && lower::quoted_number(&property.value).is_some()
+        {
+            diagnostics.push(Diagnostic {
+                code: "GL008",
+                severity,
+                element: element.name.clone(),
+                message: format!(
+                    "property `{}` of {} `{}` is the string \"{text}\", write `{} = {text}` instead",
+                    property.name, element.kind, element.name, property.name
+                ),
+            });
+        }
+    }
+    for child in element.all_children() {
+        check_element_numbers(child, severity, diagnostics);
+    }
+}
+
+/// Reports every property that is given more than once on the same element.
+///
+/// Lowering keeps the last value, so the earlier ones are silently ignored.
+/// Aliases count as the property they stand for: `ml = 1` next to
+/// `margins-left = 2` is reported as conflicting spellings instead.
+///
+/// ```
+/// use glyph::validate::{Severity, check_duplicate_properties};
+///
+/// let document = glyph::parser::parse(r#"
+///     @language ratatui
+///     @Form main {
+///         @Button ok { text = "OK" text = "Okay" }
+///     }
+/// "#).unwrap();
+/// let diagnostics = check_duplicate_properties(&document, Severity::Warning);
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].code, "GL004");
+/// assert_eq!(diagnostics[0].element, "ok");
+/// ```
+pub fn check_duplicate_properties(document: &Document, severity: Severity) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    check_element_duplicates(&lower::alias_resolved_root(document), severity, &mut diagnostics);
+    diagnostics
+}
+
+fn check_element_duplicates(element: &parser::Element, severity: Severity, diagnostics: &mut Vec<Diagnostic>) {
+    let mut reported = HashSet::new();
+    for property in &element.properties {
+        let canonical = lower::canonical_property_name(&property.name);
+        if !reported.insert(canonical) {
+            continue;
+        }
+        let spellings: Vec<&str> = element
+            .properties
+            .iter()
+            .map(|other| other.name.as_str())
+            .filter(|name| lower::canonical_property_name(name) == canonical)
+            .collect();
+        if spellings.iter().all(|name| *name == property.name) {
+            let count = element.get_all(&property.name).count();
+            if count > 1 {
+                diagnostics.push(Diagnostic {
+                    code: "GL004",
+                    severity,
+                    element: element.name.clone(),
+                    message: format!(
+                        "property `{}` is given {count} times on {} `{}`, only the last value is used",
+                        property.name, element.kind, element.name
+                    ),
+                });
+            }
+        } else {
+            let mut distinct: Vec<&str> = Vec::new();
+            for name in spellings {
+                if !distinct.contains(&name) {
+                    distinct.push(name);
+                }
+            }
+            diagnostics.push(Diagnostic {
+                code: "GL005",
+                severity,
+                element: element.name.clone(),
+                message: format!(
+                    "property `{canonical}` is given as `{}` on {} `{}`, only the last value is used",
+                    distinct.join("` and `"),
+                    element.kind,
+                    element.name
+                ),
+            });
+        }
+    }
+    for child in element.all_children() {
+        check_element_duplicates(child, severity, diagnostics);
+    }
+}
+
+/// Reports `@alias` definitions that are never used, as warnings, and names defined by more
+/// than one `@alias` line, as errors.
+///
+/// An alias is used when an element is written with it, or when a used alias stands for it.
+/// Only the elements of this file are looked at, so an alias that nothing but an `@include`d
+/// file uses is reported as unused too.
+///
+/// ```
+/// use glyph::validate::{Severity, check_definitions};
+///
+/// let document = glyph::parser::parse(r#"
+///     @language ratatui
+///     @alias Card = Panel
+///     @alias InfoCard = Card
+///     @alias Hint = Label
+///     @alias Hint = Button
+///     @Form main {
+///         @InfoCard about { }
+///     }
+/// "#).unwrap();
+/// let diagnostics = check_definitions(&document);
+/// let found: Vec<_> = diagnostics.iter().map(|d| (d.code, d.severity, d.element.as_str())).collect();
+/// assert_eq!(found, [
+///     ("GL012", Severity::Warning, "Hint"),
+///     ("GL013", Severity::Error, "Hint"),
+/// ]);
+/// assert_eq!(diagnostics[1].message, "alias `Hint` is defined 2 times, as `Label` and `Button`");
+/// ```
+pub fn check_definitions(document: &Document) -> Vec<Diagnostic> {
+    fn used_kinds<'a>(element: &'a parser::Element, kinds: &mut HashSet<&'a str>) {
+        kinds.insert(&element.kind);
+        for child in element.all_children() {
+            used_kinds(child, kinds);
+        }
+    }
+
+    let mut used = HashSet::new();
+    used_kinds(&document.root, &mut used);
+    // Following the aliases that are used marks the aliases they stand for.
+    let mut pending: Vec<&str> = used.iter().copied().collect();
+    while let Some(kind) = pending.pop() {
+        for alias in document.aliases.iter().filter(|alias| alias.name == kind) {
+            if used.insert(&alias.kind) {
+                pending.push(&alias.kind);
+            }
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+    let mut reported = HashSet::new();
+    for alias in &document.aliases {
+        if !reported.insert(alias.name.as_str()) {
+            continue;
+        }
+        if !used.contains(alias.name.as_str()) {
+            diagnostics.push(Diagnostic {
+                code: "GL012",
+                severity: Severity::Warning,
+                element: alias.name.clone(),
+                message: format!("alias `{}` is never used", alias.name),
+            });
+        }
+        let kinds: Vec<&str> = document
+            .aliases
+            .iter()
+            .filter(|other| other.name == alias.name)
+            .map(|other| other.kind.as_str())
+            .collect();
+        if kinds.len() > 1 {
+            diagnostics.push(Diagnostic {
+                code: "GL013",
+                severity: Severity::Error,
+                element: alias.name.clone(),
+                message: format!(
+                    "alias `{}` is defined {} times, as `{}`",
+                    alias.name,
+                    kinds.len(),
+                    kinds.join("` and `")
+                ),
+            });
+        }
+    }
+    diagnostics
+}
+
+/// Reports every element whose name was already used by another element.
+///
+/// Names identify elements in the layout result, so they must be unique
+/// across the whole UI, including radios, columns and tabs.
+pub fn check_names(ui: &UI) -> Vec<Diagnostic> {
+    let mut seen = HashSet::new();
+    let mut diagnostics = Vec::new();
+    check_element_names(&ui.root, &mut seen, &mut diagnostics);
+    diagnostics
+}
+
+fn check_element_names<'a>(element: &'a Element, seen: &mut HashSet<&'a str>, diagnostics: &mut Vec<Diagnostic>) {
+    check_name(element.kind_name(), element.name(), seen, diagnostics);
+    let children = match element {
+        Element::Form(form) => &form.children,
+        Element::Panel(panel) => &panel.children,
+        Element::Modal(modal) => &modal.children,
+        Element::Tab(tab) => &tab.children,
+        Element::StatusBar(status_bar) => &status_bar.children,
+        Element::CheckboxGroup(group) => &group.children,
+        Element::RadioGroup(group) => {
+            for radio in &group.children {
+                check_name("Radio", &radio.name, seen, diagnostics);
+            }
+            return;
+        }
+        Element::Grid(grid) => {
+            for column in &grid.columns {
+                check_name("Column", &column.name, seen, diagnostics);
+            }
+            return;
+        }
+        Element::Tabs(tabs) => {
+            for tab in &tabs.children {
+                check_name("Tab", &tab.name, seen, diagnostics);
+                for child in &tab.children {
+                    check_element_names(child, seen, diagnostics);
+                }
+            }
+            return;
+        }
+        Element::MenuBar(menu_bar) => {
+            check_menu_item_names(&menu_bar.items, seen, diagnostics);
+            return;
+        }
+        Element::MenuItem(item) => {
+            check_menu_item_names(&item.items, seen, diagnostics);
+            return;
+        }
+        _ => return,
+    };
+    for child in children {
+        check_element_names(child, seen, diagnostics);
+    }
+}
+
+fn check_menu_item_names<'a>(items: &'a [MenuItem], seen: &mut HashSet<&'a str>, diagnostics: &mut Vec<Diagnostic>) {
+    for item in items {
+        check_name("MenuItem", &item.name, seen, diagnostics);
+        check_menu_item_names(&item.items, seen, diagnostics);
+    }
+}
+
+fn check_name<'a>(kind: &str, name: &'a str, seen: &mut HashSet<&'a str>, diagnostics: &mut Vec<Diagnostic>) {
+    if !seen.insert(name) {
+        diagnostics.push(Diagnostic {
+            code: "GL002",
+            severity: Severity::Error,
+            element: name.to_string(),
+            message: format!("duplicate element name `{name}` on {kind}"),
+        });
+    }
+}
+
+/// Reports every element whose margins add up to more than the space its parent offers,
+/// horizontally or vertically, with the number of cells they overflow by.
+///
+/// Layout clamps such margins, so the element ends up with no space at all.
+/// Whether that is an error is up to the caller.
+///
+/// ```
+/// use glyph::layout::Rect;
+/// use glyph::validate::{Severity, check_margins};
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Panel squeezed { margins-left = 8 margins-right = 8 }
+///     }
+/// "#).unwrap();
+/// let diagnostics = check_margins(&ui, Rect::new(0, 0, 12, 6), Severity::Warning);
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].code, "GL003");
+/// assert!(diagnostics[0].message.contains("by 6 cells"));
+/// ```
+pub fn check_margins(ui: &UI, area: Rect, severity: Severity) -> Vec<Diagnostic> {
+    let rects = layout::arrange(ui, area);
+    let mut diagnostics = Vec::new();
+    check_element_margins(&ui.root, &rects, severity, &mut diagnostics);
+    diagnostics
+}
+
+fn check_element_margins(
+    element: &Element,
+    rects: &HashMap<String, Rect>,
+    severity: Severity,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(area) = rects.get(element.name()).copied() else {
+        return;
+    };
+    let (children, available) = match element {
+        Element::Form(form) => (&form.children, area.inner()),
+        Element::Panel(panel) if !panel.collapsed => (&panel.children, layout::panel_content_area(panel, area)),
+        Element::Modal(modal) => (&modal.children, area.inner()),
+        Element::CheckboxGroup(group) => (&group.children, area),
+        Element::StatusBar(status_bar) => (&status_bar.children, area),
+        Element::RadioGroup(group) => {
+            for radio in &group.children {
+                check_fit(&radio.name, &radio.margins, area, severity, diagnostics);
+            }
+            return;
+        }
+        Element::Tabs(tabs) => {
+            let content = layout::tab_content_area(tabs, area);
+            for tab in &tabs.children {
+                for child in &tab.children {
+                    check_fit(child.name(), &layout::constraints(child).1, content, severity, diagnostics);
+                    check_element_margins(child, rects, severity, diagnostics);
+                }
+            }
+            return;
+        }
+        _ => return,
+    };
+    for child in children {
+        check_fit(child.name(), &layout::constraints(child).1, available, severity, diagnostics);
+        check_element_margins(child, rects, severity, diagnostics);
+    }
+}
+
+fn check_fit(name: &str, margins: &Margins, available: Rect, severity: Severity, diagnostics: &mut Vec<Diagnostic>) {
+    let axes = [
+        ("horizontal", margins.left as u64 + margins.right as u64, available.width),
+        ("vertical", margins.top as u64 + margins.bottom as u64, available.height),
+    ];
+    for (axis, total, size) in axes {
+        if total > size as u64 {
+            diagnostics.push(Diagnostic {
+                code: "GL003",
+                severity,
+                element: name.to_string(),
+                message: format!(
+                    "{axis} margins of `{name}` take {total} cells but only {size} are available, overflowing by {} cells",
+                    total - size as u64
+                ),
+            });
+        }
+    }
+}
+
+/// Area the margins are checked against by [`validate_all`], the size of a classic terminal.
+pub const VALIDATION_AREA: Rect = Rect {
+    x: 0,
+    y: 0,
+    width: 80,
+    height: 24,
+};
+
+/// Runs every check on the UI and returns all diagnostics in the order
+/// the elements they were found on appear in the tree.
+///
+/// Margins are checked against [`VALIDATION_AREA`]. Checks that need the parsed source,
+/// [`check_properties`] and [`check_duplicate_properties`], report unknown properties
+/// here from what lowering kept in `extra` and miss repeated ones.
+///
+/// ```
+/// use glyph::validate::validate_all;
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Button ok { text = "OK" colour = "red" }
+///         @RadioGroup size {
+///             selected = "huge"
+///             @Radio small { label = "Small" }
+///         }
+///         @Label ok { text = "Again" }
+///     }
+/// "#).unwrap();
+/// let codes: Vec<&str> = validate_all(&ui).iter().map(|diagnostic| diagnostic.code).collect();
+/// assert_eq!(codes, ["GL002", "GL001", "GL007"]);
+/// ```
+pub fn validate_all(ui: &UI) -> Vec<Diagnostic> {
+    crate::trace::stage("validate", || check_all(ui), |_| crate::trace::ui_shape(ui))
+}
+
+fn check_all(ui: &UI) -> Vec<Diagnostic> {
+    let mut diagnostics = check_names(ui);
+    diagnostics.extend(check_extra_properties(ui));
+    diagnostics.extend(check_percentages(ui));
+    diagnostics.extend(check_selections(ui));
+    diagnostics.extend(check_checked_limits(ui));
+    diagnostics.extend(check_tab_order(ui));
+    diagnostics.extend(check_empty_containers(ui));
+    diagnostics.extend(check_margins(ui, VALIDATION_AREA, Severity::Warning));
+    let mut order = HashMap::new();
+    visit(&ui.root, &mut |node| {
+        let index = order.len();
+        order.entry(node.name()).or_insert(index);
+    });
+    // The sort is stable, so diagnostics on the same element keep the order of the checks.
+    diagnostics.sort_by_key(|diagnostic| order.get(diagnostic.element.as_str()).copied().unwrap_or(usize::MAX));
+    diagnostics
+}
+
+/// Fails with the error diagnostics of [`validate_all`], warnings alone pass.
+///
+/// ```
+/// use glyph::GlyphError;
+///
+/// let ui = glyph::from_str("@language ratatui\n@Form main { @Label a { } }").unwrap();
+/// assert!(glyph::validate::ensure_valid(&ui).is_ok());
+///
+/// let ui = glyph::from_str("@language ratatui\n@Form main { @Label a { } @Button a { } }").unwrap();
+/// let Err(GlyphError::Validate(diagnostics)) = glyph::validate::ensure_valid(&ui) else { panic!() };
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].code, "GL002");
+/// ```
+pub fn ensure_valid(ui: &UI) -> Result<(), GlyphError> {
+    let errors: Vec<Diagnostic> = validate_all(ui)
+        .into_iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .collect();
+    if errors.is_empty() { Ok(()) } else { Err(GlyphError::Validate(errors)) }
+}
+
+/// Counts the nodes of the tree by kind, including radios, options, columns and tabs.
+///
+/// The counts are sorted by kind name, so printing them gives the same output every run,
+/// like the diagnostics of [`validate_all`] that keep the order of the tree.
+///
+/// ```
+/// use glyph::validate::{element_counts, validate_all};
+///
+/// let src = r#"
+///     @language ratatui
+///     @Form main {
+///         @Button ok { text = "OK" on-click = "submit" on-hover = "hint" }
+///         @Label ok { text = "Again" }
+///         @Button cancel { text = "Cancel" colour = "red" }
+///     }
+/// "#;
+/// let ui = glyph::from_str(src).unwrap();
+/// let counts: Vec<(&str, usize)> = element_counts(&ui).into_iter().collect();
+/// assert_eq!(counts, [("Button", 2), ("Form", 1), ("Label", 1)]);
+///
+/// let first = validate_all(&ui);
+/// for _ in 0..10 {
+///     let again = glyph::from_str(src).unwrap();
+///     assert_eq!(validate_all(&again), first);
+///     assert_eq!(element_counts(&again), element_counts(&ui));
+/// }
+/// ```
+pub fn element_counts(ui: &UI) -> BTreeMap<&'static str, usize> {
+    let mut counts = BTreeMap::new();
+    visit(&ui.root, &mut |node| *counts.entry(node.kind()).or_insert(0) += 1);
+    counts
+}
+
+/// Represents how large a layout is, for keeping it within a budget.
+///
+/// Radios, dropdown options, grid columns and tabs count as elements of their own.
+///
+/// # Fields
+///
+/// - `total_elements` (`usize`) - Number of elements in the tree, the root included.
+/// - `max_depth` (`usize`) - Number of levels of the tree, 1 for a root without children.
+/// - `max_siblings` (`usize`) - Largest number of direct children of a single element.
+/// - `widget_counts` (`BTreeMap<&'static str, usize>`) - Elements per kind, see [`element_counts`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Complexity {
+    pub total_elements: usize,
+    pub max_depth: usize,
+    pub max_siblings: usize,
+    pub widget_counts: BTreeMap<&'static str, usize>,
+}
+
+/// Measures how wide and deep the layout is.
+///
+/// ```
+/// use glyph::validate::complexity;
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Panel side {
+///             @Dropdown size { @Option small {} @Option medium {} @Option large {} }
+///         }
+///         @Button ok { text = "OK" }
+///     }
+/// "#).unwrap();
+/// let complexity = complexity(&ui);
+/// assert_eq!(complexity.total_elements, 7);
+/// assert_eq!(complexity.max_depth, 4);
+/// assert_eq!(complexity.max_siblings, 3);
+/// let counts: Vec<(&str, usize)> = complexity.widget_counts.into_iter().collect();
+/// assert_eq!(counts, [("Button", 1), ("Dropdown", 1), ("Form", 1), ("Option", 3), ("Panel", 1)]);
+/// ```
+pub fn complexity(ui: &UI) -> Complexity {
+    let mut complexity = Complexity { widget_counts: element_counts(ui), ..Complexity::default() };
+    visit_at(&ui.root, 0, &mut |node, depth| {
+        let children = match node {
+            Node::Element(Element::Form(form)) => form.children.len(),
+            Node::Element(Element::Panel(panel)) => panel.children.len(),
+            Node::Element(Element::Modal(modal)) => modal.children.len(),
+            Node::Element(Element::Tab(tab)) | Node::Tab(tab) => tab.children.len(),
+            Node::Element(Element::RadioGroup(group)) => group.children.len(),
+            Node::Element(Element::CheckboxGroup(group)) => group.children.len(),
+            Node::Element(Element::StatusBar(status_bar)) => status_bar.children.len(),
+            Node::Element(Element::Dropdown(dropdown)) => dropdown.options.len(),
+            Node::Element(Element::Grid(grid)) => grid.columns.len(),
+            Node::Element(Element::Tabs(tabs)) => tabs.children.len(),
+            Node::Element(Element::MenuBar(menu_bar)) => menu_bar.items.len(),
+            Node::Element(Element::MenuItem(item)) | Node::MenuItem(item) => item.items.len(),
+            _ => 0,
+        };
+        complexity.total_elements += 1;
+        complexity.max_depth = complexity.max_depth.max(depth + 1);
+        complexity.max_siblings = complexity.max_siblings.max(children);
+    });
+    complexity
+}
+
+/// Reports every percentage size larger than 100%.
+///
+/// Layout hands out no more than the parent has, so siblings after
+/// such an element end up without space.
+pub fn check_percentages(ui: &UI) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    visit(&ui.root, &mut |node| {
+        for (property, size) in node.sizes() {
+            // Every size a responsive value can choose is checked, whatever the area.
+            let sizes = match size {
+                SizeConstraint::Responsive { cases, otherwise } => {
+                    cases.into_iter().map(|(_, size)| size).chain([*otherwise]).collect()
+                }
+                size => vec![size],
+            };
+            for size in sizes {
+                if let SizeConstraint::Percentage(percent) = size
+                    && percent > 100
+                {
+                    diagnostics.push(Diagnostic {
+                        code: "GL006",
+                        severity: Severity::Error,
+                        element: node.name().to_string(),
+                        message: format!("`{property}` of `{}` is {percent}%, more than its parent has", node.name()),
+                    });
+                }
+            }
+        }
+    });
+    diagnostics
+}
+
+/// Reports every tab control, radio group, grid and dropdown without tabs, radios,
+/// columns or options, which shows nothing to choose from.
+/// Empty forms and panels are fine, they simply leave their area blank.
+///
+/// ```
+/// use glyph::validate::check_empty_containers;
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Tabs pages { }
+///         @RadioGroup size { }
+///         @Grid table { }
+///         @Dropdown colour { }
+///         @Panel spacer { }
+///         @Tabs settings { @Tab general { title = "General" } }
+///     }
+/// "#).unwrap();
+/// let messages: Vec<String> = check_empty_containers(&ui).iter().map(ToString::to_string).collect();
+/// assert_eq!(messages, [
+///     "warning[GL009]: Tabs `pages` has no tabs",
+///     "warning[GL009]: RadioGroup `size` has no radios",
+///     "warning[GL009]: Grid `table` has no columns",
+///     "warning[GL009]: Dropdown `colour` has no options",
+/// ]);
+/// ```
+pub fn check_empty_containers(ui: &UI) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    visit(&ui.root, &mut |node| {
+        let Node::Element(element) = node else {
+            return;
+        };
+        let missing = match element {
+            Element::Tabs(tabs) if tabs.children.is_empty() => "tabs",
+            Element::RadioGroup(group) if group.children.is_empty() => "radios",
+            Element::CheckboxGroup(group) if group.children.is_empty() => "checkboxes",
+            Element::Grid(grid) if grid.columns.is_empty() => "columns",
+            Element::Dropdown(dropdown) if dropdown.options.is_empty() => "options",
+            _ => return,
+        };
+        diagnostics.push(Diagnostic {
+            code: "GL009",
+            severity: Severity::Warning,
+            element: element.name().to_string(),
+            message: format!("{} `{}` has no {missing}", element.kind_name(), element.name()),
+        });
+    });
+    diagnostics
+}
+
+/// Reports every radio group, dropdown and tab control whose selection
+/// matches none of its radios, options or tabs. An empty selection is fine.
+pub fn check_selections(ui: &UI) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    visit(&ui.root, &mut |node| {
+        let Node::Element(element) = node else {
+            return;
+        };
+        let (selection, valid): (Vec<&str>, Vec<&str>) = match element {
+            Element::RadioGroup(group) => (
+                vec![&group.selected_radio],
+                group.children.iter().map(|radio| radio.value.as_str()).collect(),
+            ),
+            Element::Dropdown(dropdown) => (
+                dropdown.selection(),
+                dropdown.options.iter().map(|option| option.label.as_str()).collect(),
+            ),
+            Element::Tabs(tabs) => (
+                vec![&tabs.selected_tab],
+                tabs.children.iter().map(|tab| tab.title.as_str()).collect(),
+            ),
+            _ => return,
+        };
+        for selected in selection {
+            if selected.is_empty() || valid.contains(&selected) {
+                continue;
+            }
+            diagnostics.push(Diagnostic {
+                code: "GL007",
+                severity: Severity::Error,
+                element: element.name().to_string(),
+                message: format!(
+                    "{} `{}` selects `{selected}`, expected one of: {}",
+                    element.kind_name(),
+                    element.name(),
+                    valid.join(", ")
+                ),
+            });
+        }
+    });
+    diagnostics
+}
+
+/// Reports every checkbox group whose `min-checked` can never be met,
+/// because it is above `max-checked` or above the number of checkboxes.
+///
+/// How many boxes are checked at the moment is left to [`CheckboxGroup::validate_selection`],
+/// a form usually starts out with fewer checked than it asks for.
+///
+/// ```
+/// use glyph::validate::check_checked_limits;
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @CheckboxGroup ok { min-checked = 1 max-checked = 2 @Checkbox a { } @Checkbox b { } }
+///         @CheckboxGroup crossed { min-checked = 2 max-checked = 1 @Checkbox c { } @Checkbox d { } }
+///         @CheckboxGroup short { min-checked = 3 @Checkbox e { } }
+///     }
+/// "#).unwrap();
+/// let messages: Vec<String> = check_checked_limits(&ui).iter().map(|diagnostic| diagnostic.message.clone()).collect();
+/// assert_eq!(messages, [
+///     "CheckboxGroup `crossed` needs at least 2 checked but allows at most 1",
+///     "CheckboxGroup `short` needs at least 3 checked but has 1 checkbox",
+/// ]);
+/// ```
+pub fn check_checked_limits(ui: &UI) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    visit(&ui.root, &mut |node| {
+        let Node::Element(Element::CheckboxGroup(group)) = node else {
+            return;
+        };
+        let Some(min) = group.min_checked else {
+            return;
+        };
+        let count = group.checkboxes().count();
+        let problem = match group.max_checked {
+            Some(max) if min > max => format!("allows at most {max}"),
+            _ if min as usize > count => format!("has {count} checkbox{}", if count == 1 { "" } else { "es" }),
+            _ => return,
+        };
+        diagnostics.push(Diagnostic {
+            code: "GL010",
+            severity: Severity::Error,
+            element: group.name.to_string(),
+            message: format!("CheckboxGroup `{}` needs at least {min} checked but {problem}", group.name),
+        });
+    });
+    diagnostics
+}
+
+/// Reports every tab left out of the `order` of a tab control that has one.
+/// Such tabs are shown after the listed ones, see [`Tabs::ordered`].
+///
+/// ```
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         @Tabs pages {
+///             order = ["Summary"]
+///             @Tab details { title = "Details" }
+///             @Tab summary { title = "Summary" }
+///         }
+///     }
+/// "#).unwrap();
+/// let diagnostics = glyph::validate::check_tab_order(&ui);
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].message, "Tab `details` is missing from the `order` of Tabs `pages`, it is shown last");
+/// ```
+pub fn check_tab_order(ui: &UI) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    visit(&ui.root, &mut |node| {
+        let Node::Element(Element::Tabs(tabs)) = node else {
+            return;
+        };
+        if tabs.order.is_empty() {
+            return;
+        }
+        for tab in tabs.children.iter().filter(|tab| !tabs.order.contains(&tab.title)) {
+            diagnostics.push(Diagnostic {
+                code: "GL011",
+                severity: Severity::Warning,
+                element: tab.name.to_string(),
+                message: format!(
+                    "Tab `{}` is missing from the `order` of Tabs `{}`, it is shown last",
+                    tab.name, tabs.name
+                ),
+            });
+        }
+    });
+    diagnostics
+}
+
+/// Represents an `@assert` check that does not hold, see [`check_assertions`].
+///
+/// # Fields
+///
+/// - `element` (`String`) - Name of the element the assertion is about.
+/// - `check` (`&'static str`) - The failed check, such as `min-width`.
+/// - `expected` (`u32`) - The bound given in the assertion.
+/// - `actual` (`Option<u32>`) - The size the element was laid out with, `None` when it was not laid out at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionFailure {
+    pub element: String,
+    pub check: &'static str,
+    pub expected: u32,
+    pub actual: Option<u32>,
+}
+
+impl fmt::Display for AssertionFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let assertion = format!("@assert element={:?} {}={}", self.element, self.check, self.expected);
+        match self.actual {
+            Some(actual) => write!(f, "`{assertion}` failed, `{}` is {actual} cells", self.element),
+            None => write!(f, "`{assertion}` failed, `{}` is not laid out", self.element),
+        }
+    }
+}
+
+/// Lays the UI out in `area` and checks every `@assert` directive against it.
+///
+/// Returns every failed check, in the order the assertions are written.
+///
+/// ```
+/// use glyph::layout::Rect;
+/// use glyph::validate::check_assertions;
+///
+/// let ui = glyph::from_str(r#"
+///     @language ratatui
+///     @Form main {
+///         layout = left-to-right
+///         @Panel sidebar { width = 25% }
+///         @Panel content { }
+///     }
+///     @assert element="sidebar" min-width=20 max-width=30
+/// "#).unwrap();
+/// assert!(check_assertions(&ui, Rect::new(0, 0, 100, 20)).is_ok());
+///
+/// let failures = check_assertions(&ui, Rect::new(0, 0, 40, 20)).unwrap_err();
+/// assert_eq!(failures.len(), 1);
+/// assert_eq!(failures[0].check, "min-width");
+/// assert_eq!(failures[0].actual, Some(9));
+/// assert_eq!(
+///     failures[0].to_string(),
+///     "`@assert element=\"sidebar\" min-width=20` failed, `sidebar` is 9 cells"
+/// );
+/// ```
+pub fn check_assertions(ui: &UI, area: Rect) -> Result<(), Vec<AssertionFailure>> {
+    let rects = layout::arrange(ui, area);
+    let mut failures = Vec::new();
+    for assertion in &ui.assertions {
+        let rect = rects.get(assertion.element.as_str());
+        let checks = [
+            ("min-width", assertion.min_width, rect.map(|rect| rect.width), true),
+            ("max-width", assertion.max_width, rect.map(|rect| rect.width), false),
+            ("min-height", assertion.min_height, rect.map(|rect| rect.height), true),
+            ("max-height", assertion.max_height, rect.map(|rect| rect.height), false),
+        ];
+        for (check, bound, actual, minimum) in checks {
+            let Some(expected) = bound else {
+                continue;
+            };
+            let holds = match actual {
+                Some(actual) if minimum => actual >= expected,
+                Some(actual) => actual <= expected,
+                None => false,
+            };
+            if !holds {
+                failures.push(AssertionFailure {
+                    element: assertion.element.clone(),
+                    check,
+                    expected,
+                    actual,
+                });
+            }
+        }
+    }
+    if failures.is_empty() { Ok(()) } else { Err(failures) }
+}
+
+/// Reports the unknown properties lowering kept in `extra`, see [`check_properties`].
+fn check_extra_properties(ui: &UI) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    visit(&ui.root, &mut |node| {
+        let allowed = allowed_properties(node.kind()).unwrap_or_default();
+        for property in node.extra() {
+            let name = lower::canonical_property_name(&property.name);
+            if name.starts_with("on-") || allowed.contains(&name) {
+                continue;
+            }
+            diagnostics.push(Diagnostic {
+                code: "GL001",
+                severity: Severity::Warning,
+                element: node.name().to_string(),
+                message: format!(
+                    "property `{}` does not belong to {} `{}`",
+                    property.name,
+                    node.kind(),
+                    node.name()
+                ),
+            });
+        }
+    });
+    diagnostics
+}
+
+/// Represents anything in the typed tree that has a name,
+/// including the radios, options, columns, tabs and menu items that are not [`Element`]s.
+#[derive(Clone, Copy)]
+pub(crate) enum Node<'a> {
+    Element(&'a Element),
+    Radio(&'a Radio),
+    Option(&'a DropdownOption),
+    Column(&'a Column),
+    Tab(&'a Tab),
+    MenuItem(&'a MenuItem),
+}
+
+impl<'a> Node<'a> {
+    pub(crate) fn name(self) -> &'a str {
+        match self {
+            Node::Element(element) => element.name(),
+            Node::Radio(radio) => &radio.name,
+            Node::Option(option) => &option.name,
+            Node::Column(column) => &column.name,
+            Node::Tab(tab) => &tab.name,
+            Node::MenuItem(item) => &item.name,
+        }
+    }
+
+    pub(crate) fn kind(self) -> &'static str {
+        match self {
+            Node::Element(element) => element.kind_name(),
+            Node::Radio(_) => "Radio",
+            Node::Option(_) => "Option",
+            Node::Column(_) => "Column",
+            Node::Tab(_) => "Tab",
+            Node::MenuItem(_) => "MenuItem",
+        }
+    }
+
+    fn extra(self) -> &'a [Property<'static>] {
+        match self {
+            Node::Element(element) => match element {
+                Element::Form(form) => &form.extra,
+                Element::Panel(panel) => &panel.extra,
+                Element::Label(label) => &label.extra,
+                Element::TextInput(input) => &input.extra,
+                Element::Button(button) => &button.extra,
+                Element::Checkbox(checkbox) => &checkbox.extra,
+                Element::CheckboxGroup(group) => &group.extra,
+                Element::RadioGroup(group) => &group.extra,
+                Element::Radio(radio) => &radio.extra,
+                Element::Dropdown(dropdown) => &dropdown.extra,
+                Element::Grid(grid) => &grid.extra,
+                Element::Column(column) => &column.extra,
+                Element::Modal(modal) => &modal.extra,
+                Element::Tabs(tabs) => &tabs.extra,
+                Element::Tab(tab) => &tab.extra,
+                Element::MenuBar(menu_bar) => &menu_bar.extra,
+                Element::MenuItem(item) => &item.extra,
+                Element::StatusBar(status_bar) => &status_bar.extra,
+                // Custom elements keep their source instead.
+                Element::Custom(_) => &[],
+            },
+            Node::Radio(radio) => &radio.extra,
+            Node::Option(option) => &option.extra,
+            Node::Column(column) => &column.extra,
+            Node::Tab(tab) => &tab.extra,
+            Node::MenuItem(item) => &item.extra,
+        }
+    }
+
+    pub(crate) fn sizes(self) -> Vec<(&'static str, SizeConstraint)> {
+        let size = match self {
+            Node::Element(element) => layout::constraints(element).0,
+            Node::Column(column) => SizeConstraints {
+                width: column.width.clone(),
+                ..SizeConstraints::default()
+            },
+            _ => return Vec::new(),
+        };
+        vec![("width", size.width), ("height", size.height), ("left", size.left), ("top", size.top)]
+    }
+}
+
+/// Calls `f` for every node of the tree, parents before their children.
+pub(crate) fn visit<'a>(element: &'a Element, f: &mut impl FnMut(Node<'a>)) {
+    visit_at(element, 0, &mut |node, _| f(node));
+}
+
+/// Visits the nodes like [`visit`], also passing how far below `element` each one is.
+pub(crate) fn visit_at<'a>(element: &'a Element, depth: usize, f: &mut impl FnMut(Node<'a>, usize)) {
+    let _: ControlFlow<()> = try_visit_at(element, depth, &mut |node, depth| {
+        f(node, depth);
+        ControlFlow::Continue(())
+    });
+}
+
+/// Visits the nodes like [`visit`] until `f` breaks, and returns the break.
+pub(crate) fn try_visit<'a, B>(element: &'a Element, f: &mut impl FnMut(Node<'a>) -> ControlFlow<B>) -> ControlFlow<B> {
+    try_visit_at(element, 0, &mut |node, _| f(node))
+}
+
+fn try_visit_at<'a, B>(
+    element: &'a Element,
+    depth: usize,
+    f: &mut impl FnMut(Node<'a>, usize) -> ControlFlow<B>,
+) -> ControlFlow<B> {
+    f(Node::Element(element), depth)?;
+    let children = match element {
+        Element::Form(form) => &form.children,
+        Element::Panel(panel) => &panel.children,
+        Element::Modal(modal) => &modal.children,
+        Element::Tab(tab) => &tab.children,
+        Element::StatusBar(status_bar) => &status_bar.children,
+        Element::CheckboxGroup(group) => &group.children,
+        Element::RadioGroup(group) => {
+            return group.children.iter().try_for_each(|radio| f(Node::Radio(radio), depth + 1));
+        }
+        Element::Dropdown(dropdown) => {
+            return dropdown.options.iter().try_for_each(|option| f(Node::Option(option), depth + 1));
+        }
+        Element::Grid(grid) => return grid.columns.iter().try_for_each(|column| f(Node::Column(column), depth + 1)),
+        Element::Tabs(tabs) => {
+            for tab in &tabs.children {
+                f(Node::Tab(tab), depth + 1)?;
+                for child in &tab.children {
+                    try_visit_at(child, depth + 2, f)?;
+                }
+            }
+            return ControlFlow::Continue(());
+        }
+        Element::MenuBar(menu_bar) => return visit_menu_items(&menu_bar.items, depth + 1, f),
+        Element::MenuItem(item) => return visit_menu_items(&item.items, depth + 1, f),
+        _ => return ControlFlow::Continue(()),
+    };
+    children.iter().try_for_each(|child| try_visit_at(child, depth + 1, f))
+}
+
+fn visit_menu_items<'a, B>(
+    items: &'a [MenuItem],
+    depth: usize,
+    f: &mut impl FnMut(Node<'a>, usize) -> ControlFlow<B>,
+) -> ControlFlow<B> {
+    for item in items {
+        f(Node::MenuItem(item), depth)?;
+        visit_menu_items(&item.items, depth + 1, f)?;
+    }
+    ControlFlow::Continue(())
+}
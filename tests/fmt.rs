@@ -0,0 +1,73 @@
+//! Runs `glyph fmt` on files in a temporary directory.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const MESSY: &str = "@language ratatui\n@Form main {   title=\"Login\"\n      @Button ok ( text = \"OK\" )\n}";
+const FORMATTED: &str = "\
+@language ratatui
+@Form main {
+    title = \"Login\"
+    @Button ok {
+        text = \"OK\"
+    }
+}
+";
+
+fn glyph(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_glyph")).args(args).output().unwrap()
+}
+
+/// Writes `src` to a file only this test uses.
+fn layout_file(test: &str, src: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("glyph-fmt-{}-{test}.gl", std::process::id()));
+    std::fs::write(&path, src).unwrap();
+    path
+}
+
+#[test]
+fn formats_in_place() {
+    let path = layout_file("in-place", MESSY);
+    let output = glyph(&["fmt", path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), FORMATTED);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn check_reports_without_writing() {
+    let path = layout_file("check", MESSY);
+    let output = glyph(&["fmt", "--check", path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), FORMATTED);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), MESSY);
+
+    std::fs::write(&path, FORMATTED).unwrap();
+    let output = glyph(&["fmt", "--check", path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn broken_source_exits_with_2() {
+    let path = layout_file("broken", "@language ratatui\n@Form main { title \"x\" }");
+    for args in [&["fmt", path.to_str().unwrap()][..], &["fmt", "--check", path.to_str().unwrap()]] {
+        let output = glyph(args);
+        assert_eq!(output.status.code(), Some(2));
+        assert!(String::from_utf8(output.stderr).unwrap().starts_with(path.to_str().unwrap()));
+    }
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "@language ratatui\n@Form main { title \"x\" }");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn rejects_bad_arguments() {
+    for args in [&[][..], &["fmt"], &["fmt", "a.gl", "b.gl"], &["lint", "a.gl"]] {
+        let output = glyph(args);
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        assert!(String::from_utf8(output.stderr).unwrap().contains("usage: glyph fmt [--check] <file>"), "{args:?}");
+    }
+    let output = glyph(&["fmt", "missing-file.gl"]);
+    assert_eq!(output.status.code(), Some(2));
+}